@@ -1,14 +1,88 @@
 use std::ffi::OsString;
 use std::io;
 use std::str::FromStr;
+use std::time::Duration;
 
+use clap::ArgMatches;
+use env_logger::Builder;
 use log::LevelFilter;
 
 use crate::cli::build_cli;
 use crate::cmd::{
-    execute_completion, execute_diagram_generate, execute_library_generate, execute_library_schema,
-    execute_workspace_init, execute_workspace_install,
+    execute_completion, execute_diagram_generate, execute_diagram_render, execute_doctor,
+    execute_library_add_item, execute_library_formats, execute_library_generate,
+    execute_library_list_urns, execute_library_schema, execute_manpage, execute_workspace_init,
+    execute_workspace_install, execute_workspace_list,
 };
+use crate::constants::LOG_TARGET_RESULT;
+use crate::network::configure_network;
+use crate::plantuml::{is_timeout, RenderError};
+
+/// The exit code of the failures without a specific class.
+pub const EXIT_FAILURE: i32 = 1;
+/// The exit code of the invalid command lines and the unparsable manifests.
+pub const EXIT_USAGE: i32 = 2;
+/// The exit code of the failures to read or write the file system.
+pub const EXIT_IO: i32 = 3;
+/// The exit code of the failures of PlantUML, e.g. a syntax error or a timeout.
+pub const EXIT_RENDER: i32 = 4;
+/// The exit code of the failures to download, e.g. the PlantUML jar.
+pub const EXIT_NETWORK: i32 = 5;
+
+/// The exit code of a failure, from the first cause having a known class.
+fn get_exit_code(error: &anyhow::Error) -> i32 {
+    if is_timeout(error) {
+        return EXIT_RENDER;
+    }
+    for cause in error.chain() {
+        if cause.is::<RenderError>() {
+            return EXIT_RENDER;
+        }
+        if cause.is::<reqwest::Error>() {
+            return EXIT_NETWORK;
+        }
+        if cause.is::<serde_yaml_ok::Error>() || cause.is::<serde_json::Error>() {
+            return EXIT_USAGE;
+        }
+        if cause.is::<io::Error>() {
+            return EXIT_IO;
+        }
+    }
+    EXIT_FAILURE
+}
+
+/// The exit code of a command, the failure is logged.
+fn to_exit_code(result: anyhow::Result<()>) -> i32 {
+    match result {
+        Ok(_) => 0,
+        Err(e) => {
+            log::error!("the command failed: {:#}", e);
+            get_exit_code(&e)
+        }
+    }
+}
+
+fn create_logger_builder(app_matches: &ArgMatches) -> Result<Builder, String> {
+    let level_filter = if app_matches.get_flag("quiet") {
+        LevelFilter::Warn
+    } else if app_matches.get_flag("verbose") {
+        LevelFilter::Debug
+    } else {
+        match app_matches.get_one::<String>("log_level") {
+            None => LevelFilter::Info,
+            Some(v) => LevelFilter::from_str(v)
+                .map_err(|_| format!("unable to parse the log level: {}", v))?,
+        }
+    };
+
+    let mut builder = env_logger::builder();
+    builder.filter_level(level_filter).is_test(false);
+    if app_matches.get_flag("quiet") {
+        // the final result is still expected when the logs are quiet
+        builder.filter_module(LOG_TARGET_RESULT, LevelFilter::Info);
+    }
+    Ok(builder)
+}
 
 pub fn start_app<I, T>(args: I) -> i32
 where
@@ -21,104 +95,174 @@ where
         Ok(app_matches) => app_matches,
         Err(e) => {
             eprintln!("{}", e);
-            return if e.use_stderr() { 1 } else { 0 };
+            return if e.use_stderr() { EXIT_USAGE } else { 0 };
         }
     };
-    let level_filter = match app_matches.get_one::<String>("log_level") {
-        None => LevelFilter::Info,
-        Some(v) => match LevelFilter::from_str(v) {
-            Ok(v) => v,
-            Err(_) => {
-                eprintln!("unable to parse the log level: {}", v);
-                return 2;
-            }
-        },
+    let mut logger_builder = match create_logger_builder(&app_matches) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("{}", e);
+            return EXIT_USAGE;
+        }
     };
 
-    if let Err(e) = env_logger::builder()
-        .filter_level(level_filter)
-        .is_test(false)
-        .try_init()
-    {
+    if let Err(e) = logger_builder.try_init() {
         eprintln!("unable to configure the logger: {}", e);
     }
 
+    let proxy = app_matches.get_one::<String>("proxy");
+    let download_timeout = app_matches.get_one::<u64>("download_timeout");
+    if proxy.is_some() || download_timeout.is_some() {
+        let timeout = download_timeout.map(|v| Duration::from_secs(*v));
+        if let Err(e) = configure_network(proxy.map(|v| v.as_str()), timeout) {
+            log::error!("{:#}", e);
+            return EXIT_USAGE;
+        }
+    }
+
     match app_matches.subcommand() {
         Some(("library", m)) => match m.subcommand() {
-            Some(("generate", m)) => match execute_library_generate(m) {
-                Ok(_) => 0,
-                Err(e) => {
-                    log::error!("the command failed: {}", e);
-                    2
-                }
-            },
-            Some(("schema", m)) => match execute_library_schema(m) {
-                Ok(_) => 0,
-                Err(e) => {
-                    log::error!("the command failed: {}", e);
-                    2
-                }
-            },
+            Some(("generate", m)) => to_exit_code(execute_library_generate(m)),
+            Some(("schema", m)) => to_exit_code(execute_library_schema(m)),
+            Some(("list-urns", m)) => to_exit_code(execute_library_list_urns(m)),
+            Some(("formats", m)) => to_exit_code(execute_library_formats(m)),
+            Some(("add-item", m)) => to_exit_code(execute_library_add_item(m)),
             _ => {
                 log::warn!("the SUBCOMMAND is missing");
                 app.write_help(&mut io::stderr())
                     .expect("unable to write help message");
                 eprintln!();
-                2
+                EXIT_USAGE
             }
         },
         Some(("workspace", m)) => match m.subcommand() {
-            Some(("init", m)) => match execute_workspace_init(m) {
-                Ok(_) => 0,
-                Err(e) => {
-                    log::error!("the command failed: {}", e);
-                    2
-                }
-            },
-            Some(("install", m)) => match execute_workspace_install(m) {
-                Ok(_) => 0,
-                Err(e) => {
-                    log::error!("the command failed: {}", e);
-                    2
-                }
-            },
+            Some(("init", m)) => to_exit_code(execute_workspace_init(m)),
+            Some(("install", m)) => to_exit_code(execute_workspace_install(m)),
+            Some(("list", m)) => to_exit_code(execute_workspace_list(m)),
             _ => {
                 log::warn!("the SUBCOMMAND is missing");
                 app.write_help(&mut io::stderr())
                     .expect("unable to write help message");
                 eprintln!();
-                2
+                EXIT_USAGE
             }
         },
         Some(("diagram", m)) => match m.subcommand() {
-            Some(("generate", m)) => match execute_diagram_generate(m) {
-                Ok(_) => 0,
-                Err(e) => {
-                    log::error!("the command failed: {}", e);
-                    2
-                }
-            },
+            Some(("generate", m)) => to_exit_code(execute_diagram_generate(m)),
+            Some(("render", m)) => to_exit_code(execute_diagram_render(m)),
             _ => {
                 log::warn!("the SUBCOMMAND is missing");
                 app.write_help(&mut io::stderr())
                     .expect("unable to write help message");
                 eprintln!();
-                2
-            }
-        },
-        Some(("completion", m)) => match execute_completion(m) {
-            Ok(_) => 0,
-            Err(e) => {
-                log::error!("the command failed: {}", e);
-                2
+                EXIT_USAGE
             }
         },
+        Some(("doctor", m)) => to_exit_code(execute_doctor(m)),
+        Some(("completion", m)) => to_exit_code(execute_completion(m)),
+        Some(("manpage", m)) => to_exit_code(execute_manpage(m)),
         _ => {
             log::warn!("the SUBCOMMAND is missing");
             app.write_help(&mut io::stderr())
                 .expect("unable to write help message");
             eprintln!();
-            2
+            EXIT_USAGE
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use log::{Level, Log, Metadata};
+
+    use super::*;
+
+    fn is_enabled(args: &[&str], level: Level, target: &str) -> bool {
+        let app_matches = build_cli().get_matches_from(args);
+        create_logger_builder(&app_matches)
+            .unwrap()
+            .build()
+            .enabled(&Metadata::builder().level(level).target(target).build())
+    }
+
+    #[test]
+    fn test_quiet() {
+        let args = ["plantuml-generator", "--quiet", "library", "schema"];
+        assert!(!is_enabled(&args, Level::Info, "plantuml_generator::cmd"));
+        assert!(!is_enabled(&args, Level::Debug, "plantuml_generator::cmd"));
+        assert!(is_enabled(&args, Level::Warn, "plantuml_generator::cmd"));
+        assert!(is_enabled(&args, Level::Info, LOG_TARGET_RESULT));
+    }
+
+    #[test]
+    fn test_verbose() {
+        let args = ["plantuml-generator", "--verbose", "library", "schema"];
+        assert!(is_enabled(&args, Level::Debug, "plantuml_generator::cmd"));
+        assert!(!is_enabled(&args, Level::Trace, "plantuml_generator::cmd"));
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        let directory = std::path::Path::new("target/tests/app/exit_codes");
+        crate::utils::delete_file_or_directory(directory).unwrap();
+        crate::utils::create_directory(directory).unwrap();
+        let manifest_path = directory.join("library.yaml");
+        std::fs::write(&manifest_path, "name: [library").unwrap();
+        // the commands are executed directly, i.e. without configuring the global logger
+        let generate = |manifest: &str| {
+            let app_matches = build_cli().get_matches_from([
+                "plantuml-generator",
+                "-l=Off",
+                "library",
+                "generate",
+                manifest,
+                "-O=target/tests/app/exit_codes/distribution",
+                "-C=target/tests/app/exit_codes/cache",
+                "-P=test/plantuml-1.2022.4.jar",
+            ]);
+            to_exit_code(execute_library_generate(
+                app_matches
+                    .subcommand_matches("library")
+                    .unwrap()
+                    .subcommand_matches("generate")
+                    .unwrap(),
+            ))
+        };
+        assert_eq!(generate(manifest_path.to_str().unwrap()), EXIT_USAGE);
+        assert_eq!(
+            generate("target/tests/app/exit_codes/missing.yaml"),
+            EXIT_IO
+        );
+        let args = ["plantuml-generator", "library", "generate", "--unknown"];
+        assert!(build_cli()
+            .try_get_matches_from(args)
+            .unwrap_err()
+            .use_stderr());
+        assert!(configure_network(Some(":"), None).is_err());
+    }
+
+    #[test]
+    fn test_exit_code_classes() {
+        let render_error = anyhow::Error::new(RenderError {
+            subject: "a.puml".to_string(),
+        })
+        .context("unable to render the snippet");
+        assert_eq!(get_exit_code(&render_error), EXIT_RENDER);
+        let io_error = anyhow::Error::new(io::Error::from(io::ErrorKind::NotFound));
+        assert_eq!(get_exit_code(&io_error), EXIT_IO);
+        let timeout_error = anyhow::Error::new(io::Error::from(io::ErrorKind::TimedOut));
+        assert_eq!(get_exit_code(&timeout_error), EXIT_RENDER);
+        assert_eq!(
+            get_exit_code(&anyhow::Error::msg("the URNs select no package")),
+            EXIT_FAILURE
+        );
+    }
+
+    #[test]
+    fn test_exclusive_flags() {
+        let args = ["plantuml-generator", "-q", "-l=Debug", "library", "schema"];
+        assert!(build_cli().try_get_matches_from(args).is_err());
+        let args = ["plantuml-generator", "-q", "--verbose", "library", "schema"];
+        assert!(build_cli().try_get_matches_from(args).is_err());
+    }
+}
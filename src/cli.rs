@@ -1,7 +1,8 @@
-use crate::constants::SOURCE_PATTERNS;
-use clap::builder::{PossibleValuesParser, ValueParser};
+use crate::constants::{CACHE_LOCK_TIMEOUT, RENDER_FORMAT, SOURCE_PATTERNS};
+use clap::builder::{PossibleValue, PossibleValuesParser, ValueParser};
 use clap::{
     crate_authors, crate_description, crate_version, value_parser, Arg, ArgAction, Command,
+    ValueEnum,
 };
 use clap_complete::Shell;
 
@@ -32,6 +33,19 @@ pub fn build_cli() -> Command {
         .env("PLANTUML_GENERATOR_OUTPUT_CACHE")
         .help("The cache directory.");
 
+    let arg_skip_lock: Arg = Arg::new("do_skip_lock")
+        .long("no-lock")
+        .action(ArgAction::SetTrue)
+        .help("Do not lock the cache directory during the generation.");
+
+    let arg_lock_timeout: Arg = Arg::new("lock_timeout")
+        .long("lock-timeout")
+        .action(ArgAction::Set)
+        .num_args(1)
+        .default_value(CACHE_LOCK_TIMEOUT)
+        .value_parser(value_parser!(u64))
+        .help("The seconds to wait for the lock of the cache directory.");
+
     let arg_plantuml_version: Arg = Arg::new("plantuml_version")
         .conflicts_with("plantuml_jar")
         .short('V')
@@ -50,6 +64,27 @@ pub fn build_cli() -> Command {
         .env("PLANTUML_GENERATOR_PLANTUML_JAR")
         .help("The PlantUML version.");
 
+    let arg_plantuml_retries: Arg = Arg::new("plantuml_retries")
+        .long("plantuml-retries")
+        .action(ArgAction::Set)
+        .num_args(1)
+        .value_parser(value_parser!(u32))
+        .env("PLANTUML_GENERATOR_PLANTUML_RETRIES")
+        .help("The number of retries when PlantUML is killed or cannot be started, by default 2.");
+
+    let arg_plantuml_layout: Arg = Arg::new("plantuml_layout")
+        .long("layout")
+        .action(ArgAction::Set)
+        .num_args(1)
+        .value_parser(PossibleValuesParser::new(["dot", "smetana", "elk"]))
+        .env("PLANTUML_GENERATOR_LAYOUT")
+        .help("The layout engine of PlantUML, by default the one selected by PlantUML.");
+
+    let arg_prune_old_jars: Arg = Arg::new("do_prune_old_jars")
+        .long("prune-old-jars")
+        .action(ArgAction::SetTrue)
+        .help("Delete the PlantUML jars of the other versions from the cache directory.");
+
     let arg_java_binary: Arg = Arg::new("java_binary")
         .short('J')
         .long("java")
@@ -97,7 +132,7 @@ pub fn build_cli() -> Command {
                     .help("The output directory.")
                 )
                 .arg(Arg::new("urns")
-                    .help("Handle only artifacts included in the URN.")
+                    .help("Handle only artifacts included in the URN or the alias.")
                     .short('u')
                     .long("urn")
                     .action(ArgAction::Set)
@@ -105,10 +140,220 @@ pub fn build_cli() -> Command {
                     .action(ArgAction::Append)
                     .value_parser(ValueParser::string())
                 )
+                .arg(Arg::new("excluded_urns")
+                    .help("Skip artifacts included in the URN.")
+                    .long("exclude-urn")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .action(ArgAction::Append)
+                    .value_parser(ValueParser::string())
+                )
+                .arg(Arg::new("documentation_format")
+                    .long("output-format")
+                    .env("PLANTUML_GENERATOR_OUTPUT_FORMAT")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .value_parser(PossibleValuesParser::new(["markdown", "asciidoc"]))
+                    .help("The format of the generated documentation, by default markdown.")
+                )
+                .arg(Arg::new("snippet_mode")
+                    .long("snippet-mode")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .value_parser(PossibleValuesParser::new(["local", "remote", "both"]))
+                    .help("The variants of the snippets to generate, by default both.")
+                )
+                .arg(Arg::new("profile")
+                    .long("profile")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .value_parser(PossibleValuesParser::new(["full", "remote"]))
+                    .help("The assets to generate, by default full. The remote profile skips the icons, the sprites and the local snippets, e.g. to publish the library on a CDN, so the item sources are generated without the sprites.")
+                )
+                .arg(Arg::new("index_path")
+                    .long("index")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .help("The path to the JSON index of the generated artifacts, by default generated-index.json in the output directory.")
+                )
+                .arg(Arg::new("do_strict")
+                    .long("strict")
+                    .action(ArgAction::SetTrue)
+                    .help("Fail instead of warning on the doubtful inputs, i.e. the unknown keys of the manifest, the unexpected properties of the shapes and the clean URNs matching no URN of the manifest."))
+                .arg(Arg::new("manifest_format")
+                    .long("manifest-format")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .value_parser(PossibleValuesParser::new(["yaml", "json"]))
+                    .help("The format of the manifest, by default yaml.")
+                )
+                .arg(Arg::new("do_strict_manifest")
+                    .long("strict-manifest")
+                    .action(ArgAction::SetTrue)
+                    .help("Reject the manifest when it contains unknown keys, by default they are only reported."))
+                .arg(Arg::new("changed_against")
+                    .long("changed-against")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .help("The path to a previous manifest, only the items changed since then are generated along with their package and module documentation.")
+                )
+                .arg(Arg::new("dry_run")
+                    .long("dry-run")
+                    .action(ArgAction::SetTrue)
+                    .help("Print the tasks of each phase without executing them."))
+                .arg(Arg::new("do_force")
+                    .long("force")
+                    .action(ArgAction::SetTrue)
+                    .help("Render the artifacts again even if the manifest and the templates are unchanged."))
+                .arg(Arg::new("do_only_missing")
+                    .long("only-missing")
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with_all(["do_force", "cleanup_scopes", "urns_to_clean", "do_clean_all"])
+                    .help("Generate only the missing artifacts: nothing is cleaned and the tasks having all their artifacts are skipped, even if the manifest or the templates have changed."))
+                .arg(Arg::new("do_only_docs")
+                    .long("only-docs")
+                    .action(ArgAction::SetTrue)
+                    .help("Generate only the documentation, without the sources, the icons, the sprites and the snippets, PlantUML is not used at all."))
+                .arg(Arg::new("do_render_only")
+                    .long("render-only")
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with("do_only_docs")
+                    .help("Render only the PlantUML sources already on disk, e.g. after an upgrade of PlantUML, without the cleanup, the resources and the templates. The images already rendered are kept, unless --force is set."))
+                .arg(Arg::new("do_exact_urn")
+                    .long("exact-urn")
+                    .action(ArgAction::SetTrue)
+                    .requires("urns")
+                    .help("Generate only the items matching exactly the URNs, along with the bootstraps, i.e. without the documentation of their packages and modules."))
+                .arg(Arg::new("do_fail_on_empty")
+                    .long("fail-on-empty")
+                    .action(ArgAction::SetTrue)
+                    .help("Fail when the URNs select no package, e.g. because of a typo, by default only the library artifacts are generated."))
+                .arg(Arg::new("do_inline_snippets")
+                    .long("inline-snippets")
+                    .action(ArgAction::SetTrue)
+                    .help("Inline the sources of the local snippets in the item documentation, the missing snippets are skipped."))
+                .arg(Arg::new("do_preserve_order")
+                    .long("preserve-order")
+                    .action(ArgAction::SetTrue)
+                    .help("List the packages, the modules and the items of the documentation in the manifest order, by default they are sorted by URN."))
+                .arg(Arg::new("do_keep_going")
+                    .long("keep-going")
+                    .action(ArgAction::SetTrue)
+                    .help("Go on after a task failure and report all the failures at the end, by default the generation stops on the first error."))
+                .arg(Arg::new("jobs")
+                    .short('j')
+                    .long("jobs")
+                    .env("PLANTUML_GENERATOR_JOBS")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .value_parser(value_parser!(u32).range(1..))
+                    .help("The number of jobs executing the tasks of a phase in parallel, by default 1."))
+                .arg(Arg::new("java_concurrency")
+                    .long("java-concurrency")
+                    .env("PLANTUML_GENERATOR_JAVA_CONCURRENCY")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .value_parser(value_parser!(u32).range(1..))
+                    .help("The maximum number of Java processes running at once, e.g. to render the sprites and the snippets, by default the number of jobs up to 4."))
+                .arg(Arg::new("do_experimental_pipeline")
+                    .long("experimental-pipeline")
+                    .action(ArgAction::SetTrue)
+                    .help("Experimental: clean, create the resources and render the atomic templates unit by unit, so that the items overlap, instead of phase by phase. The phases are kept when the icons of the items reference other items."))
+                .arg(Arg::new("banner")
+                    .long("banner")
+                    .env("PLANTUML_GENERATOR_BANNER")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .help("The banner written at the top of the generated sources, bootstraps and documentation, {version} and {generated_at} are replaced, by default a do-not-edit notice."))
+                .arg(Arg::new("do_no_banner")
+                    .long("no-banner")
+                    .action(ArgAction::SetTrue)
+                    .help("Do not write a banner at the top of the generated sources, bootstraps and documentation, even if --banner is set."))
+                .arg(Arg::new("do_verify_output")
+                    .long("verify-output")
+                    .action(ArgAction::SetTrue)
+                    .help("Check after the generation that the local paths referenced by the generated documentation and sources exist, e.g. the illustrations and the included files."))
+                .arg(Arg::new("output_mode")
+                    .long("output-mode")
+                    .env("PLANTUML_GENERATOR_OUTPUT_MODE")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .value_parser(|v: &str| {
+                        u32::from_str_radix(v, 8)
+                            .ok()
+                            .filter(|mode| *mode <= 0o7777)
+                            .ok_or_else(|| format!("{} is not an octal mode, e.g. 644", v))
+                    })
+                    .help("The Unix mode of the generated files in octal, e.g. 644, the directories are also searchable where readable, e.g. 755. By default the mode is not changed, it's ignored on Windows."))
+                .arg(Arg::new("report_path")
+                    .long("report")
+                    .env("PLANTUML_GENERATOR_REPORT")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .help("The path to a JSON report of the generation, written even when the generation fails: the durations of the phases, the numbers of generated, skipped and failed tasks, and the failures."))
+                .arg(Arg::new("do_keep_intermediates")
+                    .long("keep-intermediates")
+                    .visible_alias("debug-sprites")
+                    .action(ArgAction::SetTrue)
+                    .help("Copy the resized icons used as input of the sprites in the debug directory of the output, named after the URNs of the items and the sprite sizes, e.g. to inspect a poorly rendered sprite."))
+                .arg(Arg::new("resize_filter")
+                    .long("resize-filter")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .value_parser(PossibleValuesParser::new(["nearest", "triangle", "catmullrom", "gaussian", "lanczos3"]))
+                    .help("The filter resizing the raster icons, e.g. lanczos3 for crisper downscales, by default the one of the manifest, i.e. triangle unless customized."))
+                .arg(Arg::new("pre_hook")
+                    .long("pre-hook")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .help("A shell command executed before the generation in the directory of the manifest, e.g. to fetch the icons. The generation is aborted when it fails. The absolute paths of the manifest and the output directory are exposed with PLANTUML_GENERATOR_MANIFEST and PLANTUML_GENERATOR_OUTPUT_DIRECTORY."))
+                .arg(Arg::new("post_hook")
+                    .long("post-hook")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .help("A shell command executed after a successful generation in the directory of the manifest, with the same environment variables as the pre-hook."))
+                .arg(Arg::new("do_concurrency_report")
+                    .long("concurrency-report")
+                    .env("PLANTUML_GENERATOR_CONCURRENCY_REPORT")
+                    .action(ArgAction::SetTrue)
+                    .help("Log the number of work units executed by each job and their idle time at the end of the generation."))
+                .arg(Arg::new("do_no_render_cache")
+                    .long("no-render-cache")
+                    .action(ArgAction::SetTrue)
+                    .help("Render again the sources even if they have already been rendered in the render cache."))
+                .arg(Arg::new("max_depth")
+                    .long("max-depth")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .value_parser(value_parser!(u32))
+                    .help("Skip the artifacts of the URNs having more components, e.g. 2 generates only the packages and the modules."))
+                .arg(Arg::new("family")
+                    .long("family")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .help("Generate only the items of the family, in addition to the URN filtering, along with the documentation of their packages and modules."))
+                .arg(Arg::new("svg_optimizer")
+                    .long("svg-optimizer")
+                    .env("PLANTUML_GENERATOR_SVG_OPTIMIZER")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .help("Optimize the SVG icons, with builtin for the built-in optimizer or with the path to an svgo binary, by default the icons are not optimized."))
+                .arg(Arg::new("png_optimizer")
+                    .long("optimize-png")
+                    .env("PLANTUML_GENERATOR_OPTIMIZE_PNG")
+                    .action(ArgAction::Set)
+                    .num_args(0..=1)
+                    .require_equals(true)
+                    .default_missing_value("builtin")
+                    .help("Optimize losslessly the PNG icons and the sprite icons, with the built-in optimizer or with the path to an oxipng binary, e.g. --optimize-png=oxipng, by default the icons are not optimized."))
                 .arg(Arg::new("do_clean_cache")
                     .long("clean-cache")
                     .action(ArgAction::SetTrue)
                     .help("Delete the cache directory before the generation-"))
+                .arg(Arg::new("do_clean_all")
+                    .long("clean-all")
+                    .action(ArgAction::SetTrue)
+                    .help("Delete the cache and the output directories before the generation."))
                 .arg(Arg::new("urns_to_clean")
                     .help("Delete the given URN in the output directory before the generation.")
                     .long("clean-urn")
@@ -127,6 +372,9 @@ pub fn build_cli() -> Command {
                     .action(ArgAction::Append)
                     .value_parser(PossibleValuesParser::new([
                         "All",
+                        "Bootstrap",
+                        "Documentation",
+                        "Embedded",
                         "Example",
                         "Item",
                         "ItemIcon",
@@ -140,14 +388,83 @@ pub fn build_cli() -> Command {
                     ]))
                 )
                 .arg(&arg_cache_directory)
+                .arg(&arg_skip_lock)
+                .arg(&arg_lock_timeout)
                 .arg(&arg_plantuml_version)
                 .arg(&arg_plantuml_jar)
+                .arg(&arg_plantuml_retries)
+                .arg(&arg_plantuml_layout)
+                .arg(&arg_prune_old_jars)
                 .arg(&arg_java_binary)
                 .arg(&arg_inkscape_binary),
         )
         .subcommand(
             Command::new("schema")
                 .about("Generate the JSON Schema of the library manifest.")
+        )
+        .subcommand(
+            Command::new("list-urns")
+                .about("List the URNs of the packages, modules and items of a manifest.")
+                .arg(Arg::new("MANIFEST")
+                    .index(1)
+                    .required(true)
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .help("The manifest of the library.")
+                )
+                .arg(Arg::new("urn_type")
+                    .long("type")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .value_parser(PossibleValuesParser::new(["package", "module", "item"]))
+                    .help("List only the URNs of the given type, by default all the URNs.")
+                )
+                .arg(Arg::new("urns_format")
+                    .long("format")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .value_parser(PossibleValuesParser::new(["text", "json"]))
+                    .help("The format of the list, by default one URN per line.")
+                )
+        )
+        .subcommand(
+            Command::new("formats")
+                .about("List the formats of the icons and of the diagrams, the latter are read from the PlantUML jar when available.")
+                .arg(&arg_cache_directory)
+                .arg(&arg_plantuml_version)
+                .arg(&arg_plantuml_jar)
+                .arg(&arg_java_binary)
+        )
+        .subcommand(
+            Command::new("add-item")
+                .about("Add an item to a manifest, its module and its package are created when missing.")
+                .arg(Arg::new("MANIFEST")
+                    .index(1)
+                    .required(true)
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .help("The manifest of the library.")
+                )
+                .arg(Arg::new("urn")
+                    .long("urn")
+                    .required(true)
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .help("The URN of the item, e.g. Package/Module/Item.")
+                )
+                .arg(Arg::new("icon")
+                    .long("icon")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .help("The path to the source of the icon, e.g. an SVG file.")
+                )
+                .arg(Arg::new("shapes")
+                    .long("shape")
+                    .action(ArgAction::Append)
+                    .value_delimiter(',')
+                    .value_parser(PossibleValuesParser::new(["Icon", "IconCard", "IconGroup", "Group"]))
+                    .help("The shapes of the elements of the item, by default Icon, IconCard and IconGroup with an icon, otherwise Group.")
+                )
         );
 
     let command_workspace = Command::new("workspace")
@@ -159,7 +476,16 @@ pub fn build_cli() -> Command {
                 .about("Initialize a workspace")
                 .arg(&arg_workspace_manifest)
                 .arg(&arg_source_directory)
-                .arg(&arg_cache_directory),
+                .arg(&arg_cache_directory)
+                .arg(
+                    Arg::new("with_builtin")
+                        .long("with-builtin")
+                        .value_name("VERSION")
+                        .action(ArgAction::Set)
+                        .num_args(0..=1)
+                        .default_missing_value("latest")
+                        .help("Add the builtin artifact to the workspace, by default at its latest version."),
+                ),
         )
         .subcommand(
             Command::new("install")
@@ -172,7 +498,19 @@ pub fn build_cli() -> Command {
                         .long("force")
                         .action(ArgAction::SetTrue)
                         .help("Force the installation of artifacts."),
+                )
+                .arg(
+                    Arg::new("do_verify")
+                        .long("verify")
+                        .action(ArgAction::SetTrue)
+                        .help("Extract again the installed artifacts missing some of their files."),
                 ),
+        )
+        .subcommand(
+            Command::new("list")
+                .about("List the artifacts and their installation")
+                .arg(&arg_workspace_manifest)
+                .arg(&arg_source_directory),
         );
 
     let command_diagram = Command::new("diagram")
@@ -189,6 +527,10 @@ pub fn build_cli() -> Command {
                     .long("force")
                     .action(ArgAction::SetTrue)
                     .help("Force the rendering of discovered .puml file."))
+                .arg(Arg::new("do_strict")
+                    .long("strict")
+                    .action(ArgAction::SetTrue)
+                    .help("Fail when PlantUML prints a warning or an error for a source, even when it is rendered."))
                 .arg(Arg::new("plantuml_args")
                     .short('a')
                     .long("args")
@@ -196,23 +538,120 @@ pub fn build_cli() -> Command {
                     .num_args(1..)
                     .value_delimiter(' ')
                     .help("Extra arguments for PlantUML."))
+                .arg(Arg::new("plantuml_args_file")
+                    .long("args-file")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .help("The path to a file of extra arguments for PlantUML, one per line, the lines starting with # are comments."))
+                .arg(Arg::new("output_directory")
+                    .short('O')
+                    .long("output")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .help("The directory mirroring the source directory with the rendered diagrams, by default next to the sources."))
+                .arg(Arg::new("since")
+                    .long("since")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .help("Render only the sources modified after an ISO-8601 date time or the commit of a git reference, by default the last generation."))
+                .arg(Arg::new("render_timeout")
+                    .long("render-timeout")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .value_parser(value_parser!(u64).range(1..))
+                    .help("The maximum duration of a rendering in seconds, the sources timing out are reported at the end, by default unlimited."))
+                .arg(Arg::new("themes_directory")
+                    .long("themes-directory")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .help("The directory of the local themes, the sources using a modified theme are rendered again."))
+                .arg(Arg::new("do_summary_only")
+                    .long("summary-only")
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with("do_force_generation")
+                    .help("Print the number of up-to-date, stale and missing sources, i.e. never rendered or missing a diagram, then exit without rendering them."))
+                .arg(&arg_cache_directory)
+                .arg(&arg_skip_lock)
+                .arg(&arg_lock_timeout)
+                .arg(&arg_plantuml_version)
+                .arg(&arg_plantuml_jar)
+                .arg(&arg_plantuml_retries)
+                .arg(&arg_plantuml_layout)
+                .arg(&arg_prune_old_jars)
+                .arg(&arg_java_binary)
+        )
+        .subcommand(
+            Command::new("render")
+                .about("Render a single source to the standard output or to a file.")
+                .arg(Arg::new("SOURCE")
+                    .help("The path to the source, - to read it from the standard input.")
+                    .index(1)
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .required(true))
+                .arg(Arg::new("output_path")
+                    .short('o')
+                    .long("output")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .help("The path to the rendered diagram, by default the standard output."))
+                .arg(Arg::new("format")
+                    .short('t')
+                    .long("format")
+                    .action(ArgAction::Set)
+                    .num_args(1)
+                    .default_value(RENDER_FORMAT)
+                    .help("The format of the rendered diagram, e.g. svg or png."))
                 .arg(&arg_cache_directory)
                 .arg(&arg_plantuml_version)
                 .arg(&arg_plantuml_jar)
                 .arg(&arg_java_binary)
         );
 
+    let command_doctor = Command::new("doctor")
+        .about("Check the tools required to render the diagrams")
+        .arg(&arg_cache_directory)
+        .arg(&arg_plantuml_version)
+        .arg(&arg_plantuml_jar)
+        .arg(&arg_java_binary)
+        .arg(&arg_inkscape_binary);
+
     let command_completion = Command::new("completion")
         .about("Generate resources for autocompletion")
         .arg_required_else_help(true)
         .arg(
             Arg::new("SHELL")
-                .help("set the shell")
+                .help("set the shell, all generates the completion of every shell in the output directory")
                 .index(1)
                 .action(ArgAction::Set)
                 .num_args(1)
                 .required(true)
-                .value_parser(value_parser!(Shell)),
+                .requires_if("all", "output_directory")
+                .value_parser(PossibleValuesParser::new(
+                    Shell::value_variants()
+                        .iter()
+                        .filter_map(|shell| shell.to_possible_value())
+                        .chain([PossibleValue::new("all")]),
+                )),
+        )
+        .arg(
+            Arg::new("output_directory")
+                .help("The directory receiving the completion file, by default the completion is written to stdout.")
+                .short('o')
+                .long("output")
+                .action(ArgAction::Set)
+                .num_args(1),
+        );
+
+    let command_manpage = Command::new("manpage")
+        .about("Generate the man pages")
+        .arg(
+            Arg::new("output_directory")
+                .help("The directory receiving one man page per command, by default the page of the root command is written to stdout.")
+                .short('o')
+                .long("output")
+                .action(ArgAction::Set)
+                .num_args(1),
         );
 
     Command::new("plantuml-generator")
@@ -233,8 +672,53 @@ pub fn build_cli() -> Command {
                 ]))
                 .help("Set the verbosity of the logs."),
         )
+        .arg(
+            Arg::new("quiet")
+                .short('q')
+                .long("quiet")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["log_level", "verbose"])
+                .help("Only log the warnings, the errors and the final result."),
+        )
+        .arg(
+            Arg::new("verbose")
+                .long("verbose")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("log_level")
+                .help("Log the debug messages, i.e. `--log-level=Debug`."),
+        )
+        .arg(
+            Arg::new("config_file")
+                .long("config")
+                .action(ArgAction::Set)
+                .num_args(1)
+                .global(true)
+                .env("PLANTUML_GENERATOR_CONFIG_FILE")
+                .help("The project config file, by default `.pgen.yaml` is discovered from the current directory."),
+        )
+        .arg(
+            Arg::new("proxy")
+                .long("proxy")
+                .action(ArgAction::Set)
+                .num_args(1)
+                .global(true)
+                .env("PLANTUML_GENERATOR_PROXY")
+                .help("The URL of the HTTP(S) proxy of the downloads, by default HTTP_PROXY, HTTPS_PROXY and ALL_PROXY are used, the hosts of NO_PROXY are reached directly."),
+        )
+        .arg(
+            Arg::new("download_timeout")
+                .long("download-timeout")
+                .action(ArgAction::Set)
+                .num_args(1)
+                .global(true)
+                .env("PLANTUML_GENERATOR_DOWNLOAD_TIMEOUT")
+                .value_parser(value_parser!(u64).range(1..))
+                .help("The timeout of the connections and of the reads of the downloads in seconds, by default 30."),
+        )
         .subcommand(command_library)
         .subcommand(command_workspace)
         .subcommand(command_diagram)
+        .subcommand(command_doctor)
         .subcommand(command_completion)
+        .subcommand(command_manpage)
 }
@@ -1,28 +1,68 @@
 use std::io;
+use std::path::Path;
 
 use anyhow::Result;
-use clap::ArgMatches;
-use clap_complete::{generate, Shell};
+use clap::{ArgMatches, ValueEnum};
+use clap_complete::{generate, generate_to, Shell};
 
 use crate::cli::build_cli;
+use crate::constants::LOG_TARGET_RESULT;
+use crate::utils::create_directory;
+
+/// Write the completion of a shell in the output directory.
+fn write_completion(shell: Shell, output_directory: &str) -> Result<()> {
+    let path = generate_to(
+        shell,
+        &mut build_cli(),
+        "plantuml-generator",
+        output_directory,
+    )
+    .map_err(|e| {
+        anyhow::Error::new(e).context(format!(
+            "unable to write the {} completion in {}",
+            shell, output_directory
+        ))
+    })?;
+    log::info!(target: LOG_TARGET_RESULT, "{}", path.display());
+    Ok(())
+}
 
 pub fn execute_completion(arg_matches: &ArgMatches) -> Result<()> {
-    match arg_matches.get_one::<Shell>("SHELL") {
-        None => Err(anyhow::Error::msg("unable to get the SHELL")),
-        Some(shell) => {
-            generate(
-                *shell,
-                &mut build_cli(),
-                "plantuml-generator",
-                &mut io::stdout(),
-            );
-            Ok(())
+    let shell = arg_matches
+        .get_one::<String>("SHELL")
+        .ok_or_else(|| anyhow::Error::msg("unable to get the SHELL"))?;
+    let shells: Vec<Shell> = match shell.as_str() {
+        "all" => Shell::value_variants().to_vec(),
+        _ => vec![Shell::from_str(shell, true).map_err(anyhow::Error::msg)?],
+    };
+    match arg_matches.get_one::<String>("output_directory") {
+        None => {
+            for shell in shells {
+                generate(
+                    shell,
+                    &mut build_cli(),
+                    "plantuml-generator",
+                    &mut io::stdout(),
+                );
+            }
+        }
+        // the file name is the conventional one of the shell, e.g. _plantuml-generator for zsh
+        Some(output_directory) => {
+            create_directory(Path::new(output_directory))?;
+            for shell in shells {
+                write_completion(shell, output_directory)?;
+            }
         }
     }
+    Ok(())
 }
 
 #[cfg(test)]
 mod test {
+    use std::fs::read_to_string;
+
+    use crate::utils::delete_file_or_directory;
+
     use super::*;
 
     #[test]
@@ -31,4 +71,44 @@ mod test {
             build_cli().get_matches_from(["plantuml-generator", "-l=Debug", "completion", "bash"]);
         execute_completion(arg_matches.subcommand_matches("completion").unwrap()).unwrap();
     }
+
+    #[test]
+    fn test_completion_to_file() {
+        let output_directory = "target/tests/completion";
+        delete_file_or_directory(Path::new(output_directory)).unwrap();
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "completion",
+            "zsh",
+            "--output=target/tests/completion",
+        ]);
+        execute_completion(arg_matches.subcommand_matches("completion").unwrap()).unwrap();
+        let content = read_to_string(Path::new(output_directory).join("_plantuml-generator"));
+        assert!(!content.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_all_completions_to_files() {
+        let output_directory = "target/tests/completion_all";
+        delete_file_or_directory(Path::new(output_directory)).unwrap();
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "completion",
+            "all",
+            "--output=target/tests/completion_all",
+        ]);
+        execute_completion(arg_matches.subcommand_matches("completion").unwrap()).unwrap();
+        for file_name in [
+            "plantuml-generator.bash",
+            "_plantuml-generator",
+            "plantuml-generator.fish",
+        ] {
+            let content = read_to_string(Path::new(output_directory).join(file_name));
+            assert!(!content.unwrap().is_empty());
+        }
+        let result = build_cli().try_get_matches_from(["plantuml-generator", "completion", "all"]);
+        assert!(result.is_err());
+    }
 }
@@ -8,6 +8,7 @@ use crate::constants::get_default_plantuml_jar;
 use crate::constants::get_default_plantuml_version;
 use crate::constants::get_default_source_directory;
 use crate::constants::{get_default_cache_directory, get_default_source_patterns};
+use crate::project::ProjectConfig;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Config {
@@ -17,6 +18,9 @@ pub struct Config {
     /// The patterns to discover the source files separated by comas.
     #[serde(default = "get_default_source_patterns")]
     pub source_patterns: String,
+    /// The path to the directory mirroring the sources with the rendered diagrams.
+    #[serde(default)]
+    pub output_directory: Option<String>,
     /// The path to the cache directory.
     #[serde(default = "get_default_cache_directory")]
     pub cache_directory: String,
@@ -29,6 +33,9 @@ pub struct Config {
     /// The path to the java binary.
     #[serde(default = "get_default_java_binary")]
     pub java_binary: String,
+    /// The path to the directory of the local themes used by the `!theme` directives.
+    #[serde(default)]
+    pub themes_directory: Option<String>,
 }
 
 impl Config {
@@ -43,6 +50,11 @@ impl Config {
             .map(|v| v.to_string())
             .unwrap_or_else(|| self.source_patterns.clone());
 
+        let output_directory = args
+            .get_one::<String>("output_directory")
+            .map(|v| v.to_string())
+            .or_else(|| self.output_directory.clone());
+
         let cache_directory = args
             .get_one::<String>("cache_directory")
             .map(|v| v.to_string())
@@ -56,7 +68,13 @@ impl Config {
             Some(v) => v,
         };
 
+        // the jar is explicitly configured when it doesn't follow the default location
+        let is_jar_configured = self.plantuml_jar != get_default_plantuml_jar()
+            && args.get_one::<String>("cache_directory").is_none()
+            && args.get_one::<String>("plantuml_version").is_none();
+
         let plantuml_jar = match args.get_one::<String>("plantuml_jar") {
+            None if is_jar_configured => self.plantuml_jar.clone(),
             None => match Path::new(&cache_directory)
                 .join(format!("plantuml-{}.jar", plantuml_version))
                 .as_path()
@@ -71,6 +89,7 @@ impl Config {
         Config {
             source_directory,
             source_patterns,
+            output_directory,
             cache_directory,
             plantuml_version,
             plantuml_jar,
@@ -78,29 +97,51 @@ impl Config {
                 .get_one::<String>("java_binary")
                 .map(|v| v.to_string())
                 .unwrap_or_else(|| self.java_binary.clone()),
+            themes_directory: args
+                .get_one::<String>("themes_directory")
+                .map(|v| v.to_string())
+                .or_else(|| self.themes_directory.clone()),
         }
     }
 }
 
-impl Default for Config {
-    fn default() -> Self {
+impl Config {
+    /// Create the config, the environment takes precedence over the project config file.
+    pub fn from_project(project: &ProjectConfig) -> Config {
         Config {
             source_directory: std::env::var("PLANTUML_GENERATOR_SOURCE_DIRECTORY")
                 .unwrap_or_else(|_| get_default_source_directory()),
             source_patterns: std::env::var("PLANTUML_GENERATOR_SOURCE_PATTERNS")
                 .unwrap_or_else(|_| get_default_source_patterns()),
+            output_directory: None,
             cache_directory: std::env::var("PLANTUML_GENERATOR_CACHE_DIRECTORY")
-                .unwrap_or_else(|_| get_default_cache_directory()),
+                .ok()
+                .or_else(|| project.cache_directory.clone())
+                .unwrap_or_else(get_default_cache_directory),
             plantuml_version: std::env::var("PLANTUML_GENERATOR_PLANTUML_VERSION")
-                .unwrap_or_else(|_| get_default_plantuml_version()),
+                .ok()
+                .or_else(|| project.plantuml_version.clone())
+                .unwrap_or_else(get_default_plantuml_version),
             plantuml_jar: std::env::var("PLANTUML_GENERATOR_PLANTUML_JAR")
-                .unwrap_or_else(|_| get_default_plantuml_jar()),
+                .ok()
+                .or_else(|| project.plantuml_jar.clone())
+                .unwrap_or_else(get_default_plantuml_jar),
             java_binary: std::env::var("PLANTUML_GENERATOR_JAVA_BINARY").unwrap_or_else(|_| {
-                match std::env::var("JAVA_HOME") {
-                    Ok(v) => format!("{}/bin/java", v),
-                    Err(_) => get_default_java_binary(),
+                match &project.java_binary {
+                    Some(v) => v.clone(),
+                    None => match std::env::var("JAVA_HOME") {
+                        Ok(v) => format!("{}/bin/java", v),
+                        Err(_) => get_default_java_binary(),
+                    },
                 }
             }),
+            themes_directory: std::env::var("PLANTUML_GENERATOR_THEMES_DIRECTORY").ok(),
         }
     }
 }
+
+impl Default for Config {
+    fn default() -> Self {
+        Config::from_project(&ProjectConfig::default())
+    }
+}
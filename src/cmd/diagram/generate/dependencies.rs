@@ -0,0 +1,260 @@
+use std::collections::HashSet;
+use std::fs::read_to_string;
+use std::iter::once;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use crate::utils::normalize_path;
+
+/// The prefix of the file names of the PlantUML themes.
+const THEME_PREFIX: &str = "puml-theme-";
+
+/// True when the reference targets a remote resource.
+fn is_remote(reference: &str) -> bool {
+    reference.starts_with("http://") || reference.starts_with("https://")
+}
+
+/// Resolve the local file of a preprocessing directive, none for the remote and built-in resources.
+fn parse_directive(
+    line: &str,
+    source_directory: &Path,
+    themes_directory: Option<&Path>,
+) -> Option<PathBuf> {
+    let (directive, argument) = line.trim().split_once(char::is_whitespace)?;
+    let argument = argument.trim().trim_matches('"');
+    match directive {
+        "!include" | "!include_many" | "!include_once" | "!includesub" | "!import" => {
+            // e.g. !include <C4/C4_Container> from the standard library
+            if argument.starts_with('<') {
+                return None;
+            }
+            if is_remote(argument) {
+                log::info!("ignore the remote dependency {}", argument);
+                return None;
+            }
+            // e.g. !include file.puml!2 or !includesub file.puml!BLOCK
+            let path = argument.split('!').next().unwrap_or(argument);
+            Some(source_directory.join(path))
+        }
+        "!includeurl" => {
+            log::info!("ignore the remote dependency {}", argument);
+            None
+        }
+        "!theme" => {
+            let mut parts = argument.split_whitespace();
+            let file_name = format!("{}{}.puml", THEME_PREFIX, parts.next()?);
+            match (parts.next(), parts.next()) {
+                // e.g. !theme name from directory
+                (Some("from"), Some(directory)) if is_remote(directory) => {
+                    log::info!("ignore the remote dependency {}", directory);
+                    None
+                }
+                (Some("from"), Some(directory)) if !directory.starts_with('<') => {
+                    Some(source_directory.join(directory).join(file_name))
+                }
+                (Some("from"), Some(_)) => None,
+                // the themes missing in the themes directory are the built-in ones
+                _ => themes_directory.map(|directory| directory.join(file_name)),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Visit the dependencies of a file depth first, the files being visited are in the stack.
+fn visit_dependencies(
+    themes_directory: Option<&Path>,
+    stack: &mut Vec<PathBuf>,
+    visited: &mut HashSet<PathBuf>,
+    dependencies: &mut Vec<PathBuf>,
+) -> Result<()> {
+    let path = match stack.last() {
+        Some(path) => path.clone(),
+        None => return Ok(()),
+    };
+    // the missing files and the archives don't have dependencies
+    let content = match read_to_string(&path) {
+        Ok(content) => content,
+        Err(_) => return Ok(()),
+    };
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+    for dependency in content
+        .lines()
+        .filter_map(|line| parse_directive(line, directory, themes_directory))
+        .map(|dependency| normalize_path(&dependency))
+    {
+        if let Some(index) = stack.iter().position(|path| path == &dependency) {
+            return Err(anyhow::Error::msg(format!(
+                "the includes are circular: {}",
+                stack[index..]
+                    .iter()
+                    .chain(once(&dependency))
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<String>>()
+                    .join(" -> ")
+            )));
+        }
+        if visited.insert(dependency.clone()) {
+            dependencies.push(dependency.clone());
+            stack.push(dependency);
+            visit_dependencies(themes_directory, stack, visited, dependencies)?;
+            stack.pop();
+        }
+    }
+    Ok(())
+}
+
+/// The local files a source depends on, recursively through the included files.
+///
+/// The circular includes are reported, e.g. `a.puml` including `b.puml` including `a.puml`.
+pub fn find_dependencies(
+    source_path: &Path,
+    themes_directory: Option<&Path>,
+) -> Result<Vec<PathBuf>> {
+    let source_path = normalize_path(source_path);
+    let mut dependencies: Vec<PathBuf> = vec![];
+    let mut visited: HashSet<PathBuf> = HashSet::from([source_path.clone()]);
+    let mut stack: Vec<PathBuf> = vec![source_path];
+    visit_dependencies(
+        themes_directory,
+        &mut stack,
+        &mut visited,
+        &mut dependencies,
+    )?;
+    Ok(dependencies)
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::write;
+
+    use crate::utils::{create_directory, delete_file_or_directory};
+
+    use super::*;
+
+    #[test]
+    fn test_parse_include() {
+        let directory = Path::new("sources");
+        assert_eq!(
+            parse_directive("!include common.puml", directory, None),
+            Some(directory.join("common.puml"))
+        );
+        assert_eq!(
+            parse_directive("  !include_once \"common.puml\"", directory, None),
+            Some(directory.join("common.puml"))
+        );
+        assert_eq!(
+            parse_directive("!include_many ../common.puml!2", directory, None),
+            Some(directory.join("../common.puml"))
+        );
+        assert_eq!(
+            parse_directive("!includesub common.puml!BLOCK", directory, None),
+            Some(directory.join("common.puml"))
+        );
+        assert_eq!(
+            parse_directive("!include <C4/C4_Container>", directory, None),
+            None
+        );
+        assert_eq!(
+            parse_directive("!include https://host/common.puml", directory, None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_includeurl() {
+        let directory = Path::new("sources");
+        assert_eq!(
+            parse_directive("!includeurl https://host/common.puml", directory, None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_import() {
+        let directory = Path::new("sources");
+        assert_eq!(
+            parse_directive("!import library.zip", directory, None),
+            Some(directory.join("library.zip"))
+        );
+    }
+
+    #[test]
+    fn test_parse_theme() {
+        let directory = Path::new("sources");
+        let themes_directory = Path::new("themes");
+        assert_eq!(
+            parse_directive("!theme custom", directory, Some(themes_directory)),
+            Some(themes_directory.join("puml-theme-custom.puml"))
+        );
+        assert_eq!(parse_directive("!theme custom", directory, None), None);
+        assert_eq!(
+            parse_directive("!theme custom from ../themes", directory, None),
+            Some(directory.join("../themes/puml-theme-custom.puml"))
+        );
+        assert_eq!(
+            parse_directive("!theme custom from https://host/themes", directory, None),
+            None
+        );
+        assert_eq!(
+            parse_directive("!theme", directory, Some(themes_directory)),
+            None
+        );
+        assert_eq!(
+            parse_directive("skinparam monochrome true", directory, None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_find_dependencies() {
+        let directory = Path::new("target/tests/diagram/dependencies");
+        delete_file_or_directory(directory).unwrap();
+        create_directory(&directory.join("themes")).unwrap();
+        let source_path = directory.join("diagram.puml");
+        write(
+            &source_path,
+            "@startuml\n!theme custom\n!include common.puml\n!includeurl https://host/a.puml\n@enduml\n",
+        )
+        .unwrap();
+        write(
+            directory.join("common.puml"),
+            "!include nested.puml\n!include ./nested.puml\n",
+        )
+        .unwrap();
+        let dependencies =
+            find_dependencies(&source_path, Some(&directory.join("themes"))).unwrap();
+        assert_eq!(
+            dependencies,
+            vec![
+                directory.join("themes/puml-theme-custom.puml"),
+                directory.join("common.puml"),
+                directory.join("nested.puml"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_circular_includes() {
+        let directory = Path::new("target/tests/diagram/circular_includes");
+        delete_file_or_directory(directory).unwrap();
+        create_directory(directory).unwrap();
+        write(directory.join("a.puml"), "!include b.puml\n").unwrap();
+        write(
+            directory.join("b.puml"),
+            "!include ../circular_includes/a.puml\n",
+        )
+        .unwrap();
+        let error = find_dependencies(&directory.join("a.puml"), None).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "the includes are circular: {} -> {} -> {}",
+                directory.join("a.puml").display(),
+                directory.join("b.puml").display(),
+                directory.join("a.puml").display()
+            )
+        );
+    }
+}
@@ -1,18 +1,30 @@
-use std::fs::{read_to_string, OpenOptions};
-use std::io::Write;
-use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::read_to_string;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime};
 
 use anyhow::Result;
 use chrono::prelude::*;
 use clap::ArgMatches;
 use glob::glob;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 
 use crate::cmd::diagram::generate::config::Config;
-use crate::plantuml::create_plantuml;
-use crate::utils::create_parent_directory;
+use crate::cmd::diagram::generate::dependencies::find_dependencies;
+use crate::cmd::diagram::generate::outputs::{get_expected_outputs, get_output_extension};
+use crate::constants::{
+    get_default_plantuml_retries, DIAGRAM_IGNORE, DIAGRAM_LAST_GENERATION, DIAGRAM_LAST_GENERATIONS,
+};
+use crate::lock::lock_cache_directory;
+use crate::plantuml::{create_plantuml, find_problems, is_timeout, prune_jars};
+use crate::project::ProjectConfig;
+use crate::utils::{create_directory, create_parent_directory, delete_file, write_file_atomically};
 
 mod config;
+mod dependencies;
+mod outputs;
 
 fn get_last_modified(path: &Path) -> Result<i64> {
     match path.exists() {
@@ -54,27 +66,90 @@ fn get_last_generation_timestamp(last_gen_path: &Path) -> Result<i64> {
     }
 }
 
-fn save_last_generation_timestamp(last_gen_path: &Path) -> Result<()> {
-    let now: DateTime<Local> = DateTime::from(SystemTime::now());
-    let value = now.timestamp_nanos_opt().unwrap().to_string();
-    log::debug!("save_last_generation_timestamp {}", value);
-    let mut last_gen_file = OpenOptions::new()
-        .create(true)
-        .write(true)
-        .truncate(true)
-        .append(false)
-        .open(last_gen_path)
-        .map_err(|e| {
-            anyhow::Error::new(e).context(format!("unable to open {:?}", &last_gen_path))
-        })?;
-    last_gen_file.write_all(value.as_bytes()).map_err(|e| {
-        anyhow::Error::new(e).context(format!("unable to write {:?}", last_gen_file))
+/// Resolve the timestamp of an ISO-8601 date time or of the commit of a git reference.
+fn parse_since(value: &str) -> Result<i64> {
+    let date_time = match DateTime::parse_from_rfc3339(value) {
+        Ok(date_time) => date_time,
+        Err(e) => {
+            let output = Command::new("git")
+                .args(["show", "-s", "--format=%cI", value])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .ok_or_else(|| {
+                    anyhow::Error::new(e).context(format!(
+                        "unable to parse {} as a date time or a git reference",
+                        value
+                    ))
+                })?;
+            let commit_time = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            DateTime::parse_from_rfc3339(&commit_time).map_err(|e| {
+                anyhow::Error::new(e).context(format!("unable to parse {}", commit_time))
+            })?
+        }
+    };
+    date_time
+        .timestamp_nanos_opt()
+        .ok_or_else(|| anyhow::Error::msg(format!("the date time {} is out of range", value)))
+}
+
+/// The timestamps of the last generations keyed by the relative paths of the sources.
+type LastGenerations = BTreeMap<String, i64>;
+
+/// The key of a source, e.g. `src/diagram.puml` for `./src/diagram.puml`.
+fn get_source_key(source_path: &Path) -> String {
+    source_path
+        .components()
+        .filter(|component| !matches!(component, Component::CurDir))
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Read the timestamps of the last generations, a corrupted file is ignored like a missing one.
+fn read_last_generations(last_gens_path: &Path) -> Result<LastGenerations> {
+    if !last_gens_path.exists() {
+        return Ok(LastGenerations::new());
+    }
+    let content = read_to_string(last_gens_path).map_err(|e| {
+        anyhow::Error::new(e).context(format!("unable to read {:?}", last_gens_path))
     })?;
-    Ok(())
+    Ok(serde_json::from_str(&content).unwrap_or_else(|e| {
+        log::warn!("unable to parse {:?}: {}", last_gens_path, e);
+        LastGenerations::new()
+    }))
+}
+
+fn save_last_generations(last_gens_path: &Path, last_generations: &LastGenerations) -> Result<()> {
+    log::debug!("save_last_generations {:?}", last_generations);
+    write_file_atomically(last_gens_path, |file| {
+        serde_json::to_writer_pretty(file, last_generations).map_err(|e| {
+            anyhow::Error::new(e).context(format!("unable to write {:?}", last_gens_path))
+        })
+    })
 }
 
-fn get_puml_paths(config: &Config) -> Vec<PathBuf> {
-    config
+/// Read the ignore file of the source directory, none when it doesn't exist.
+fn read_ignore_file(config: &Config) -> Result<Option<Gitignore>> {
+    let ignore_path = Path::new(&config.source_directory).join(DIAGRAM_IGNORE);
+    if !ignore_path.exists() {
+        return Ok(None);
+    }
+    let mut builder = GitignoreBuilder::new(&config.source_directory);
+    if let Some(e) = builder.add(&ignore_path) {
+        return Err(
+            anyhow::Error::new(e).context(format!("unable to read {}", ignore_path.display()))
+        );
+    }
+    let gitignore = builder.build().map_err(|e| {
+        anyhow::Error::new(e).context(format!("unable to parse {}", ignore_path.display()))
+    })?;
+    Ok(Some(gitignore))
+}
+
+fn get_puml_paths(config: &Config) -> Result<Vec<PathBuf>> {
+    let ignore = read_ignore_file(config)?;
+    Ok(config
         .source_patterns
         .split(",")
         .map(str::trim)
@@ -91,61 +166,299 @@ fn get_puml_paths(config: &Config) -> Vec<PathBuf> {
                 .map(|paths| paths.collect::<Vec<PathBuf>>())
                 .unwrap()
         })
-        .collect::<Vec<PathBuf>>()
+        .filter(|path| {
+            ignore
+                .as_ref()
+                .is_none_or(|ignore| !ignore.matched_path_or_any_parents(path, false).is_ignore())
+        })
+        .collect::<Vec<PathBuf>>())
+}
+
+/// The directory mirroring the directory of the source in the output directory, when set.
+fn get_mirror_directory(config: &Config, source_path: &Path) -> Result<Option<PathBuf>> {
+    let output_directory = match &config.output_directory {
+        None => return Ok(None),
+        Some(v) => Path::new(v),
+    };
+    let relative_directory = source_path
+        .parent()
+        .and_then(|p| p.strip_prefix(&config.source_directory).ok())
+        .ok_or_else(|| {
+            anyhow::Error::msg(format!(
+                "unable to resolve the relative path of {:?}",
+                source_path
+            ))
+        })?;
+    Ok(Some(output_directory.join(relative_directory)))
+}
+
+/// Resolve the directory of the rendered diagrams mirroring the directory of the source.
+fn get_output_directory(config: &Config, source_path: &Path) -> Result<Option<PathBuf>> {
+    let mirror_directory = match get_mirror_directory(config, source_path)? {
+        None => return Ok(None),
+        Some(v) => v,
+    };
+    create_directory(&mirror_directory)?;
+    // PlantUML resolves a relative output directory from the directory of the source
+    let mirror_directory = mirror_directory.canonicalize().map_err(|e| {
+        anyhow::Error::new(e).context(format!("unable to resolve {:?}", mirror_directory))
+    })?;
+    Ok(Some(mirror_directory))
+}
+
+/// Read the PlantUML arguments of a file, one per line, the empty lines and the comments are skipped.
+fn read_args_file(path: &Path) -> Result<Vec<String>> {
+    let content = read_to_string(path)
+        .map_err(|e| anyhow::Error::new(e).context(format!("unable to read {}", path.display())))?;
+    Ok(content
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.to_string())
+        .collect())
+}
+
+/// The last modification of a source, including the modifications of its included files and themes.
+fn get_last_modification_timestamp(
+    source_path: &Path,
+    themes_directory: Option<&Path>,
+) -> Result<i64> {
+    find_dependencies(source_path, themes_directory)?
+        .iter()
+        .try_fold(get_last_modified(source_path)?, |timestamp, path| {
+            get_last_modified(path).map(|v| v.max(timestamp))
+        })
+}
+
+/// True when a diagram of the source is missing, e.g. deleted since the last generation.
+fn has_missing_outputs(config: &Config, source_path: &Path, extension: &str) -> Result<bool> {
+    let output_directory = match get_mirror_directory(config, source_path)? {
+        Some(v) => v,
+        None => source_path.parent().unwrap_or(Path::new(".")).to_path_buf(),
+    };
+    Ok(
+        get_expected_outputs(source_path, &output_directory, extension)?
+            .iter()
+            .any(|path| !path.exists()),
+    )
+}
+
+/// The number of sources per state against their last generation.
+#[derive(Debug, Default, PartialEq)]
+struct Summary {
+    /// The sources not modified since their last generation.
+    up_to_date: usize,
+    /// The sources modified since their last generation.
+    stale: usize,
+    /// The sources never rendered or having missing diagrams.
+    missing: usize,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "up-to-date: {}", self.up_to_date)?;
+        writeln!(f, "stale: {}", self.stale)?;
+        write!(f, "missing: {}", self.missing)
+    }
+}
+
+/// Count the sources per state like the generation would resolve them, nothing is rendered.
+fn summarize_sources(
+    config: &Config,
+    last_generations: &LastGenerations,
+    default_timestamp: i64,
+    since_timestamp: Option<i64>,
+    extension: &str,
+) -> Result<Summary> {
+    let themes_directory = config.themes_directory.as_ref().map(Path::new);
+    let mut summary = Summary::default();
+    for source_path in get_puml_paths(config)? {
+        let last_generation_timestamp = last_generations
+            .get(&get_source_key(&source_path))
+            .copied()
+            .unwrap_or(default_timestamp);
+        let is_missing = last_generation_timestamp == 0
+            || (since_timestamp.is_none() && has_missing_outputs(config, &source_path, extension)?);
+        if is_missing {
+            summary.missing += 1;
+            continue;
+        }
+        let last_generation_timestamp = since_timestamp.unwrap_or(last_generation_timestamp);
+        match get_last_modification_timestamp(&source_path, themes_directory)?
+            > last_generation_timestamp
+        {
+            true => summary.stale += 1,
+            false => summary.up_to_date += 1,
+        }
+    }
+    Ok(summary)
 }
 
 pub fn execute_diagram_generate(arg_matches: &ArgMatches) -> Result<()> {
     // resolve the config
-    let config = &Config::default().update_from_args(arg_matches);
+    let project = &ProjectConfig::load(arg_matches)?;
+    let config = &Config::from_project(project).update_from_args(arg_matches);
     let force_generation = arg_matches.get_flag("do_force_generation");
+    let is_strict = arg_matches.get_flag("do_strict");
     if log::log_enabled!(log::Level::Info) {
         log::info!("source_directory: {}", &config.source_directory);
+        if let Some(output_directory) = &config.output_directory {
+            log::info!("output_directory: {}", output_directory);
+        }
         log::info!("cache_directory: {}", &config.cache_directory);
         log::info!("plantuml_jar: {}", &config.plantuml_jar);
         log::info!("java_binary: {}", &config.java_binary);
         log::info!("force_generation: {}", force_generation);
+        if let Some(themes_directory) = &config.themes_directory {
+            log::info!("themes_directory: {}", themes_directory);
+        }
+    }
+    // resolve the LAST_GENERATIONS file, the former LAST_GENERATION one is the default baseline
+    let last_gens_path = &Path::new(&config.cache_directory).join(DIAGRAM_LAST_GENERATIONS);
+    let last_gen_path = &Path::new(&config.cache_directory).join(DIAGRAM_LAST_GENERATION);
+    let since_timestamp = arg_matches
+        .get_one::<String>("since")
+        .map(|since| parse_since(since))
+        .transpose()?;
+    // the arguments of the file are appended to the inline ones
+    let mut extra_plantuml_args = arg_matches
+        .get_many::<String>("plantuml_args")
+        .unwrap_or_default()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>();
+    if let Some(args_file) = arg_matches.get_one::<String>("plantuml_args_file") {
+        extra_plantuml_args.extend(read_args_file(Path::new(args_file))?);
+    }
+    // the extension of the diagrams expected for each source
+    let extension = &get_output_extension(&extra_plantuml_args);
+    // the summary neither renders the sources nor updates the cache
+    if arg_matches.get_flag("do_summary_only") {
+        let summary = summarize_sources(
+            config,
+            &read_last_generations(last_gens_path)?,
+            get_last_generation_timestamp(last_gen_path)?,
+            since_timestamp,
+            extension,
+        )?;
+        println!("{}", summary);
+        return Ok(());
     }
-    // resolve the LAST_GENERATION file
-    let last_gen_path_buff = Path::new(config.cache_directory.as_str()).join("LAST_GENERATION");
-    let last_gen_path = last_gen_path_buff.as_path();
-    create_parent_directory(last_gen_path)?;
+    // lock the cache directory, the lock is released when the generation ends
+    let _lock = lock_cache_directory(arg_matches, &config.cache_directory)?;
+    create_parent_directory(last_gens_path)?;
     // create PlantUML
     let plantuml = create_plantuml(
         &config.java_binary,
         &config.plantuml_jar,
         &config.plantuml_version,
-    )?;
+    )?
+    .with_retries(
+        arg_matches
+            .get_one::<u32>("plantuml_retries")
+            .copied()
+            .unwrap_or_else(get_default_plantuml_retries),
+    )
+    .with_layout(arg_matches.get_one::<String>("plantuml_layout").cloned())
+    .with_timeout(
+        arg_matches
+            .get_one::<u64>("render_timeout")
+            .map(|v| Duration::from_secs(*v)),
+    );
     plantuml.download()?;
-    // get latest generation
-    let last_generation_timestamp = get_last_generation_timestamp(last_gen_path)?;
+    if arg_matches.get_flag("do_prune_old_jars") {
+        prune_jars(&config.cache_directory, &config.plantuml_jar)?;
+    }
+    // get latest generations, the baseline of every source can be overridden
+    let default_timestamp = get_last_generation_timestamp(last_gen_path)?;
+    let mut last_generations = read_last_generations(last_gens_path)?;
+    // the sources modified while they are rendered are rendered again the next time
+    let generation_timestamp = DateTime::<Local>::from(SystemTime::now())
+        .timestamp_nanos_opt()
+        .unwrap();
     // discover source files
-    let puml_paths = get_puml_paths(config);
+    let puml_paths = get_puml_paths(config)?;
+    // the sources timing out don't stop the generation of the others
+    let mut timed_out_paths: Vec<PathBuf> = vec![];
     // generate source files
+    let themes_directory = config.themes_directory.as_ref().map(Path::new);
     for source_path in puml_paths {
-        let last_modification_timestamp = get_last_modified(&source_path)?;
+        let source_key = get_source_key(&source_path);
+        let last_generation_timestamp = *last_generations
+            .entry(source_key.clone())
+            .or_insert(default_timestamp);
+        let last_generation_timestamp = since_timestamp.unwrap_or(last_generation_timestamp);
+        // the source is also mutated by the modification of its included files and themes
+        let last_modification_timestamp =
+            get_last_modification_timestamp(&source_path, themes_directory)?;
         log::debug!(
             "{} > {} = {}",
             last_modification_timestamp,
             last_generation_timestamp,
             last_modification_timestamp > last_generation_timestamp,
         );
-        if force_generation || last_modification_timestamp > last_generation_timestamp {
+        // the missing diagrams are rendered again, e.g. deleted or renamed by `@startuml name`
+        if force_generation
+            || last_modification_timestamp > last_generation_timestamp
+            || (since_timestamp.is_none() && has_missing_outputs(config, &source_path, extension)?)
+        {
             log::info!("generate {:?}", source_path);
-            let plantuml_args = arg_matches
-                .get_many::<String>("plantuml_args")
-                .unwrap_or_default()
-                .map(|v| v.to_string())
-                .collect::<Vec<_>>();
-            plantuml.render(&source_path, Some(plantuml_args))?;
+            let mut plantuml_args = extra_plantuml_args.clone();
+            if let Some(output_directory) = get_output_directory(config, &source_path)? {
+                plantuml_args.push("-o".to_string());
+                plantuml_args.push(output_directory.to_string_lossy().to_string());
+            }
+            match plantuml.render(&source_path, Some(plantuml_args)) {
+                Err(e) if is_timeout(&e) => {
+                    log::error!("{:#}", e);
+                    timed_out_paths.push(source_path);
+                }
+                Err(e) => {
+                    // the sources rendered so far are not rendered again
+                    save_last_generations(last_gens_path, &last_generations)?;
+                    return Err(e);
+                }
+                Ok(stderr) if is_strict && !find_problems(&stderr).is_empty() => {
+                    // the source is rendered again the next time
+                    save_last_generations(last_gens_path, &last_generations)?;
+                    return Err(anyhow::Error::msg(format!(
+                        "PlantUML reported problems for {}:\n{}",
+                        source_path.display(),
+                        find_problems(&stderr).join("\n")
+                    )));
+                }
+                Ok(_) => {
+                    last_generations.insert(source_key, generation_timestamp);
+                }
+            }
         }
     }
-    save_last_generation_timestamp(last_gen_path)?;
+    // the timestamps of the sources timing out are not saved, so that they are rendered again
+    save_last_generations(last_gens_path, &last_generations)?;
+    if last_gen_path.exists() {
+        delete_file(last_gen_path)?;
+    }
+    if !timed_out_paths.is_empty() {
+        return Err(anyhow::Error::msg(format!(
+            "the rendering timed out for {} source(s):\n{}",
+            timed_out_paths.len(),
+            timed_out_paths
+                .iter()
+                .map(|path| format!("- {}", path.display()))
+                .collect::<Vec<String>>()
+                .join("\n")
+        )));
+    }
     Ok(())
 }
 
 #[cfg(test)]
 mod test {
+    use std::fs::OpenOptions;
+    use std::io::Write;
+    use std::time::Duration;
+
     use crate::cli::build_cli;
+    use crate::lock::CacheLock;
     use crate::utils::{create_parent_directory, delete_file, delete_file_or_directory};
 
     use super::*;
@@ -229,7 +542,380 @@ mod test {
         let path_diagram_a_0_png_modified_after =
             path_diagram_a_0_png.metadata().unwrap().modified().unwrap();
         assert!(path_diagram_a_0_png_modified_before < path_diagram_a_0_png_modified_after);
-        // check diagram_b_0 hasn't been generated again
-        assert!(!path_diagram_b_0_png.exists());
+        // check the missing diagram_b_0 has been generated again
+        assert!(path_diagram_b_0_png.exists());
+    }
+
+    #[test]
+    fn test_diagram_generation_to_output_directory() {
+        delete_file_or_directory("target/tests/cmd/diagram/output".as_ref()).unwrap();
+        for source_file in &["diagrams_a.puml", "folder_a/diagrams_b.puml"] {
+            let from_path = Path::new("test/source").join(source_file);
+            let to_path = Path::new("target/tests/cmd/diagram/output/source").join(source_file);
+            create_parent_directory(&to_path).unwrap();
+            std::fs::copy(&from_path, &to_path).unwrap();
+        }
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "diagram",
+            "generate",
+            "-s=target/tests/cmd/diagram/output/source",
+            "-O=target/tests/cmd/diagram/output/output",
+            "-C=target/tests/cmd/diagram/output/cache",
+            "-P=test/plantuml-1.2022.4.jar",
+        ]);
+        execute_diagram_generate(
+            arg_matches
+                .subcommand_matches("diagram")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(Path::new("target/tests/cmd/diagram/output/output/diagram_a_0.png").exists());
+        assert!(
+            Path::new("target/tests/cmd/diagram/output/output/folder_a/diagram_b_0.png").exists()
+        );
+        assert!(!Path::new("target/tests/cmd/diagram/output/source/diagram_a_0.png").exists());
+        assert!(
+            !Path::new("target/tests/cmd/diagram/output/source/folder_a/diagram_b_0.png").exists()
+        );
+    }
+
+    #[test]
+    fn test_diagram_generation_with_args_file() {
+        delete_file_or_directory("target/tests/cmd/diagram/args_file".as_ref()).unwrap();
+        let to_path = Path::new("target/tests/cmd/diagram/args_file/source/diagrams_a.puml");
+        create_parent_directory(to_path).unwrap();
+        std::fs::copy("test/source/diagrams_a.puml", to_path).unwrap();
+        std::fs::write(
+            "target/tests/cmd/diagram/args_file/plantuml.args",
+            "# render the diagrams as SVG\n\n  -tsvg  \n",
+        )
+        .unwrap();
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "diagram",
+            "generate",
+            "-s=target/tests/cmd/diagram/args_file/source",
+            "-C=target/tests/cmd/diagram/args_file/cache",
+            "-P=test/plantuml-1.2022.4.jar",
+            "--args-file=target/tests/cmd/diagram/args_file/plantuml.args",
+        ]);
+        execute_diagram_generate(
+            arg_matches
+                .subcommand_matches("diagram")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(Path::new("target/tests/cmd/diagram/args_file/source/diagram_a_0.svg").exists());
+        assert!(!Path::new("target/tests/cmd/diagram/args_file/source/diagram_a_0.png").exists());
+    }
+
+    #[test]
+    fn test_parse_since() {
+        assert_eq!(parse_since("1970-01-01T00:00:01Z").unwrap(), 1_000_000_000);
+        assert_eq!(
+            parse_since("1970-01-01T01:00:01+01:00").unwrap(),
+            1_000_000_000
+        );
+        assert!(parse_since("not a date").is_err());
+    }
+
+    #[test]
+    fn test_diagram_generation_since() {
+        delete_file_or_directory("target/tests/cmd/diagram/since".as_ref()).unwrap();
+        let source_path = Path::new("target/tests/cmd/diagram/since/source/diagrams_a.puml");
+        create_parent_directory(source_path).unwrap();
+        std::fs::copy("test/source/diagrams_a.puml", source_path).unwrap();
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "diagram",
+            "generate",
+            "-s=target/tests/cmd/diagram/since/source",
+            "-C=target/tests/cmd/diagram/since/cache",
+            "-P=test/plantuml-1.2022.4.jar",
+            "--since=2200-01-01T00:00:00Z",
+        ]);
+        execute_diagram_generate(
+            arg_matches
+                .subcommand_matches("diagram")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(!Path::new("target/tests/cmd/diagram/since/source/diagram_a_0.png").exists());
+        assert!(Path::new("target/tests/cmd/diagram/since/cache")
+            .join(DIAGRAM_LAST_GENERATIONS)
+            .exists());
+    }
+
+    fn generate_from(source_directory: &str) {
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "diagram",
+            "generate",
+            &format!("-s={}", source_directory),
+            "-C=target/tests/cmd/diagram/last_generations/cache",
+            "-P=test/plantuml-1.2022.4.jar",
+        ]);
+        execute_diagram_generate(
+            arg_matches
+                .subcommand_matches("diagram")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_last_generations() {
+        let root = Path::new("target/tests/cmd/diagram/last_generations");
+        delete_file_or_directory(root).unwrap();
+        for source_file in &["diagrams_a.puml", "folder_a/diagrams_b.puml"] {
+            let to_path = root.join("source").join(source_file);
+            create_parent_directory(&to_path).unwrap();
+            std::fs::copy(Path::new("test/source").join(source_file), &to_path).unwrap();
+        }
+        // the former global timestamp is the baseline of the sources, then it's replaced
+        let last_gen_path = root.join("cache").join(DIAGRAM_LAST_GENERATION);
+        create_parent_directory(&last_gen_path).unwrap();
+        std::fs::write(&last_gen_path, "0").unwrap();
+
+        // generate the sub-directory first
+        generate_from("target/tests/cmd/diagram/last_generations/source/folder_a");
+        let path_diagram_a_0_png = root.join("source/diagram_a_0.png");
+        let path_diagram_b_0_png = root.join("source/folder_a/diagram_b_0.png");
+        assert!(!path_diagram_a_0_png.exists());
+        assert!(path_diagram_b_0_png.exists());
+        let path_diagram_b_0_png_modified_before =
+            path_diagram_b_0_png.metadata().unwrap().modified().unwrap();
+
+        // then the whole tree, the source of the sub-directory is not rendered again
+        generate_from("target/tests/cmd/diagram/last_generations/source");
+        assert!(path_diagram_a_0_png.exists());
+        assert_eq!(
+            path_diagram_b_0_png.metadata().unwrap().modified().unwrap(),
+            path_diagram_b_0_png_modified_before
+        );
+        let last_generations =
+            read_last_generations(&root.join("cache").join(DIAGRAM_LAST_GENERATIONS)).unwrap();
+        assert_eq!(
+            last_generations.keys().collect::<Vec<_>>(),
+            vec![
+                "target/tests/cmd/diagram/last_generations/source/diagrams_a.puml",
+                "target/tests/cmd/diagram/last_generations/source/folder_a/diagrams_b.puml",
+            ]
+        );
+        assert!(!last_gen_path.exists());
+    }
+
+    #[test]
+    fn test_summary_only() {
+        let root = Path::new("target/tests/cmd/diagram/summary");
+        delete_file_or_directory(root).unwrap();
+        for source_file in &[
+            "diagrams_a.puml",
+            "diagrams_c.plantuml",
+            "folder_a/diagrams_b.puml",
+        ] {
+            let to_path = root.join("source").join(source_file);
+            create_parent_directory(&to_path).unwrap();
+            std::fs::copy(Path::new("test/source").join(source_file), &to_path).unwrap();
+        }
+        // diagrams_a is up-to-date, diagrams_b is stale and diagrams_c has never been rendered
+        let path_diagram_a_src = root.join("source/diagrams_a.puml");
+        let last_generations = LastGenerations::from([
+            (
+                get_source_key(&path_diagram_a_src),
+                get_last_modified(&path_diagram_a_src).unwrap(),
+            ),
+            (
+                get_source_key(&root.join("source/folder_a/diagrams_b.puml")),
+                1,
+            ),
+        ]);
+        let last_gens_path = root.join("cache").join(DIAGRAM_LAST_GENERATIONS);
+        create_parent_directory(&last_gens_path).unwrap();
+        save_last_generations(&last_gens_path, &last_generations).unwrap();
+        // the diagrams of the rendered sources exist
+        for output_file in &[
+            "diagram_a_0.png",
+            "diagram_a_1.png",
+            "folder_a/diagram_b_0.png",
+            "folder_a/diagram_b_1.png",
+        ] {
+            std::fs::write(root.join("source").join(output_file), "").unwrap();
+        }
+
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "diagram",
+            "generate",
+            "-s=target/tests/cmd/diagram/summary/source",
+            "-C=target/tests/cmd/diagram/summary/cache",
+            "-P=target/tests/cmd/diagram/summary/plantuml.jar",
+            "--summary-only",
+        ]);
+        let arg_matches = arg_matches
+            .subcommand_matches("diagram")
+            .unwrap()
+            .subcommand_matches("generate")
+            .unwrap();
+        let config = &Config::default().update_from_args(arg_matches);
+        assert_eq!(
+            summarize_sources(config, &last_generations, 0, None, "png").unwrap(),
+            Summary {
+                up_to_date: 1,
+                stale: 1,
+                missing: 1,
+            }
+        );
+        // PlantUML is neither downloaded nor executed
+        execute_diagram_generate(arg_matches).unwrap();
+        assert!(!root.join("plantuml.jar").exists());
+        assert!(!root.join("source/diagram_c_0.png").exists());
+        // the source missing a diagram is missing too
+        delete_file(&root.join("source/diagram_a_1.png")).unwrap();
+        assert_eq!(
+            summarize_sources(config, &last_generations, 0, None, "png").unwrap(),
+            Summary {
+                up_to_date: 0,
+                stale: 1,
+                missing: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_get_source_key() {
+        assert_eq!(get_source_key(Path::new("./src/./a.puml")), "src/a.puml");
+    }
+
+    #[test]
+    fn test_ignore_file() {
+        let source_directory = Path::new("target/tests/cmd/diagram/ignore/source");
+        delete_file_or_directory(source_directory).unwrap();
+        for (from_file, to_file) in [
+            ("diagrams_a.puml", "diagrams_a.puml"),
+            ("folder_a/diagrams_b.puml", "vendor/diagrams_b.puml"),
+            ("diagrams_c.plantuml", "vendor/folder_a/diagrams_c.plantuml"),
+        ] {
+            let to_path = source_directory.join(to_file);
+            create_parent_directory(&to_path).unwrap();
+            std::fs::copy(Path::new("test/source").join(from_file), &to_path).unwrap();
+        }
+        std::fs::write(
+            source_directory.join(DIAGRAM_IGNORE),
+            "# vendored\nvendor/**\n",
+        )
+        .unwrap();
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "diagram",
+            "generate",
+            "-s=target/tests/cmd/diagram/ignore/source",
+        ]);
+        let config = &Config::default().update_from_args(
+            arg_matches
+                .subcommand_matches("diagram")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        );
+        assert_eq!(
+            get_puml_paths(config).unwrap(),
+            vec![source_directory.join("diagrams_a.puml")]
+        );
+    }
+
+    #[test]
+    fn test_diagram_generation_strict() {
+        let root = Path::new("target/tests/cmd/diagram/strict");
+        delete_file_or_directory(root).unwrap();
+        // PlantUML warns about the sources without diagram but exits successfully
+        let source_path = root.join("source/empty.puml");
+        create_parent_directory(&source_path).unwrap();
+        std::fs::write(&source_path, "' no diagram\n").unwrap();
+        let args = [
+            "plantuml-generator",
+            "-l=Off",
+            "diagram",
+            "generate",
+            "-s=target/tests/cmd/diagram/strict/source",
+            "-C=target/tests/cmd/diagram/strict/cache",
+            "-P=test/plantuml-1.2022.4.jar",
+        ];
+        let arg_matches = build_cli().get_matches_from([&args[..], &["--strict"]].concat());
+        let error = execute_diagram_generate(
+            arg_matches
+                .subcommand_matches("diagram")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "PlantUML reported problems for {}:\nWarning: no image in {}",
+                source_path.display(),
+                source_path.display()
+            )
+        );
+        // the warning is ignored without --strict
+        let arg_matches = build_cli().get_matches_from(args);
+        execute_diagram_generate(
+            arg_matches
+                .subcommand_matches("diagram")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_locked_cache() {
+        let cache_directory = Path::new("target/tests/cmd/diagram/locked/cache");
+        let _lock = CacheLock::acquire(cache_directory, Duration::from_secs(1)).unwrap();
+        let args = [
+            "plantuml-generator",
+            "-l=Off",
+            "diagram",
+            "generate",
+            "-s=target/tests/cmd/diagram/locked/source",
+            "-C=target/tests/cmd/diagram/locked/cache",
+            "-P=test/plantuml-1.2022.4.jar",
+            "--lock-timeout=0",
+        ];
+        let arg_matches = build_cli().get_matches_from(args);
+        let result = execute_diagram_generate(
+            arg_matches
+                .subcommand_matches("diagram")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        );
+        assert!(result.unwrap_err().to_string().contains("unable to lock"));
+        let arg_matches = build_cli().get_matches_from([&args[..], &["--no-lock"]].concat());
+        execute_diagram_generate(
+            arg_matches
+                .subcommand_matches("diagram")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap();
     }
 }
@@ -0,0 +1,117 @@
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use crate::constants::DIAGRAM_FORMATS;
+
+/// The extension of the diagrams rendered without format argument.
+const DEFAULT_EXTENSION: &str = "png";
+
+/// The extension of the rendered diagrams, the last format argument wins, e.g. `-tsvg` or `-svg`.
+pub fn get_output_extension(plantuml_args: &[String]) -> String {
+    let format = plantuml_args.iter().rev().find_map(|arg| {
+        [arg.strip_prefix("-t"), arg.strip_prefix('-')]
+            .into_iter()
+            .flatten()
+            .find(|format| DIAGRAM_FORMATS.contains(format))
+    });
+    match format.and_then(|format| format.split(':').next()) {
+        Some("latex") => "tex".to_string(),
+        Some("txt") => "atxt".to_string(),
+        Some(format) => format.to_string(),
+        None => DEFAULT_EXTENSION.to_string(),
+    }
+}
+
+/// The names of the diagrams of a source, i.e. the name of `@startuml name` when set.
+///
+/// The unnamed diagrams are named after the source, suffixed by their index but for the first one.
+fn parse_diagram_names(content: &str, source_name: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        // e.g. @startuml, @startmindmap or @startgantt
+        .filter_map(|line| line.strip_prefix("@start"))
+        .enumerate()
+        .map(|(index, directive)| {
+            let name = directive
+                .split_once(char::is_whitespace)
+                .map(|(_, name)| name.trim().trim_matches('"'))
+                .filter(|name| !name.is_empty());
+            match (name, index) {
+                (Some(name), _) => name.to_string(),
+                (None, 0) => source_name.to_string(),
+                (None, index) => format!("{}_{:03}", source_name, index),
+            }
+        })
+        .collect()
+}
+
+/// The paths of the diagrams rendered from a source in the output directory.
+pub fn get_expected_outputs(
+    source_path: &Path,
+    output_directory: &Path,
+    extension: &str,
+) -> Result<Vec<PathBuf>> {
+    let content = read_to_string(source_path).map_err(|e| {
+        anyhow::Error::new(e).context(format!("unable to read {}", source_path.display()))
+    })?;
+    let source_name = source_path
+        .file_stem()
+        .map(|v| v.to_string_lossy().to_string())
+        .unwrap_or_default();
+    Ok(parse_diagram_names(&content, &source_name)
+        .into_iter()
+        .map(|name| {
+            // e.g. @startuml diagram.png
+            match name.ends_with(&format!(".{}", extension)) {
+                true => output_directory.join(name),
+                false => output_directory.join(format!("{}.{}", name, extension)),
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_get_output_extension() {
+        assert_eq!(get_output_extension(&[]), "png");
+        assert_eq!(
+            get_output_extension(&["-png".to_string(), "-v".to_string()]),
+            "png"
+        );
+        assert_eq!(
+            get_output_extension(&["-tpng".to_string(), "-tsvg".to_string()]),
+            "svg"
+        );
+        assert_eq!(
+            get_output_extension(&["-tlatex:nopreamble".to_string()]),
+            "tex"
+        );
+    }
+
+    #[test]
+    fn test_get_expected_outputs() {
+        assert_eq!(
+            get_expected_outputs(
+                Path::new("test/source/diagrams_a.puml"),
+                Path::new("output"),
+                "png"
+            )
+            .unwrap(),
+            vec![
+                PathBuf::from("output/diagram_a_0.png"),
+                PathBuf::from("output/diagram_a_1.png"),
+            ]
+        );
+        let content = "@startuml\nA -> B\n@enduml\n@startuml \"named\"\nA -> B\n@enduml\n@startmindmap\n* A\n@endmindmap";
+        assert_eq!(
+            parse_diagram_names(content, "source"),
+            vec!["source", "named", "source_002"]
+        );
+    }
+}
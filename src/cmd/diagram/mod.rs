@@ -1,3 +1,5 @@
 pub use self::generate::execute_diagram_generate;
+pub use self::render::execute_diagram_render;
 
 mod generate;
+mod render;
@@ -0,0 +1,117 @@
+use std::fs::write;
+use std::io;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use anyhow::Result;
+use clap::ArgMatches;
+
+use crate::cmd::diagram::render::config::Config;
+use crate::constants::RENDER_FORMAT;
+use crate::plantuml::create_plantuml;
+use crate::project::ProjectConfig;
+use crate::utils::create_parent_directory;
+
+mod config;
+
+/// Read the source from the file, or from the given input when the path is `-`.
+fn read_source<R: Read>(source_path: &str, mut input: R) -> Result<Vec<u8>> {
+    let mut source = vec![];
+    match source_path {
+        "-" => input
+            .read_to_end(&mut source)
+            .map_err(|e| anyhow::Error::new(e).context("unable to read the standard input"))?,
+        _ => std::fs::File::open(source_path)
+            .and_then(|mut file| file.read_to_end(&mut source))
+            .map_err(|e| {
+                anyhow::Error::new(e).context(format!("unable to read {}", source_path))
+            })?,
+    };
+    Ok(source)
+}
+
+pub fn execute_diagram_render(arg_matches: &ArgMatches) -> Result<()> {
+    // resolve the config
+    let project = &ProjectConfig::load(arg_matches)?;
+    let config = &Config::from_project(project).update_from_args(arg_matches);
+    if log::log_enabled!(log::Level::Info) {
+        log::info!("plantuml_jar: {}", &config.plantuml_jar);
+        log::info!("java_binary: {}", &config.java_binary);
+    }
+
+    // read the source
+    let source_path = arg_matches
+        .get_one::<String>("SOURCE")
+        .ok_or_else(|| anyhow::Error::msg("SOURCE is required".to_string()))?;
+    let source = read_source(source_path, io::stdin())?;
+
+    // render the source
+    let plantuml = create_plantuml(
+        &config.java_binary,
+        &config.plantuml_jar,
+        &config.plantuml_version,
+    )?;
+    plantuml.download()?;
+    let format = arg_matches
+        .get_one::<String>("format")
+        .map(|v| v.as_str())
+        .unwrap_or(RENDER_FORMAT);
+    let diagram = plantuml.pipe(&source, format)?;
+
+    // write the diagram
+    match arg_matches.get_one::<String>("output_path") {
+        None => io::stdout()
+            .write_all(&diagram)
+            .map_err(|e| anyhow::Error::new(e).context("unable to write the standard output")),
+        Some(output_path) => {
+            create_parent_directory(Path::new(output_path))?;
+            write(output_path, &diagram).map_err(|e| {
+                anyhow::Error::new(e).context(format!("unable to write {}", output_path))
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use crate::cli::build_cli;
+    use crate::utils::delete_file;
+
+    use super::*;
+
+    #[test]
+    fn test_render_stdin() {
+        let input: &[u8] = b"@startuml\nA -> B\n@enduml\n";
+        let source = read_source("-", input).unwrap();
+        let plantuml = create_plantuml("java", "test/plantuml-1.2022.4.jar", "1.2022.4").unwrap();
+        let diagram = plantuml.pipe(&source, RENDER_FORMAT).unwrap();
+        assert!(!diagram.is_empty());
+        assert!(String::from_utf8_lossy(&diagram).contains("<svg"));
+    }
+
+    #[test]
+    fn test_render_file() {
+        let output_path = "target/tests/cmd/diagram/render/diagram_a.svg";
+        delete_file(Path::new(output_path)).unwrap();
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "diagram",
+            "render",
+            "test/source/diagrams_a.puml",
+            "-o=target/tests/cmd/diagram/render/diagram_a.svg",
+            "-P=test/plantuml-1.2022.4.jar",
+        ]);
+        execute_diagram_render(
+            arg_matches
+                .subcommand_matches("diagram")
+                .unwrap()
+                .subcommand_matches("render")
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(read_to_string(output_path).unwrap().contains("<svg"));
+    }
+}
@@ -0,0 +1,111 @@
+use std::path::Path;
+
+use clap::ArgMatches;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::get_default_cache_directory;
+use crate::constants::get_default_inkscape_binary;
+use crate::constants::get_default_java_binary;
+use crate::constants::get_default_plantuml_jar;
+use crate::constants::get_default_plantuml_version;
+use crate::project::ProjectConfig;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Config {
+    /// The path to the cache directory.
+    #[serde(default = "get_default_cache_directory")]
+    pub cache_directory: String,
+    /// The PlantUML version.
+    #[serde(default = "get_default_plantuml_version")]
+    pub plantuml_version: String,
+    /// The path to the PlantUML jar.
+    #[serde(default = "get_default_plantuml_jar")]
+    pub plantuml_jar: String,
+    /// The path to the java binary.
+    #[serde(default = "get_default_java_binary")]
+    pub java_binary: String,
+    /// The path to the inkscape binary.
+    #[serde(default = "get_default_inkscape_binary")]
+    pub inkscape_binary: String,
+}
+
+impl Config {
+    pub fn update_from_args(&self, args: &ArgMatches) -> Config {
+        let cache_directory = args
+            .get_one::<String>("cache_directory")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| self.cache_directory.clone());
+
+        let plantuml_version = args
+            .get_one::<String>("plantuml_version")
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| self.plantuml_version.clone());
+
+        // the jar is explicitly configured when it doesn't follow the default location
+        let is_jar_configured = self.plantuml_jar != get_default_plantuml_jar()
+            && args.get_one::<String>("cache_directory").is_none()
+            && args.get_one::<String>("plantuml_version").is_none();
+
+        let plantuml_jar = match args.get_one::<String>("plantuml_jar") {
+            None if is_jar_configured => self.plantuml_jar.clone(),
+            None => Path::new(&cache_directory)
+                .join(format!("plantuml-{}.jar", plantuml_version))
+                .to_string_lossy()
+                .to_string(),
+            Some(v) => v.to_string(),
+        };
+
+        Config {
+            cache_directory,
+            plantuml_version,
+            plantuml_jar,
+            java_binary: args
+                .get_one::<String>("java_binary")
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| self.java_binary.clone()),
+            inkscape_binary: args
+                .get_one::<String>("inkscape_binary")
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| self.inkscape_binary.clone()),
+        }
+    }
+}
+
+impl Config {
+    /// Create the config, the environment takes precedence over the project config file.
+    pub fn from_project(project: &ProjectConfig) -> Config {
+        Config {
+            cache_directory: std::env::var("PLANTUML_GENERATOR_CACHE_DIRECTORY")
+                .ok()
+                .or_else(|| project.cache_directory.clone())
+                .unwrap_or_else(get_default_cache_directory),
+            plantuml_version: std::env::var("PLANTUML_GENERATOR_PLANTUML_VERSION")
+                .ok()
+                .or_else(|| project.plantuml_version.clone())
+                .unwrap_or_else(get_default_plantuml_version),
+            plantuml_jar: std::env::var("PLANTUML_GENERATOR_PLANTUML_JAR")
+                .ok()
+                .or_else(|| project.plantuml_jar.clone())
+                .unwrap_or_else(get_default_plantuml_jar),
+            java_binary: std::env::var("PLANTUML_GENERATOR_JAVA_BINARY").unwrap_or_else(|_| {
+                match &project.java_binary {
+                    Some(v) => v.clone(),
+                    None => match std::env::var("JAVA_HOME") {
+                        Ok(v) => format!("{}/bin/java", v),
+                        Err(_) => get_default_java_binary(),
+                    },
+                }
+            }),
+            inkscape_binary: std::env::var("PLANTUML_GENERATOR_INKSCAPE_BINARY")
+                .ok()
+                .or_else(|| project.inkscape_binary.clone())
+                .unwrap_or_else(get_default_inkscape_binary),
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config::from_project(&ProjectConfig::default())
+    }
+}
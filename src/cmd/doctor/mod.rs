@@ -0,0 +1,188 @@
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::process::{Command, Output};
+
+use anyhow::Result;
+use clap::{crate_version, ArgMatches};
+
+use crate::cmd::doctor::config::Config;
+use crate::project::ProjectConfig;
+
+mod config;
+
+#[derive(Debug)]
+struct Check {
+    /// The name of the checked item.
+    name: String,
+    /// When true, the item is usable.
+    passed: bool,
+    /// The detected version or the reason of the failure.
+    detail: String,
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let status = if self.passed { "pass" } else { "fail" };
+        write!(f, "[{}] {}: {}", status, self.name, self.detail)
+    }
+}
+
+/// Run the command and keep the first line of its output, some tools print their version on stderr.
+fn check_output(name: &str, binary: &str, args: &[&str], is_valid: fn(&Output) -> bool) -> Check {
+    let (passed, detail) = match Command::new(binary).args(args).output() {
+        Err(e) => (false, format!("unable to execute `{}`: {}", binary, e)),
+        Ok(output) if !is_valid(&output) => {
+            (false, format!("`{}` failed with {}", binary, output.status))
+        }
+        Ok(output) => (
+            true,
+            [output.stdout, output.stderr]
+                .iter()
+                .flat_map(|o| {
+                    String::from_utf8_lossy(o)
+                        .lines()
+                        .map(String::from)
+                        .collect::<Vec<_>>()
+                })
+                .find(|l| !l.trim().is_empty())
+                .unwrap_or_else(|| binary.to_string()),
+        ),
+    };
+    Check {
+        name: name.to_string(),
+        passed,
+        detail,
+    }
+}
+
+fn check_command(name: &str, binary: &str, args: &[&str]) -> Check {
+    check_output(name, binary, args, |o| o.status.success())
+}
+
+/// Check the Graphviz binary used by PlantUML to layout most of the diagrams.
+fn check_dot(binary: &str) -> Check {
+    let mut check = check_command("graphviz", binary, &["-V"]);
+    if !check.passed {
+        check.detail = format!(
+            "`{}` not found, PlantUML will fall back to Smetana for the layout",
+            binary
+        );
+    }
+    check
+}
+
+/// PlantUML resolves the Graphviz binary from the `GRAPHVIZ_DOT` environment variable first.
+fn check_graphviz(graphviz_dot: Option<String>) -> Check {
+    match graphviz_dot {
+        Some(v) if !Path::new(&v).exists() => {
+            log::warn!("GRAPHVIZ_DOT points to a missing binary: {}", v);
+            Check {
+                name: "graphviz".to_string(),
+                passed: false,
+                detail: format!(
+                    "GRAPHVIZ_DOT points to the missing binary {}, PlantUML will fall back to Smetana for the layout",
+                    v
+                ),
+            }
+        }
+        Some(v) => check_dot(&v),
+        None => check_dot("dot"),
+    }
+}
+
+fn create_checks(config: &Config) -> Vec<Check> {
+    let plantuml = if Path::new(&config.plantuml_jar).exists() {
+        // PlantUML exits with a non-zero status once the version is printed
+        check_output(
+            "plantuml",
+            &config.java_binary,
+            &["-jar", &config.plantuml_jar, "-version"],
+            |o| String::from_utf8_lossy(&o.stdout).contains("PlantUML version"),
+        )
+    } else {
+        Check {
+            name: "plantuml".to_string(),
+            passed: false,
+            detail: format!(
+                "{} not found, the version {} is downloaded by the generate commands",
+                config.plantuml_jar, config.plantuml_version
+            ),
+        }
+    };
+    vec![
+        Check {
+            name: "plantuml-generator".to_string(),
+            passed: true,
+            detail: crate_version!().to_string(),
+        },
+        plantuml,
+        check_command("java", &config.java_binary, &["-version"]),
+        check_command("inkscape", &config.inkscape_binary, &["--version"]),
+        check_graphviz(std::env::var("GRAPHVIZ_DOT").ok()),
+    ]
+}
+
+pub fn execute_doctor(arg_matches: &ArgMatches) -> Result<()> {
+    let project = &ProjectConfig::load(arg_matches)?;
+    let config = &Config::from_project(project).update_from_args(arg_matches);
+
+    log::info!("plantuml_jar: {}", &config.plantuml_jar);
+    log::info!("plantuml_version: {}", &config.plantuml_version);
+
+    for check in create_checks(config) {
+        println!("{}", check);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cli::build_cli;
+
+    use super::*;
+
+    #[test]
+    fn test_checks() {
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "doctor",
+            "-P=test/plantuml-1.2022.4.jar",
+            "-I=a_missing_inkscape",
+        ]);
+        let config =
+            &Config::default().update_from_args(arg_matches.subcommand_matches("doctor").unwrap());
+        let lines: Vec<String> = create_checks(config)
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        assert_eq!(
+            lines[0],
+            format!("[pass] plantuml-generator: {}", crate_version!())
+        );
+        assert!(lines[1].contains("PlantUML version 1.2022.4"));
+        assert!(lines[3].starts_with("[fail] inkscape: unable to execute `a_missing_inkscape`"));
+    }
+
+    #[test]
+    fn test_graphviz_not_found() {
+        let check = check_dot("a_missing_dot");
+        assert!(!check.passed);
+        assert_eq!(
+            check.to_string(),
+            "[fail] graphviz: `a_missing_dot` not found, PlantUML will fall back to Smetana for the layout"
+        );
+        let check = check_graphviz(Some("target/tests/cmd/doctor/a_missing_dot".to_string()));
+        assert!(!check.passed);
+        assert!(check
+            .detail
+            .starts_with("GRAPHVIZ_DOT points to the missing binary"));
+    }
+
+    #[test]
+    fn test_doctor() {
+        let arg_matches = build_cli().get_matches_from(["plantuml-generator", "-l=Off", "doctor"]);
+        execute_doctor(arg_matches.subcommand_matches("doctor").unwrap()).unwrap();
+    }
+}
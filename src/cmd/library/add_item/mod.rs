@@ -0,0 +1,233 @@
+use std::fs::{read_to_string, write};
+use std::path::Path;
+
+use anyhow::Result;
+use clap::ArgMatches;
+use serde_yaml_ok::{Mapping, Value};
+
+use crate::cmd::library::manifest::library::Library;
+use crate::urn::Urn;
+
+/// The list of a node, e.g. the modules of a package, created when missing.
+fn get_children<'a>(node: &'a mut Value, key: &str) -> Result<&'a mut Vec<Value>> {
+    let mapping = node
+        .as_mapping_mut()
+        .ok_or_else(|| anyhow::Error::msg("the node of the manifest is not a mapping"))?;
+    let children = mapping
+        .entry(Value::from(key))
+        .or_insert_with(|| Value::Sequence(vec![]));
+    if children.is_null() {
+        *children = Value::Sequence(vec![]);
+    }
+    children
+        .as_sequence_mut()
+        .ok_or_else(|| anyhow::Error::msg(format!("`{}` is not a list", key)))
+}
+
+/// The node having the URN, appended when missing.
+fn find_or_push<'a>(nodes: &'a mut Vec<Value>, urn: &str) -> &'a mut Value {
+    let index = match nodes
+        .iter()
+        .position(|node| node.get("urn").and_then(Value::as_str) == Some(urn))
+    {
+        Some(index) => index,
+        None => {
+            let mut node = Mapping::new();
+            node.insert(Value::from("urn"), Value::from(urn));
+            nodes.push(Value::Mapping(node));
+            nodes.len() - 1
+        }
+    };
+    &mut nodes[index]
+}
+
+fn create_item(urn: &Urn, icon: Option<&str>, shapes: &[String]) -> Value {
+    let mut item = Mapping::new();
+    item.insert(Value::from("urn"), Value::from(urn.value.as_str()));
+    if let Some(icon) = icon {
+        let mut source = Mapping::new();
+        source.insert(Value::from("type"), Value::from("Source"));
+        source.insert(Value::from("source"), Value::from(icon));
+        item.insert(Value::from("icon"), Value::Mapping(source));
+    }
+    let elements = shapes
+        .iter()
+        .map(|shape_type| {
+            let mut shape = Mapping::new();
+            shape.insert(Value::from("type"), Value::from(shape_type.as_str()));
+            let mut element = Mapping::new();
+            element.insert(Value::from("shape"), Value::Mapping(shape));
+            Value::Mapping(element)
+        })
+        .collect();
+    item.insert(Value::from("elements"), Value::Sequence(elements));
+    Value::Mapping(item)
+}
+
+/// Append the item to its module, the module and the package are created when missing.
+fn add_item(manifest: &mut Value, urn: &Urn, item: Value) -> Result<()> {
+    let components: Vec<&str> = urn.value.split('/').collect();
+    if components.len() < 3 {
+        return Err(anyhow::Error::msg(format!(
+            "the URN `{}` must have at least a package, a module and an item",
+            urn.value
+        )));
+    }
+    let package_urn = components[0];
+    let module_urn = components[..2].join("/");
+    let package = find_or_push(get_children(manifest, "packages")?, package_urn);
+    let module = find_or_push(get_children(package, "modules")?, &module_urn);
+    get_children(module, "items")?.push(item);
+    Ok(())
+}
+
+pub fn execute_library_add_item(arg_matches: &ArgMatches) -> Result<()> {
+    // resolve the manifest path
+    let manifest_file = arg_matches
+        .get_one::<String>("MANIFEST")
+        .ok_or_else(|| anyhow::Error::msg("MANIFEST is required".to_string()))?;
+    let manifest_path = Path::new(manifest_file);
+
+    let urn = Urn::parse(
+        arg_matches
+            .get_one::<String>("urn")
+            .ok_or_else(|| anyhow::Error::msg("the URN is required".to_string()))?,
+    )?;
+    let icon = arg_matches.get_one::<String>("icon").map(|v| v.as_str());
+    if let Some(icon) = icon {
+        if !Path::new(icon).exists() {
+            return Err(anyhow::Error::msg(format!(
+                "the icon source {} doesn't exist",
+                icon
+            )));
+        }
+    }
+    // the shapes without sprite by default when there is no icon
+    let shapes: Vec<String> = match arg_matches.get_many::<String>("shapes") {
+        Some(shapes) => shapes.cloned().collect(),
+        None if icon.is_some() => vec!["Icon".into(), "IconCard".into(), "IconGroup".into()],
+        None => vec!["Group".into()],
+    };
+
+    // refuse the duplicates
+    let library = Library::load(manifest_path)?;
+    let is_duplicate = library
+        .packages
+        .iter()
+        .flat_map(|package| &package.modules)
+        .flat_map(|module| &module.items)
+        .any(|item| item.urn == urn);
+    if is_duplicate {
+        return Err(anyhow::Error::msg(format!(
+            "the item {} already exists in {}",
+            urn.value, manifest_file
+        )));
+    }
+
+    // the untyped manifest keeps the values as written, i.e. without the defaults
+    let content = read_to_string(manifest_path)
+        .map_err(|e| anyhow::Error::new(e).context(format!("unable to read {}", manifest_file)))?;
+    let mut manifest: Value = serde_yaml_ok::from_str(&content)
+        .map_err(|e| anyhow::Error::new(e).context(format!("unable to parse {}", manifest_file)))?;
+    add_item(&mut manifest, &urn, create_item(&urn, icon, &shapes))?;
+
+    // the manifest must still be valid once updated
+    serde_yaml_ok::from_value::<Library>(manifest.clone()).map_err(|e| {
+        anyhow::Error::new(e).context(format!("unable to add the item {}", urn.value))
+    })?;
+    let content = serde_yaml_ok::to_string(&manifest)
+        .map_err(|e| anyhow::Error::new(e).context("unable to serialize the manifest"))?;
+    write(manifest_path, content)
+        .map_err(|e| anyhow::Error::new(e).context(format!("unable to write {}", manifest_file)))?;
+
+    log::info!("the item {} has been added to {}", urn.value, manifest_file);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cli::build_cli;
+    use crate::cmd::library::generate::execute_library_generate;
+    use crate::utils::{create_parent_directory, delete_file_or_directory};
+
+    use super::*;
+
+    fn add(args: &[&str]) -> Result<()> {
+        let arg_matches = build_cli().get_matches_from(
+            [
+                "plantuml-generator",
+                "-l=Off",
+                "library",
+                "add-item",
+                "target/tests/cmd/library/add_item/library.yaml",
+            ]
+            .iter()
+            .chain(args),
+        );
+        execute_library_add_item(
+            arg_matches
+                .subcommand_matches("library")
+                .unwrap()
+                .subcommand_matches("add-item")
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_add_item() {
+        let root = Path::new("target/tests/cmd/library/add_item");
+        delete_file_or_directory(root).unwrap();
+        let manifest_path = root.join("library.yaml");
+        create_parent_directory(&manifest_path).unwrap();
+        write(
+            &manifest_path,
+            "name: library\nremote_url: distribution\npackages:\n  - urn: PackageA\n",
+        )
+        .unwrap();
+
+        add(&[
+            "--urn=PackageA/ModuleA/ItemA",
+            "--icon=test/original_icon.png",
+        ])
+        .unwrap();
+        add(&["--urn=PackageB/ModuleB/ItemB", "--shape=Group"]).unwrap();
+        let error = add(&["--urn=PackageA/ModuleA/ItemA"]).unwrap_err();
+        assert!(error.to_string().contains("already exists"));
+        assert!(add(&["--urn=PackageA/ItemC"]).is_err());
+
+        let library = Library::load(&manifest_path).unwrap();
+        assert_eq!(library.packages.len(), 2);
+        let item = &library.packages[0].modules[0].items[0];
+        assert_eq!(item.urn.value, "PackageA/ModuleA/ItemA");
+        assert_eq!(item.elements.len(), 3);
+
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "generate",
+            "target/tests/cmd/library/add_item/library.yaml",
+            "-O=target/tests/cmd/library/add_item/distribution",
+            "-C=target/tests/cmd/library/add_item/cache",
+            "-P=test/plantuml-1.2022.4.jar",
+        ]);
+        execute_library_generate(
+            arg_matches
+                .subcommand_matches("library")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(root
+            .join("distribution/PackageA/ModuleA/ItemA.puml")
+            .exists());
+        assert!(root
+            .join("distribution/PackageA/ModuleA/ItemA.png")
+            .exists());
+        assert!(root
+            .join("distribution/PackageB/ModuleB/ItemB.puml")
+            .exists());
+    }
+}
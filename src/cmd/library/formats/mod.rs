@@ -0,0 +1,107 @@
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::Result;
+use clap::ArgMatches;
+
+use crate::cmd::library::formats::config::Config;
+use crate::constants::{DIAGRAM_FORMATS, ICON_FORMATS};
+use crate::project::ProjectConfig;
+
+mod config;
+
+/// The formats of the `-t` options listed by the help of PlantUML, e.g. `-tsvg`.
+fn parse_diagram_formats(help: &str) -> Vec<String> {
+    help.lines()
+        .filter_map(|line| {
+            let (option, description) = line.trim().split_once(char::is_whitespace)?;
+            // e.g. -theme or -timeout are not formats
+            match description.trim().starts_with("To generate") {
+                true => option.strip_prefix("-t").map(String::from),
+                false => None,
+            }
+        })
+        .collect()
+}
+
+/// The output formats supported by the PlantUML jar, the built-in list when the jar cannot be queried.
+fn get_diagram_formats(config: &Config) -> Vec<String> {
+    let default_formats = || DIAGRAM_FORMATS.iter().map(|f| f.to_string()).collect();
+    if !Path::new(&config.plantuml_jar).exists() {
+        log::info!(
+            "{} not found, the formats of PlantUML {} are not checked",
+            config.plantuml_jar,
+            config.plantuml_version
+        );
+        return default_formats();
+    }
+    let formats = match Command::new(&config.java_binary)
+        .args(["-jar", &config.plantuml_jar, "-help"])
+        .output()
+    {
+        Ok(output) => parse_diagram_formats(&String::from_utf8_lossy(&output.stdout)),
+        Err(e) => {
+            log::warn!("unable to execute `{}`: {}", config.java_binary, e);
+            vec![]
+        }
+    };
+    match formats.is_empty() {
+        true => default_formats(),
+        false => formats,
+    }
+}
+
+fn create_lines(config: &Config) -> Vec<String> {
+    vec![
+        format!("icon formats: {}", ICON_FORMATS.join(", ")),
+        format!(
+            "diagram formats: {}",
+            get_diagram_formats(config).join(", ")
+        ),
+    ]
+}
+
+pub fn execute_library_formats(arg_matches: &ArgMatches) -> Result<()> {
+    let project = &ProjectConfig::load(arg_matches)?;
+    let config = &Config::from_project(project).update_from_args(arg_matches);
+
+    for line in create_lines(config) {
+        println!("{}", line);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cli::build_cli;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_diagram_formats() {
+        let help = "    -tpng\t\tTo generate images using PNG format (default)\n    -tlatex:nopreamble\tTo generate images using LaTeX/Tikz format without preamble\n    -theme xxx\t\tTo use a specific theme\n    -testdot\t\tTo test the installation of graphviz";
+        assert_eq!(parse_diagram_formats(help), vec!["png", "latex:nopreamble"]);
+    }
+
+    #[test]
+    fn test_formats() {
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "formats",
+            "-P=test/plantuml-1.2022.4.jar",
+        ]);
+        let arg_matches = arg_matches
+            .subcommand_matches("library")
+            .unwrap()
+            .subcommand_matches("formats")
+            .unwrap();
+        let config = &Config::default().update_from_args(arg_matches);
+        let lines = create_lines(config);
+        assert!(lines[0].starts_with("icon formats: png, svg"));
+        assert!(lines[1].starts_with("diagram formats: png, svg"));
+        execute_library_formats(arg_matches).unwrap();
+    }
+}
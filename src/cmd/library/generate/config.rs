@@ -1,15 +1,23 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 use clap::ArgMatches;
 use serde::{Deserialize, Serialize};
 
+use crate::cmd::library::manifest::library::customization::ResizeFilter;
 use crate::constants::get_default_cache_directory;
 use crate::constants::get_default_inkscape_binary;
 use crate::constants::get_default_java_binary;
+use crate::constants::get_default_jobs;
 use crate::constants::get_default_output_directory;
 use crate::constants::get_default_plantuml_jar;
+use crate::constants::get_default_plantuml_retries;
 use crate::constants::get_default_plantuml_version;
 use crate::constants::get_default_tera_discovery_pattern;
+use crate::constants::BANNER;
+use crate::constants::GENERATED_INDEX;
+use crate::constants::JAVA_CONCURRENCY;
+use crate::project::ProjectConfig;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Config {
@@ -28,12 +36,208 @@ pub struct Config {
     /// The path to the PlantUML jar.
     #[serde(default = "get_default_plantuml_jar")]
     pub plantuml_jar: String,
+    /// The number of retries when PlantUML is killed or cannot be started.
+    #[serde(default = "get_default_plantuml_retries")]
+    pub plantuml_retries: u32,
+    /// The layout engine of PlantUML, e.g. `smetana` to render without Graphviz.
+    #[serde(default)]
+    pub plantuml_layout: Option<String>,
     /// The path to the java binary.
     #[serde(default = "get_default_java_binary")]
     pub java_binary: String,
     /// The inkscape to the java binary.
     #[serde(default = "get_default_inkscape_binary")]
     pub inkscape_binary: String,
+    /// The format of the generated documentation.
+    #[serde(default)]
+    pub documentation_format: DocumentationFormat,
+    /// The path to the index of the generated artifacts.
+    #[serde(default)]
+    pub index_path: Option<String>,
+    /// When true, the artifacts are rendered again even if their fingerprints are unchanged.
+    #[serde(default)]
+    pub force: bool,
+    /// When true, nothing is cleaned and the tasks having all their artifacts are skipped, even if outdated.
+    #[serde(default)]
+    pub only_missing: bool,
+    /// When true, the generation goes on after a failure and reports all the failures at the end.
+    #[serde(default)]
+    pub keep_going: bool,
+    /// When set, the tasks of the URNs having more components are skipped.
+    #[serde(default)]
+    pub max_depth: Option<usize>,
+    /// When set, only the items of the family are generated, along with their packages and modules.
+    #[serde(default)]
+    pub family: Option<String>,
+    /// When set, the SVG icons are optimized with the built-in optimizer or an svgo-like binary.
+    #[serde(default)]
+    pub svg_optimizer: Option<String>,
+    /// When set, the PNG icons are optimized losslessly with the built-in optimizer or an oxipng-like binary.
+    #[serde(default)]
+    pub png_optimizer: Option<String>,
+    /// When true, the rendered artifacts are not restored from the render cache.
+    #[serde(default)]
+    pub no_render_cache: bool,
+    /// The number of jobs executing the tasks of a phase in parallel.
+    #[serde(default = "get_default_jobs")]
+    pub jobs: usize,
+    /// The maximum number of Java processes running at once, by default the jobs up to 4.
+    #[serde(default)]
+    pub java_concurrency: Option<usize>,
+    /// When true, the utilization of the jobs is reported at the end of the generation.
+    #[serde(default)]
+    pub concurrency_report: bool,
+    /// When true, only the documentation is generated and PlantUML is not used at all.
+    #[serde(default)]
+    pub only_docs: bool,
+    /// The variants of the snippets to generate.
+    #[serde(default)]
+    pub snippet_mode: SnippetModes,
+    /// The assets to generate, e.g. only the remote ones to publish the library on a CDN.
+    #[serde(default)]
+    pub profile: RenderingProfile,
+    /// When true, the documentation lists the URNs in the manifest order instead of sorting them.
+    #[serde(default)]
+    pub preserve_order: bool,
+    /// When true, the sources of the local snippets are inlined in the item documentation.
+    #[serde(default)]
+    pub inline_snippets: bool,
+    /// When true, the generation fails when the URNs select no package.
+    #[serde(default)]
+    pub fail_on_empty: bool,
+    /// When true, only the items matching exactly the URNs are generated, along with the bootstraps.
+    #[serde(default)]
+    pub exact_urn: bool,
+    /// When true, only the sources already on disk are rendered, the other phases are skipped.
+    #[serde(default)]
+    pub render_only: bool,
+    /// When true, the units go through the first phases one after the other, instead of phase by phase.
+    #[serde(default)]
+    pub experimental_pipeline: bool,
+    /// The banner of the generated text artifacts, by default the built-in one.
+    #[serde(default)]
+    pub banner: Option<String>,
+    /// When true, the generated text artifacts have no banner.
+    #[serde(default)]
+    pub no_banner: bool,
+    /// When true, the local paths referenced by the generated artifacts are checked after the generation.
+    #[serde(default)]
+    pub verify_output: bool,
+    /// When set, the Unix mode of the generated files and directories, e.g. `0o644`.
+    #[serde(default)]
+    pub output_mode: Option<u32>,
+    /// When set, the path to the JSON report of the generation, e.g. for the CI.
+    #[serde(default)]
+    pub report_path: Option<String>,
+    /// When true, the intermediate sprite icons are copied in the debug directory of the output.
+    #[serde(default)]
+    pub keep_intermediates: bool,
+    /// When set, the filter resizing the raster icons, instead of the one of the manifest.
+    #[serde(default)]
+    pub resize_filter: Option<ResizeFilter>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum DocumentationFormat {
+    #[default]
+    Markdown,
+    AsciiDoc,
+}
+
+impl FromStr for DocumentationFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "markdown" => Ok(DocumentationFormat::Markdown),
+            "asciidoc" => Ok(DocumentationFormat::AsciiDoc),
+            _ => Err(anyhow::Error::msg(format!(
+                "unable to find a documentation format for {}",
+                s
+            ))),
+        }
+    }
+}
+
+impl DocumentationFormat {
+    /// The extension of the generated documentation files.
+    pub fn get_extension(&self) -> String {
+        match self {
+            DocumentationFormat::Markdown => "md".to_string(),
+            DocumentationFormat::AsciiDoc => "adoc".to_string(),
+        }
+    }
+    /// Swap the built-in Markdown template for its AsciiDoc counterpart, custom templates are kept.
+    pub fn resolve_template(&self, template: &str, markdown: &str, asciidoc: &str) -> String {
+        match self {
+            DocumentationFormat::AsciiDoc if template == markdown => asciidoc.to_string(),
+            _ => template.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum SnippetModes {
+    #[default]
+    Both,
+    Local,
+    Remote,
+}
+
+impl FromStr for SnippetModes {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "both" => Ok(SnippetModes::Both),
+            "local" => Ok(SnippetModes::Local),
+            "remote" => Ok(SnippetModes::Remote),
+            _ => Err(anyhow::Error::msg(format!(
+                "unable to find a snippet mode for {}",
+                s
+            ))),
+        }
+    }
+}
+
+impl SnippetModes {
+    /// True when the snippets including the local sprites are generated.
+    pub fn has_local(&self) -> bool {
+        matches!(self, SnippetModes::Both | SnippetModes::Local)
+    }
+    /// True when the snippets including the remote sprites are generated.
+    pub fn has_remote(&self) -> bool {
+        matches!(self, SnippetModes::Both | SnippetModes::Remote)
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum RenderingProfile {
+    #[default]
+    Full,
+    Remote,
+}
+
+impl FromStr for RenderingProfile {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "full" => Ok(RenderingProfile::Full),
+            "remote" => Ok(RenderingProfile::Remote),
+            _ => Err(anyhow::Error::msg(format!(
+                "unable to find a rendering profile for {}",
+                s
+            ))),
+        }
+    }
+}
+
+impl RenderingProfile {
+    /// True when the local assets, i.e. the icons, the sprites and the local snippets, are generated.
+    pub fn has_local_assets(&self) -> bool {
+        matches!(self, RenderingProfile::Full)
+    }
 }
 
 #[cfg(test)]
@@ -59,8 +263,39 @@ impl Config {
             tera_discovery_pattern: self.tera_discovery_pattern.clone(),
             plantuml_version: self.plantuml_version.clone(),
             plantuml_jar: self.plantuml_jar.clone(),
+            plantuml_retries: self.plantuml_retries,
+            plantuml_layout: self.plantuml_layout.clone(),
             java_binary: self.java_binary.clone(),
             inkscape_binary: self.inkscape_binary.clone(),
+            documentation_format: self.documentation_format.clone(),
+            index_path: self.index_path.clone(),
+            force: self.force,
+            only_missing: self.only_missing,
+            keep_going: self.keep_going,
+            max_depth: self.max_depth,
+            family: self.family.clone(),
+            svg_optimizer: self.svg_optimizer.clone(),
+            png_optimizer: self.png_optimizer.clone(),
+            no_render_cache: self.no_render_cache,
+            jobs: self.jobs,
+            java_concurrency: self.java_concurrency,
+            concurrency_report: self.concurrency_report,
+            only_docs: self.only_docs,
+            snippet_mode: self.snippet_mode.clone(),
+            profile: self.profile.clone(),
+            preserve_order: self.preserve_order,
+            inline_snippets: self.inline_snippets,
+            fail_on_empty: self.fail_on_empty,
+            exact_urn: self.exact_urn,
+            render_only: self.render_only,
+            experimental_pipeline: self.experimental_pipeline,
+            banner: self.banner.clone(),
+            no_banner: self.no_banner,
+            verify_output: self.verify_output,
+            output_mode: self.output_mode,
+            report_path: self.report_path.clone(),
+            keep_intermediates: self.keep_intermediates,
+            resize_filter: self.resize_filter,
         }
     }
     pub fn update_plantuml_jar(&self, plantuml_jar: String) -> Config {
@@ -70,13 +305,64 @@ impl Config {
             tera_discovery_pattern: self.tera_discovery_pattern.clone(),
             plantuml_version: self.plantuml_version.clone(),
             plantuml_jar,
+            plantuml_retries: self.plantuml_retries,
+            plantuml_layout: self.plantuml_layout.clone(),
             java_binary: self.java_binary.clone(),
             inkscape_binary: self.inkscape_binary.clone(),
+            documentation_format: self.documentation_format.clone(),
+            index_path: self.index_path.clone(),
+            force: self.force,
+            only_missing: self.only_missing,
+            keep_going: self.keep_going,
+            max_depth: self.max_depth,
+            family: self.family.clone(),
+            svg_optimizer: self.svg_optimizer.clone(),
+            png_optimizer: self.png_optimizer.clone(),
+            no_render_cache: self.no_render_cache,
+            jobs: self.jobs,
+            java_concurrency: self.java_concurrency,
+            concurrency_report: self.concurrency_report,
+            only_docs: self.only_docs,
+            snippet_mode: self.snippet_mode.clone(),
+            profile: self.profile.clone(),
+            preserve_order: self.preserve_order,
+            inline_snippets: self.inline_snippets,
+            fail_on_empty: self.fail_on_empty,
+            exact_urn: self.exact_urn,
+            render_only: self.render_only,
+            experimental_pipeline: self.experimental_pipeline,
+            banner: self.banner.clone(),
+            no_banner: self.no_banner,
+            verify_output: self.verify_output,
+            output_mode: self.output_mode,
+            report_path: self.report_path.clone(),
+            keep_intermediates: self.keep_intermediates,
+            resize_filter: self.resize_filter,
         }
     }
 }
 
 impl Config {
+    /// The index is written in the output directory unless a path is given.
+    pub fn get_index_path(&self) -> PathBuf {
+        match &self.index_path {
+            Some(v) => PathBuf::from(v),
+            None => Path::new(&self.output_directory).join(GENERATED_INDEX),
+        }
+    }
+    /// The maximum number of Java processes running at once, the JVMs are heavier than the jobs.
+    pub fn get_java_concurrency(&self) -> usize {
+        self.java_concurrency
+            .unwrap_or_else(|| self.jobs.min(JAVA_CONCURRENCY))
+            .max(1)
+    }
+    /// The banner of the generated text artifacts, none when disabled.
+    pub fn get_banner(&self) -> Option<&str> {
+        match self.no_banner {
+            true => None,
+            false => Some(self.banner.as_deref().unwrap_or(BANNER)),
+        }
+    }
     pub fn update_from_args(&self, args: &ArgMatches) -> Config {
         let cache_directory = args
             .get_one::<String>("cache_directory")
@@ -91,7 +377,13 @@ impl Config {
             Some(v) => v,
         };
 
+        // the jar is explicitly configured when it doesn't follow the default location
+        let is_jar_configured = self.plantuml_jar != get_default_plantuml_jar()
+            && args.get_one::<String>("cache_directory").is_none()
+            && args.get_one::<String>("plantuml_version").is_none();
+
         let plantuml_jar = match args.get_one::<String>("plantuml_jar") {
+            None if is_jar_configured => self.plantuml_jar.clone(),
             None => match Path::new(&cache_directory)
                 .join(format!("plantuml-{}.jar", plantuml_version))
                 .as_path()
@@ -112,6 +404,14 @@ impl Config {
             tera_discovery_pattern: self.tera_discovery_pattern.clone(),
             plantuml_version,
             plantuml_jar,
+            plantuml_retries: args
+                .get_one::<u32>("plantuml_retries")
+                .copied()
+                .unwrap_or(self.plantuml_retries),
+            plantuml_layout: args
+                .get_one::<String>("plantuml_layout")
+                .map(|v| v.to_string())
+                .or_else(|| self.plantuml_layout.clone()),
             java_binary: args
                 .get_one::<String>("java_binary")
                 .map(|v| v.to_string())
@@ -120,32 +420,160 @@ impl Config {
                 .get_one::<String>("inkscape_binary")
                 .map(|v| v.to_string())
                 .unwrap_or_else(|| self.inkscape_binary.clone()),
+            documentation_format: args
+                .get_one::<String>("documentation_format")
+                .and_then(|v| DocumentationFormat::from_str(v).ok())
+                .unwrap_or_else(|| self.documentation_format.clone()),
+            index_path: args
+                .get_one::<String>("index_path")
+                .map(|v| v.to_string())
+                .or_else(|| self.index_path.clone()),
+            force: args.get_flag("do_force") || self.force,
+            only_missing: args.get_flag("do_only_missing") || self.only_missing,
+            keep_going: args.get_flag("do_keep_going") || self.keep_going,
+            max_depth: args
+                .get_one::<u32>("max_depth")
+                .map(|v| *v as usize)
+                .or(self.max_depth),
+            family: args
+                .get_one::<String>("family")
+                .map(|v| v.to_string())
+                .or_else(|| self.family.clone()),
+            svg_optimizer: args
+                .get_one::<String>("svg_optimizer")
+                .map(|v| v.to_string())
+                .or_else(|| self.svg_optimizer.clone()),
+            png_optimizer: args
+                .get_one::<String>("png_optimizer")
+                .map(|v| v.to_string())
+                .or_else(|| self.png_optimizer.clone()),
+            no_render_cache: args.get_flag("do_no_render_cache") || self.no_render_cache,
+            jobs: args
+                .get_one::<u32>("jobs")
+                .map(|v| *v as usize)
+                .unwrap_or(self.jobs),
+            java_concurrency: args
+                .get_one::<u32>("java_concurrency")
+                .map(|v| *v as usize)
+                .or(self.java_concurrency),
+            concurrency_report: args.get_flag("do_concurrency_report") || self.concurrency_report,
+            only_docs: args.get_flag("do_only_docs") || self.only_docs,
+            snippet_mode: args
+                .get_one::<String>("snippet_mode")
+                .and_then(|v| SnippetModes::from_str(v).ok())
+                .unwrap_or_else(|| self.snippet_mode.clone()),
+            profile: args
+                .get_one::<String>("profile")
+                .and_then(|v| RenderingProfile::from_str(v).ok())
+                .unwrap_or_else(|| self.profile.clone()),
+            preserve_order: args.get_flag("do_preserve_order") || self.preserve_order,
+            inline_snippets: args.get_flag("do_inline_snippets") || self.inline_snippets,
+            fail_on_empty: args.get_flag("do_fail_on_empty") || self.fail_on_empty,
+            exact_urn: args.get_flag("do_exact_urn") || self.exact_urn,
+            render_only: args.get_flag("do_render_only") || self.render_only,
+            experimental_pipeline: args.get_flag("do_experimental_pipeline")
+                || self.experimental_pipeline,
+            banner: args
+                .get_one::<String>("banner")
+                .map(|v| v.to_string())
+                .or_else(|| self.banner.clone()),
+            no_banner: args.get_flag("do_no_banner") || self.no_banner,
+            verify_output: args.get_flag("do_verify_output") || self.verify_output,
+            output_mode: args
+                .get_one::<u32>("output_mode")
+                .copied()
+                .or(self.output_mode),
+            report_path: args
+                .get_one::<String>("report_path")
+                .map(|v| v.to_string())
+                .or_else(|| self.report_path.clone()),
+            keep_intermediates: args.get_flag("do_keep_intermediates") || self.keep_intermediates,
+            resize_filter: args
+                .get_one::<String>("resize_filter")
+                .and_then(|v| ResizeFilter::from_str(v).ok())
+                .or(self.resize_filter),
         }
     }
 }
 
-impl Default for Config {
-    fn default() -> Self {
+impl Config {
+    /// Create the config, the environment takes precedence over the project config file.
+    pub fn from_project(project: &ProjectConfig) -> Config {
+        Config::from_project_and_env(project, &|name| std::env::var(name).ok())
+    }
+    /// Create the config from the project config file and the given environment variables.
+    pub fn from_project_and_env(
+        project: &ProjectConfig,
+        get_var: &dyn Fn(&str) -> Option<String>,
+    ) -> Config {
         Config {
-            output_directory: std::env::var("PLANTUML_GENERATOR_OUTPUT_DIRECTORY")
-                .unwrap_or_else(|_| get_default_output_directory()),
-            cache_directory: std::env::var("PLANTUML_GENERATOR_CACHE_DIRECTORY")
-                .unwrap_or_else(|_| get_default_cache_directory()),
-            tera_discovery_pattern: std::env::var("PLANTUML_GENERATOR_DISCOVERY_PATTERN")
-                .unwrap_or_else(|_| get_default_tera_discovery_pattern()),
-            plantuml_version: std::env::var("PLANTUML_GENERATOR_PLANTUML_VERSION")
-                .unwrap_or_else(|_| get_default_plantuml_version()),
-            plantuml_jar: std::env::var("PLANTUML_GENERATOR_PLANTUML_JAR")
-                .unwrap_or_else(|_| get_default_plantuml_jar()),
-            java_binary: match std::env::var("PLANTUML_GENERATOR_JAVA_BINARY") {
-                Ok(v) => v,
-                Err(_) => match std::env::var("JAVA_HOME") {
-                    Ok(v) => format!("{}/bin/java", v),
-                    Err(_) => get_default_java_binary(),
+            output_directory: get_var("PLANTUML_GENERATOR_OUTPUT_DIRECTORY")
+                .or_else(|| project.output_directory.clone())
+                .unwrap_or_else(get_default_output_directory),
+            cache_directory: get_var("PLANTUML_GENERATOR_CACHE_DIRECTORY")
+                .or_else(|| project.cache_directory.clone())
+                .unwrap_or_else(get_default_cache_directory),
+            tera_discovery_pattern: get_var("PLANTUML_GENERATOR_DISCOVERY_PATTERN")
+                .unwrap_or_else(get_default_tera_discovery_pattern),
+            plantuml_version: get_var("PLANTUML_GENERATOR_PLANTUML_VERSION")
+                .or_else(|| project.plantuml_version.clone())
+                .unwrap_or_else(get_default_plantuml_version),
+            plantuml_jar: get_var("PLANTUML_GENERATOR_PLANTUML_JAR")
+                .or_else(|| project.plantuml_jar.clone())
+                .unwrap_or_else(get_default_plantuml_jar),
+            plantuml_retries: get_default_plantuml_retries(),
+            plantuml_layout: None,
+            java_binary: match get_var("PLANTUML_GENERATOR_JAVA_BINARY") {
+                Some(v) => v,
+                None => match &project.java_binary {
+                    Some(v) => v.clone(),
+                    None => match get_var("JAVA_HOME") {
+                        Some(v) => format!("{}/bin/java", v),
+                        None => get_default_java_binary(),
+                    },
                 },
             },
-            inkscape_binary: std::env::var("PLANTUML_GENERATOR_INKSCAPE_BINARY")
-                .unwrap_or_else(|_| get_default_inkscape_binary()),
+            inkscape_binary: get_var("PLANTUML_GENERATOR_INKSCAPE_BINARY")
+                .or_else(|| project.inkscape_binary.clone())
+                .unwrap_or_else(get_default_inkscape_binary),
+            documentation_format: DocumentationFormat::default(),
+            index_path: None,
+            force: false,
+            only_missing: false,
+            keep_going: false,
+            max_depth: None,
+            family: None,
+            svg_optimizer: None,
+            png_optimizer: None,
+            no_render_cache: false,
+            jobs: get_var("PLANTUML_GENERATOR_JOBS")
+                .and_then(|v| v.parse().ok())
+                .or(project.threads)
+                .unwrap_or_else(get_default_jobs),
+            java_concurrency: None,
+            concurrency_report: false,
+            only_docs: false,
+            snippet_mode: SnippetModes::default(),
+            profile: RenderingProfile::default(),
+            preserve_order: false,
+            inline_snippets: false,
+            fail_on_empty: false,
+            exact_urn: false,
+            render_only: false,
+            experimental_pipeline: false,
+            banner: None,
+            no_banner: false,
+            verify_output: false,
+            output_mode: None,
+            report_path: None,
+            keep_intermediates: false,
+            resize_filter: None,
         }
     }
 }
+
+impl Default for Config {
+    fn default() -> Self {
+        Config::from_project(&ProjectConfig::default())
+    }
+}
@@ -0,0 +1,152 @@
+use std::fs::{read_to_string, write};
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::Serialize;
+use tera::Tera;
+
+use crate::cmd::library::generate::sink::OutputSink;
+use crate::cmd::library::generate::templates::TEMPLATES;
+use crate::utils::{delete_file, read_file};
+
+/// The extension of the files storing the fingerprints, next to the rendered artifacts.
+const FINGERPRINT_EXTENSION: &str = "hash";
+
+/// Hash the given bytes with FNV-1a, the result is stable across runs and platforms.
+pub fn hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Resolve the source of a template, from its file or from the built-in templates.
+fn get_template_source(tera: &Tera, template: &str) -> String {
+    match tera
+        .get_template(template)
+        .ok()
+        .and_then(|t| t.path.clone())
+    {
+        Some(path) => read_to_string(path).unwrap_or_default(),
+        None => TEMPLATES
+            .iter()
+            .find(|(name, _)| *name == template)
+            .map(|(_, source)| source.to_string())
+            .unwrap_or_default(),
+    }
+}
+
+/// Compute the fingerprint of an artifact from the data of its task and the source of its template.
+pub fn compute_fingerprint<T: Serialize>(data: &T, tera: &Tera, template: &str) -> Result<String> {
+    // the value is used as an intermediate to get a stable order of the maps' keys
+    let value = serde_json::to_value(data)
+        .map_err(|e| anyhow::Error::new(e).context("unable to serialize the fingerprint data"))?;
+    let content = format!("{}\n{}", value, get_template_source(tera, template));
+    Ok(format!("{:016x}", hash(content.as_bytes())))
+}
+
+/// The path of the fingerprint of a rendered artifact.
+pub fn get_fingerprint_path(destination_path: &Path) -> PathBuf {
+    let mut path = destination_path.as_os_str().to_os_string();
+    path.push(".");
+    path.push(FINGERPRINT_EXTENSION);
+    PathBuf::from(path)
+}
+
+/// True when the artifact is missing or has been rendered from another fingerprint.
+pub fn is_outdated(destination_path: &Path, fingerprint: &str) -> bool {
+    !destination_path.exists()
+        || read_file(&get_fingerprint_path(destination_path))
+            .ok()
+            .flatten()
+            .as_deref()
+            != Some(fingerprint)
+}
+
+/// Store the fingerprint of a rendered artifact.
+pub fn write_fingerprint(destination_path: &Path, fingerprint: &str) -> Result<()> {
+    let fingerprint_path = get_fingerprint_path(destination_path);
+    write(&fingerprint_path, fingerprint).map_err(|e| {
+        anyhow::Error::new(e).context(format!("unable to write {}", fingerprint_path.display()))
+    })
+}
+
+/// True when the artifact of the sink is missing or has been rendered from another fingerprint.
+pub fn is_outdated_in(sink: &dyn OutputSink, relative_path: &Path, fingerprint: &str) -> bool {
+    !matches!(sink.read(relative_path), Ok(Some(_)))
+        || sink
+            .read(&get_fingerprint_path(relative_path))
+            .ok()
+            .flatten()
+            .as_deref()
+            != Some(fingerprint.as_bytes())
+}
+
+/// Store the fingerprint of an artifact of the sink.
+pub fn write_fingerprint_in(
+    sink: &dyn OutputSink,
+    relative_path: &Path,
+    fingerprint: &str,
+) -> Result<()> {
+    sink.write(&get_fingerprint_path(relative_path), fingerprint.as_bytes())
+}
+
+/// Forget the fingerprint of an artifact, so that it is rendered again.
+pub fn delete_fingerprint(destination_path: &Path) -> Result<()> {
+    delete_file(&get_fingerprint_path(destination_path))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cmd::library::generate::sink::MemorySink;
+    use crate::tera::create_tera;
+    use crate::utils::{create_directory, delete_file_or_directory};
+
+    use super::*;
+
+    #[test]
+    fn test_fingerprint() {
+        let tera = &create_tera(TEMPLATES.to_vec(), None).unwrap();
+        let template = TEMPLATES[0].0;
+        let fingerprint_a = compute_fingerprint(&("a", 1), tera, template).unwrap();
+        assert_eq!(
+            fingerprint_a,
+            compute_fingerprint(&("a", 1), tera, template).unwrap()
+        );
+        assert_ne!(
+            fingerprint_a,
+            compute_fingerprint(&("a", 2), tera, template).unwrap()
+        );
+        assert_ne!(
+            fingerprint_a,
+            compute_fingerprint(&("a", 1), tera, TEMPLATES[1].0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_outdated() {
+        let directory = Path::new("target/tests/fingerprint");
+        delete_file_or_directory(directory).unwrap();
+        create_directory(directory).unwrap();
+        let destination_path = directory.join("artifact.puml");
+        assert!(is_outdated(&destination_path, "a"));
+        write(&destination_path, "content").unwrap();
+        assert!(is_outdated(&destination_path, "a"));
+        write_fingerprint(&destination_path, "a").unwrap();
+        assert!(!is_outdated(&destination_path, "a"));
+        assert!(is_outdated(&destination_path, "b"));
+        delete_fingerprint(&destination_path).unwrap();
+        assert!(is_outdated(&destination_path, "a"));
+    }
+
+    #[test]
+    fn test_outdated_in() {
+        let sink = &MemorySink::default();
+        let relative_path = Path::new("artifact.puml");
+        assert!(is_outdated_in(sink, relative_path, "a"));
+        sink.write(relative_path, b"content").unwrap();
+        assert!(is_outdated_in(sink, relative_path, "a"));
+        write_fingerprint_in(sink, relative_path, "a").unwrap();
+        assert!(!is_outdated_in(sink, relative_path, "a"));
+        assert!(is_outdated_in(sink, relative_path, "b"));
+    }
+}
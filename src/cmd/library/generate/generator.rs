@@ -1,22 +1,294 @@
-use std::fmt::{Debug, Formatter};
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::{Debug, Display, Formatter};
+use std::fs::write;
+use std::iter::once;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
 
 use anyhow::Result;
-use tera::Tera;
+use serde::{Deserialize, Serialize};
+use tera::{Context, Tera};
 
 use crate::cmd::library::generate::config::Config;
-use crate::cmd::library::generate::task::{CleanupScope, Task};
+use crate::cmd::library::generate::fingerprint::{delete_fingerprint, get_fingerprint_path};
+use crate::cmd::library::generate::meta::Meta;
+use crate::cmd::library::generate::task::{
+    CleanupScope, OutputKind, Task, TaskError, PHASE_CLEANUP, PHASE_CREATE_RESOURCES,
+    PHASE_RENDER_ATOMIC_TEMPLATES, PHASE_RENDER_COMPOSED_TEMPLATES, PHASE_RENDER_SOURCES,
+};
 use crate::cmd::library::generate::tasks::item::parse_item;
 use crate::cmd::library::generate::tasks::library::parse_library;
 use crate::cmd::library::generate::tasks::module::parse_module;
 use crate::cmd::library::generate::tasks::package::parse_package;
+use crate::cmd::library::manifest::icon::Icon;
+use crate::cmd::library::manifest::item::Item;
 use crate::cmd::library::manifest::library::Library;
+use crate::cmd::library::manifest::package::Package;
 use crate::counter::Counter;
 use crate::plantuml::PlantUML;
 use crate::urn::Urn;
+use crate::utils::{
+    check_contained_path, check_writable_path, create_parent_directory, delete_file, read_file,
+    set_mode,
+};
+
+/// The generated artifacts of a work unit, relative to the output directory when possible.
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct IndexEntry {
+    /// The URN of the package, the module or the item, none for the library.
+    urn: Option<String>,
+    /// The PlantUML sources, e.g. `<item>.puml`.
+    #[serde(default)]
+    sources: Vec<String>,
+    /// The icons of the items.
+    #[serde(default)]
+    icons: Vec<String>,
+    /// The debug icons of the sprites.
+    #[serde(default)]
+    sprites: Vec<String>,
+    /// The sources and the images of the snippets.
+    #[serde(default)]
+    snippets: Vec<String>,
+    /// The documentation, e.g. `<item>.md`.
+    #[serde(default)]
+    documentations: Vec<String>,
+    /// The sources and the images of the examples.
+    #[serde(default)]
+    examples: Vec<String>,
+    /// The artifacts of the other kinds.
+    #[serde(default)]
+    others: Vec<String>,
+}
+
+impl IndexEntry {
+    fn push(&mut self, kind: OutputKind, path: String) {
+        match kind {
+            OutputKind::Source => &mut self.sources,
+            OutputKind::Icon => &mut self.icons,
+            OutputKind::Sprite => &mut self.sprites,
+            OutputKind::Snippet => &mut self.snippets,
+            OutputKind::Documentation => &mut self.documentations,
+            OutputKind::Example => &mut self.examples,
+            OutputKind::Other => &mut self.others,
+        }
+        .push(path);
+    }
+    /// Forget the artifacts which have been deleted since the index was written.
+    fn retain_existing(&mut self, output_directory: &Path) {
+        for paths in [
+            &mut self.sources,
+            &mut self.icons,
+            &mut self.sprites,
+            &mut self.snippets,
+            &mut self.documentations,
+            &mut self.examples,
+            &mut self.others,
+        ] {
+            paths.retain(|path| output_directory.join(path).exists());
+        }
+    }
+    fn is_empty(&self) -> bool {
+        [
+            &self.sources,
+            &self.icons,
+            &self.sprites,
+            &self.snippets,
+            &self.documentations,
+            &self.examples,
+            &self.others,
+        ]
+        .iter()
+        .all(|paths| paths.is_empty())
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct GeneratedIndex {
+    /// The generated artifacts grouped by URN, the library comes first.
+    entries: Vec<IndexEntry>,
+}
+
+/// The phases of the generation, in the order of their execution.
+pub const PHASES: [&str; 5] = [
+    PHASE_CLEANUP,
+    PHASE_CREATE_RESOURCES,
+    PHASE_RENDER_ATOMIC_TEMPLATES,
+    PHASE_RENDER_COMPOSED_TEMPLATES,
+    PHASE_RENDER_SOURCES,
+];
+
+/// A failed task, as written in the report of the generation.
+#[derive(Debug, Serialize)]
+struct TaskFailure {
+    /// The index of the task.
+    #[serde(skip)]
+    index: usize,
+    /// The description of the task, e.g. `ItemIconTask(Package/Module/Item)`.
+    task: String,
+    /// The URN of the task, none for the tasks of the library.
+    urn: Option<String>,
+    /// The phase of the failure.
+    phase: String,
+    /// The error, with its causes.
+    message: String,
+}
+
+/// The failed tasks, collected when the generation keeps going.
+#[derive(Debug, Default)]
+struct Failures {
+    /// The indexes of the failed tasks.
+    indexes: HashSet<usize>,
+    /// The failures, in the order of their occurrence.
+    errors: Vec<TaskError>,
+    /// The failures of the report, including the one stopping the generation.
+    reported: Vec<TaskFailure>,
+}
+
+impl Failures {
+    fn add(&mut self, index: usize, urn: Option<&Urn>, error: TaskError) {
+        log::error!("{}: {:#}", error, error.cause);
+        self.record(index, urn, &error);
+        self.indexes.insert(index);
+        self.errors.push(error);
+    }
+    /// Record the failure for the report only, e.g. when the generation doesn't keep going.
+    fn record(&mut self, index: usize, urn: Option<&Urn>, error: &TaskError) {
+        self.reported.push(TaskFailure {
+            index,
+            task: error.task.clone(),
+            urn: urn.map(|urn| urn.value.clone()),
+            phase: error.phase.clone(),
+            message: format!("{:#}", error.cause),
+        });
+    }
+    fn contains(&self, index: usize) -> bool {
+        self.indexes.contains(&index)
+    }
+    fn to_result(&self) -> Result<()> {
+        if self.errors.is_empty() {
+            return Ok(());
+        }
+        // the failures are grouped by phase, the phases keep the order of their execution
+        let mut phases: Vec<&str> = vec![];
+        for error in &self.errors {
+            if !phases.contains(&error.phase.as_str()) {
+                phases.push(&error.phase);
+            }
+        }
+        Err(anyhow::Error::msg(format!(
+            "the generation failed for {} task(s):\n{}",
+            self.errors.len(),
+            phases
+                .iter()
+                .map(|phase| {
+                    let reports = self
+                        .errors
+                        .iter()
+                        .filter(|error| error.phase == *phase)
+                        .map(|error| format!("- {}: {:#}", error.task, error.cause))
+                        .collect::<Vec<String>>()
+                        .join("\n");
+                    format!("{}:\n{}", phase, reports)
+                })
+                .collect::<Vec<String>>()
+                .join("\n")
+        )))
+    }
+}
+
+/// The utilization of a job during a phase.
+#[derive(Debug, Default)]
+struct JobReport {
+    /// The number of executed work units.
+    units: usize,
+    /// The time spent to execute the work units.
+    busy: Duration,
+}
+
+/// The utilization of the jobs during a phase, recorded when the concurrency report is enabled.
+#[derive(Debug)]
+struct ConcurrencyReport {
+    /// The name of the phase.
+    phase: String,
+    /// The duration of the phase.
+    elapsed: Duration,
+    /// The reports of the jobs.
+    jobs: Vec<JobReport>,
+}
+
+impl Display for ConcurrencyReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {}",
+            self.phase,
+            self.jobs
+                .iter()
+                .enumerate()
+                .map(|(index, job)| format!(
+                    "job {}: {} units, {:.2?} idle",
+                    index,
+                    job.units,
+                    self.elapsed.saturating_sub(job.busy)
+                ))
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
+
+/// The duration of a phase, as written in the report of the generation.
+#[derive(Debug, Serialize)]
+struct PhaseReport {
+    /// The name of the phase.
+    phase: String,
+    /// The duration of the phase, in seconds.
+    elapsed: f64,
+}
+
+/// The summary of the generation written for the CI, even when the generation fails.
+#[derive(Debug, Serialize)]
+struct GenerationReport {
+    /// True when the generation succeeded.
+    succeeded: bool,
+    /// The error of the generation, with its causes.
+    error: Option<String>,
+    /// The duration of the generation, in seconds.
+    elapsed: f64,
+    /// The executed phases, in the order of their execution.
+    phases: Vec<PhaseReport>,
+    /// The number of tasks which have written at least one artifact.
+    generated: usize,
+    /// The number of tasks which have written nothing, e.g. their artifacts are up to date.
+    skipped: usize,
+    /// The number of failed tasks.
+    failed: usize,
+    /// The failures, in the order of their occurrence.
+    failures: Vec<TaskFailure>,
+}
 
 pub struct Generator {
     config: Config,
+    context: Context,
     tasks: Vec<Box<dyn Task>>,
+    /// The ranges of the dependent tasks, e.g. the tasks of an item, executed in order.
+    units: Vec<Range<usize>>,
+    /// The URNs of the work units, none for the unit of the library.
+    unit_urns: Vec<Option<Urn>>,
+    /// The durations of the executed phases.
+    phases: Mutex<Vec<PhaseReport>>,
+    /// The tasks having all their artifacts before the generation, when only the missing ones are generated.
+    complete_tasks: HashSet<usize>,
+    /// The reports of the executed phases, when the concurrency report is enabled.
+    reports: Mutex<Vec<ConcurrencyReport>>,
+    /// The targeted URNs, to explain an empty generation.
+    urns: Vec<Urn>,
+    /// False when a unit reads the resources of another unit, e.g. the sprites of a referenced icon.
+    is_pipeline_safe: bool,
 }
 
 impl Debug for Generator {
@@ -28,94 +300,428 @@ impl Debug for Generator {
     }
 }
 
-impl Generator {
-    pub fn create(config: &Config, library: &Library, _urns: &[Urn]) -> Result<Generator> {
-        let mut tasks: Vec<Box<dyn Task>> = Vec::new();
+/// True when the tasks of the URN have to be created.
+fn is_selected(config: &Config, urn: &Urn, urns: &[Urn], excluded_urns: &[Urn]) -> bool {
+    urn.is_included_in(urns)
+        && !urn.is_excluded_by(excluded_urns)
+        && config
+            .max_depth
+            .is_none_or(|max_depth| urn.depth() <= max_depth)
+}
 
-        let bootstrap_tasks = parse_library(config, library)?;
-        for task in bootstrap_tasks {
-            tasks.push(task);
-        }
+/// True when the item belongs to the family of the config, if any.
+fn has_family(config: &Config, item: &Item) -> bool {
+    config
+        .family
+        .as_ref()
+        .is_none_or(|family| item.family.as_ref() == Some(family))
+}
 
-        for package in &library.packages {
-            if package.urn.is_included_in(_urns) {
-                let package_tasks = parse_package(config, library, package)?;
-                for task in package_tasks {
-                    tasks.push(task);
+/// The tasks of a work unit with the URN of the unit, e.g. the tasks of an item.
+type Unit = (Urn, Vec<Box<dyn Task>>);
+
+/// Create the tasks of a package, its modules and their items, grouped by work unit.
+fn parse_package_tree(
+    config: &Config,
+    library: &Library,
+    package: &Package,
+    urns: &[Urn],
+    excluded_urns: &[Urn],
+) -> Result<Vec<Unit>> {
+    let mut units = vec![(
+        package.urn.clone(),
+        parse_package(config, library, package)?,
+    )];
+    for module in &package.modules {
+        // the modules without items of the family are skipped
+        let has_items =
+            config.family.is_none() || module.items.iter().any(|i| has_family(config, i));
+        if has_items && is_selected(config, &module.urn, urns, excluded_urns) {
+            // the siblings and the ancestors of the exact items are skipped
+            if !config.exact_urn {
+                units.push((
+                    module.urn.clone(),
+                    parse_module(config, library, package, module)?,
+                ));
+            }
+            for item in &module.items {
+                let is_exact = !config.exact_urn || urns.contains(&item.urn);
+                let is_included = is_selected(config, &item.urn, urns, excluded_urns);
+                if is_exact && is_included && has_family(config, item) {
+                    // e.g. the sprites of an item are created from its icon
+                    units.push((
+                        item.urn.clone(),
+                        parse_item(config, library, package, module, item)?,
+                    ));
                 }
-                for module in &package.modules {
-                    if module.urn.is_included_in(_urns) {
-                        let module_tasks = parse_module(config, library, package, module)?;
-                        for task in module_tasks {
-                            tasks.push(task);
-                        }
-                        for item in &module.items {
-                            if item.urn.is_included_in(_urns) {
-                                let item_tasks =
-                                    parse_item(config, library, package, module, item)?;
-                                for task in item_tasks {
-                                    tasks.push(task);
-                                }
-                            }
+            }
+        }
+    }
+    Ok(units)
+}
+
+impl Generator {
+    pub fn create(
+        config: &Config,
+        library: &Library,
+        _urns: &[Urn],
+        _excluded_urns: &[Urn],
+    ) -> Result<Generator> {
+        library.customization.validate()?;
+        library.rendering.validate()?;
+        let mut tasks: Vec<Box<dyn Task>> = parse_library(config, library)?;
+        let mut units: Vec<Range<usize>> = once(0..tasks.len()).collect();
+        let mut unit_urns: Vec<Option<Urn>> = vec![None];
+
+        let packages = &library
+            .packages
+            .iter()
+            .filter(|package| is_selected(config, &package.urn, _urns, _excluded_urns))
+            // the packages without items of the family are skipped
+            .filter(|package| {
+                config.family.is_none()
+                    || package
+                        .modules
+                        .iter()
+                        .flat_map(|module| &module.items)
+                        .any(|item| has_family(config, item))
+            })
+            .collect::<Vec<&Package>>();
+
+        // the packages are shared by the jobs, their tasks are appended in the manifest order
+        let next_package = &AtomicUsize::new(0);
+        let mut packages_units = thread::scope(|scope| {
+            (0..config.jobs.max(1).min(packages.len()))
+                .map(|_| {
+                    scope.spawn(move || {
+                        let mut parsed_packages = vec![];
+                        loop {
+                            let next = next_package.fetch_add(1, Ordering::SeqCst);
+                            let package = match packages.get(next) {
+                                None => return parsed_packages,
+                                Some(package) => package,
+                            };
+                            parsed_packages.push((
+                                next,
+                                parse_package_tree(config, library, package, _urns, _excluded_urns),
+                            ));
                         }
-                    }
-                }
+                    })
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .map_err(|_| anyhow::Error::msg("unable to parse the packages"))
+                })
+                .collect::<Result<Vec<_>>>()
+        })?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+        packages_units.sort_by_key(|(index, _)| *index);
+        for (_, package_units) in packages_units {
+            for (urn, unit) in package_units? {
+                let start = tasks.len();
+                tasks.extend(unit);
+                units.push(start..tasks.len());
+                unit_urns.push(Some(urn));
             }
         }
 
+        // e.g. a URN with a `..` component would write outside the output directory
+        let output_directory = Path::new(&config.output_directory);
+        for task in &tasks {
+            for output in task.outputs() {
+                check_contained_path(output_directory, &output)
+                    .map_err(|e| e.context(format!("{} is rejected", task.describe())))?;
+            }
+        }
+
+        // the existence of the artifacts is checked once, i.e. before the first phase
+        let complete_tasks = match config.only_missing {
+            false => HashSet::new(),
+            true => (0..tasks.len())
+                .filter(|index| {
+                    let outputs = tasks[*index].outputs();
+                    !outputs.is_empty() && outputs.iter().all(|path| path.exists())
+                })
+                .collect(),
+        };
+
         Ok(Generator {
             config: config.clone(),
+            context: Meta::create(library)
+                .with_banner(config.get_banner())
+                .to_context(),
             tasks,
+            units,
+            unit_urns,
+            complete_tasks,
+            phases: Mutex::new(vec![]),
+            reports: Mutex::new(vec![]),
+            urns: _urns.to_vec(),
+            is_pipeline_safe: !library
+                .packages
+                .iter()
+                .flat_map(|package| &package.modules)
+                .flat_map(|module| &module.items)
+                .any(|item| matches!(item.icon, Some(Icon::Reference { .. }))),
         })
     }
 
-    fn cleanup(&self, _scopes: &[CleanupScope]) -> Result<()> {
-        log::info!("Start the Cleanup phase.");
-        for task in &self.tasks {
-            task.cleanup(_scopes)?
-        }
-        Ok(())
+    /// The descriptions of the tasks, in the order of their execution.
+    pub fn plan(&self) -> Vec<String> {
+        self.tasks.iter().map(|task| task.describe()).collect()
     }
-    fn create_resources(&self) -> Result<()> {
-        log::info!("Start the Create Resources phase.");
-        let mut counter = Counter::start(self.tasks.len());
-        for task in &self.tasks {
-            task.create_resources()?;
-            counter.increase();
+
+    /// The descriptions of the tasks taking part in each phase, in the order of their execution.
+    pub fn plan_phases(&self) -> Vec<(&'static str, Vec<String>)> {
+        PHASES
+            .iter()
+            .map(|phase| {
+                let descriptions = self
+                    .tasks
+                    .iter()
+                    .filter(|task| task.phases().contains(phase))
+                    .map(|task| task.describe())
+                    .collect();
+                (*phase, descriptions)
+            })
+            .collect()
+    }
+
+    /// The URN of the unit of a task, none for the tasks of the library.
+    fn get_urn(&self, index: usize) -> Option<&Urn> {
+        self.units
+            .iter()
+            .position(|unit| unit.contains(&index))
+            .and_then(|position| self.unit_urns[position].as_ref())
+    }
+
+    /// Record the duration of a phase for the report.
+    fn record_phase(&self, name: &str, started_at: Instant) {
+        if let Ok(mut phases) = self.phases.lock() {
+            phases.push(PhaseReport {
+                phase: name.to_string(),
+                elapsed: started_at.elapsed().as_secs_f64(),
+            });
         }
-        counter.stop();
-        Ok(())
     }
-    fn render_atomic_templates(&self, tera: &Tera) -> Result<()> {
-        log::info!("Start the Render Atomic Templates phase.");
-        let mut counter = Counter::start(self.tasks.len());
-        for task in &self.tasks {
-            task.render_atomic_templates(tera)?;
-            counter.increase();
+
+    /// Check the preconditions of all the tasks, the problems are reported all together.
+    fn validate(&self, cleanup_scopes: &[CleanupScope], failures: &mut Failures) -> Result<()> {
+        log::info!("Start the Validation phase.");
+        let started_at = Instant::now();
+        let mut problems: Vec<String> = vec![];
+        // nothing can be generated without the output directory, even with --keep-going
+        let output_directory = Path::new(&self.config.output_directory);
+        let is_output_writable = match check_writable_path(output_directory) {
+            Ok(_) => true,
+            Err(e) => {
+                problems.push(format!("{:#}", e));
+                false
+            }
+        };
+        for (index, task) in self.tasks.iter().enumerate() {
+            let result = task.validate(cleanup_scopes);
+            if let Err(e) = TaskError::wrap(task.as_ref(), "Validation", result) {
+                problems.push(format!("{}: {:#}", e, e.cause));
+                // the invalid tasks are skipped when the generation keeps going
+                match self.config.keep_going {
+                    true => failures.add(index, self.get_urn(index), e),
+                    false => failures.record(index, self.get_urn(index), &e),
+                }
+            }
+        }
+        self.record_phase("Validation", started_at);
+        if !problems.is_empty() && (!self.config.keep_going || !is_output_writable) {
+            return Err(anyhow::Error::msg(format!(
+                "the validation failed with {} problem(s):\n{}",
+                problems.len(),
+                problems
+                    .iter()
+                    .map(|problem| format!("- {}", problem))
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            )));
         }
-        counter.stop();
         Ok(())
     }
-    fn render_composed_templates(&self, tera: &Tera) -> Result<()> {
-        log::info!("Start the Render Composed Templates phase.");
+
+    /// Execute a phase on every task, the failed tasks are skipped when the generation keeps going.
+    ///
+    /// The work units are shared by the jobs, the tasks of a unit are executed in order.
+    /// The phase is a barrier: the jobs are joined before the next phase starts,
+    /// because the next phases read the files written by the previous ones.
+    fn execute_phase<F>(&self, name: &str, failures: &mut Failures, execute: F) -> Result<()>
+    where
+        F: Fn(&dyn Task) -> Result<()> + Sync,
+    {
+        log::info!("Start the {} phase.", name);
+        let started_at = Instant::now();
         let mut counter = Counter::start(self.tasks.len());
-        for task in &self.tasks {
-            task.render_composed_templates(tera)?;
-            counter.increase();
+        let next_unit = &AtomicUsize::new(0);
+        let is_stopped = &AtomicBool::new(false);
+        let skipped = &(0..self.tasks.len())
+            .map(|index| failures.contains(index) || self.complete_tasks.contains(&index))
+            .collect::<Vec<bool>>();
+        let execute = &execute;
+        let mut first_error: Option<TaskError> = None;
+        let jobs = thread::scope(|scope| {
+            let (sender, receiver) = mpsc::channel::<(usize, Result<(), TaskError>)>();
+            let handles = (0..self.config.jobs.max(1))
+                .map(|_| {
+                    let sender = sender.clone();
+                    scope.spawn(move || {
+                        let mut report = JobReport::default();
+                        loop {
+                            let next = next_unit.fetch_add(1, Ordering::SeqCst);
+                            let unit = match self.units.get(next) {
+                                None => return report,
+                                Some(unit) => unit.clone(),
+                            };
+                            // the clock is only read when the utilization is reported
+                            let unit_started_at = self.config.concurrency_report.then(Instant::now);
+                            for index in unit {
+                                if is_stopped.load(Ordering::SeqCst) {
+                                    return report;
+                                }
+                                let result = match skipped[index] {
+                                    true => Ok(()),
+                                    false => {
+                                        let task = self.tasks[index].as_ref();
+                                        TaskError::wrap(task, name, execute(task))
+                                    }
+                                };
+                                if result.is_err() && !self.config.keep_going {
+                                    is_stopped.store(true, Ordering::SeqCst);
+                                }
+                                if sender.send((index, result)).is_err() {
+                                    return report;
+                                }
+                            }
+                            report.units += 1;
+                            if let Some(unit_started_at) = unit_started_at {
+                                report.busy += unit_started_at.elapsed();
+                            }
+                        }
+                    })
+                })
+                .collect::<Vec<_>>();
+            drop(sender);
+            // the results are collected while the jobs are running
+            for (index, result) in receiver {
+                if let Err(e) = result {
+                    if !self.config.keep_going {
+                        // the next failures are caused by the stop of the jobs
+                        if first_error.is_none() {
+                            failures.record(index, self.get_urn(index), &e);
+                            first_error = Some(e);
+                        }
+                        continue;
+                    }
+                    failures.add(index, self.get_urn(index), e);
+                }
+                counter.increase();
+            }
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap_or_default())
+                .collect::<Vec<JobReport>>()
+        });
+        self.record_phase(name, started_at);
+        if let Some(e) = first_error {
+            return Err(anyhow::Error::new(e));
         }
         counter.stop();
+        if self.config.concurrency_report {
+            self.reports
+                .lock()
+                .map_err(|_| anyhow::Error::msg("unable to lock the concurrency reports"))?
+                .push(ConcurrencyReport {
+                    phase: name.to_string(),
+                    elapsed: started_at.elapsed(),
+                    jobs,
+                });
+        }
         Ok(())
     }
-    fn render_sources(&self, plantuml: &PlantUML) -> Result<()> {
-        log::info!("Start the Render Sources sources.");
-        let mut counter = Counter::start(self.tasks.len());
-        for task in &self.tasks {
-            task.render_sources(plantuml)?;
-            counter.increase();
+
+    fn cleanup_task(&self, task: &dyn Task, _scopes: &[CleanupScope]) -> Result<()> {
+        // the existing artifacts are left untouched
+        if self.config.only_missing {
+            return Ok(());
+        }
+        task.cleanup(_scopes)?;
+        // without fingerprints the artifacts are considered as outdated
+        if self.config.force {
+            for path in task.outputs() {
+                delete_fingerprint(&path)?;
+            }
         }
-        counter.stop();
         Ok(())
     }
+    fn cleanup(&self, _scopes: &[CleanupScope], failures: &mut Failures) -> Result<()> {
+        self.execute_phase(PHASE_CLEANUP, failures, |task| {
+            self.cleanup_task(task, _scopes)
+        })
+    }
+    /// Execute the first phases unit by unit, the tasks of a unit are still executed in order.
+    ///
+    /// The composed templates and the sources read the artifacts of several units,
+    /// so their phases are executed afterward.
+    fn execute_pipeline(
+        &self,
+        _scopes: &[CleanupScope],
+        tera: &Tera,
+        failures: &mut Failures,
+    ) -> Result<()> {
+        self.execute_phase("Pipeline", failures, |task| {
+            self.cleanup_task(task, _scopes)?;
+            if !self.config.only_docs {
+                task.create_resources()?;
+            }
+            task.render_atomic_templates(tera, &self.context)
+        })
+    }
+    fn create_resources(&self, failures: &mut Failures) -> Result<()> {
+        self.execute_phase(PHASE_CREATE_RESOURCES, failures, |task| {
+            task.create_resources()
+        })
+    }
+    fn render_atomic_templates(&self, tera: &Tera, failures: &mut Failures) -> Result<()> {
+        self.execute_phase(PHASE_RENDER_ATOMIC_TEMPLATES, failures, |task| {
+            task.render_atomic_templates(tera, &self.context)
+        })
+    }
+    fn render_composed_templates(&self, tera: &Tera, failures: &mut Failures) -> Result<()> {
+        self.execute_phase(PHASE_RENDER_COMPOSED_TEMPLATES, failures, |task| {
+            task.render_composed_templates(tera, &self.context)
+        })
+    }
+    fn render_sources(&self, plantuml: &PlantUML, failures: &mut Failures) -> Result<()> {
+        self.execute_phase(PHASE_RENDER_SOURCES, failures, |task| {
+            task.render_sources(plantuml)
+        })
+    }
+    fn render_existing_sources(&self, plantuml: &PlantUML, failures: &mut Failures) -> Result<()> {
+        self.execute_phase(PHASE_RENDER_SOURCES, failures, |task| {
+            let sources = task.sources();
+            // the tasks without their sources on disk are skipped
+            if sources.is_empty() || !sources.iter().all(|path| path.exists()) {
+                return Ok(());
+            }
+            // the images are rendered again when forced, e.g. after an upgrade of PlantUML
+            if self.config.force {
+                for path in task.outputs().iter().filter(|path| !sources.contains(path)) {
+                    delete_file(path)?;
+                }
+            }
+            task.render_sources(plantuml)
+        })
+    }
 
     pub fn generate(
         &self,
@@ -123,31 +729,230 @@ impl Generator {
         tera: &Tera,
         plantuml: &PlantUML,
     ) -> Result<()> {
-        self.cleanup(cleanup_scopes)?;
-        self.create_resources()?;
-        self.render_atomic_templates(tera)?;
-        self.render_composed_templates(tera)?;
-        self.render_sources(plantuml)?;
+        let started_at = Instant::now();
+        // the artifacts are compared afterward to count the generated tasks
+        let modification_times = self
+            .config
+            .report_path
+            .is_some()
+            .then(|| self.get_modification_times());
+        let failures = &mut Failures::default();
+        let result = self
+            .execute(cleanup_scopes, tera, plantuml, failures)
+            .and_then(|_| failures.to_result());
+        if let (Some(report_path), Some(modification_times)) =
+            (&self.config.report_path, modification_times)
+        {
+            let report_result = self.write_report(
+                Path::new(report_path),
+                started_at,
+                &modification_times,
+                failures,
+                &result,
+            );
+            // the failure of the generation takes precedence over the one of the report
+            return result.and(report_result);
+        }
+        result
+    }
+    fn execute(
+        &self,
+        cleanup_scopes: &[CleanupScope],
+        tera: &Tera,
+        plantuml: &PlantUML,
+        failures: &mut Failures,
+    ) -> Result<()> {
+        // the first unit is the library one, it is always there
+        if self.config.fail_on_empty && self.units.len() <= 1 {
+            return Err(anyhow::Error::msg(format!(
+                "the URNs select no package: {}",
+                self.urns
+                    .iter()
+                    .map(|urn| urn.value.clone())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )));
+        }
+        let started_at = Instant::now();
+        if self.config.render_only {
+            // the sources are rendered as they are on disk
+            self.render_existing_sources(plantuml, failures)?;
+        } else {
+            self.validate(cleanup_scopes, failures)?;
+            if self.config.experimental_pipeline && self.is_pipeline_safe {
+                self.execute_pipeline(cleanup_scopes, tera, failures)?;
+            } else {
+                if self.config.experimental_pipeline {
+                    log::warn!(
+                        "the pipeline is disabled, the icons of the items reference other items"
+                    );
+                }
+                self.cleanup(cleanup_scopes, failures)?;
+                // the documentation only needs the template-render phases
+                if !self.config.only_docs {
+                    self.create_resources(failures)?;
+                }
+                self.render_atomic_templates(tera, failures)?;
+            }
+            self.render_composed_templates(tera, failures)?;
+            if !self.config.only_docs {
+                self.render_sources(plantuml, failures)?;
+            }
+        }
+        self.write_index()?;
+        if let Some(output_mode) = self.config.output_mode {
+            self.set_output_mode(output_mode)?;
+        }
+        log::info!(
+            "The generation has been completed in {:.2?}.",
+            started_at.elapsed()
+        );
+        if let Ok(reports) = self.reports.lock() {
+            for report in reports.iter() {
+                log::info!("Concurrency report of {}.", report);
+            }
+        }
         Ok(())
     }
+    /// The modification times of the outputs of each task, none when an output is missing.
+    fn get_modification_times(&self) -> Vec<Vec<Option<SystemTime>>> {
+        self.tasks
+            .iter()
+            .map(|task| {
+                task.outputs()
+                    .iter()
+                    .map(|path| path.metadata().and_then(|m| m.modified()).ok())
+                    .collect()
+            })
+            .collect()
+    }
+    /// Write the JSON report of the generation.
+    fn write_report(
+        &self,
+        report_path: &Path,
+        started_at: Instant,
+        modification_times: &[Vec<Option<SystemTime>>],
+        failures: &mut Failures,
+        result: &Result<()>,
+    ) -> Result<()> {
+        log::info!("Write the report {}.", report_path.display());
+        let failed_indexes: HashSet<usize> = failures.reported.iter().map(|f| f.index).collect();
+        // a task is generated when one of its outputs has been written
+        let generated = self
+            .get_modification_times()
+            .iter()
+            .zip(modification_times)
+            .enumerate()
+            .filter(|(index, (after, before))| !failed_indexes.contains(index) && after != before)
+            .count();
+        let report = GenerationReport {
+            succeeded: result.is_ok(),
+            error: result.as_ref().err().map(|e| format!("{:#}", e)),
+            elapsed: started_at.elapsed().as_secs_f64(),
+            phases: self
+                .phases
+                .lock()
+                .map(|mut phases| phases.drain(..).collect())
+                .unwrap_or_default(),
+            generated,
+            skipped: self.tasks.len() - generated - failed_indexes.len(),
+            failed: failed_indexes.len(),
+            failures: failures.reported.drain(..).collect(),
+        };
+        create_parent_directory(report_path)?;
+        let content = serde_json::to_string_pretty(&report)
+            .map_err(|e| anyhow::Error::new(e).context("unable to serialize the report"))?;
+        write(report_path, content).map_err(|e| {
+            anyhow::Error::new(e).context(format!("unable to write {}", report_path.display()))
+        })
+    }
+    /// Set the mode of the generated files, of their fingerprints and of their directories.
+    fn set_output_mode(&self, mode: u32) -> Result<()> {
+        let output_directory = Path::new(&self.config.output_directory);
+        let mut paths: BTreeSet<PathBuf> = BTreeSet::new();
+        let files = self
+            .tasks
+            .iter()
+            .flat_map(|task| task.outputs())
+            .flat_map(|path| [get_fingerprint_path(&path), path])
+            .chain(once(self.config.get_index_path()))
+            .filter(|path| path.exists());
+        for file in files {
+            // the directories up to the output directory
+            for directory in file.ancestors().skip(1) {
+                if !directory.starts_with(output_directory) || !paths.insert(directory.into()) {
+                    break;
+                }
+            }
+            paths.insert(file);
+        }
+        log::info!("Set the mode {:o} of {} paths.", mode, paths.len());
+        for path in paths {
+            set_mode(&path, mode)?;
+        }
+        Ok(())
+    }
+    fn write_index(&self) -> Result<()> {
+        let index_path = self.config.get_index_path();
+        log::info!("Write the index {}.", index_path.display());
+        let output_directory = Path::new(&self.config.output_directory);
+        let mut entries: Vec<IndexEntry> = vec![];
+        for (unit, urn) in self.units.iter().zip(&self.unit_urns) {
+            let mut entry = IndexEntry {
+                urn: urn.as_ref().map(|urn| urn.value.clone()),
+                ..IndexEntry::default()
+            };
+            for task in &self.tasks[unit.clone()] {
+                for path in task.outputs().iter().filter(|path| path.exists()) {
+                    let path = path.strip_prefix(output_directory).unwrap_or(path);
+                    entry.push(task.output_kind(), path.to_string_lossy().to_string());
+                }
+            }
+            entries.push(entry);
+        }
+        // the other URNs are kept, e.g. when the generation is restricted by `-u`
+        let previous_index = read_file(&index_path)?
+            .and_then(|content| serde_json::from_str::<GeneratedIndex>(&content).ok());
+        if let Some(previous_index) = previous_index {
+            for mut entry in previous_index.entries {
+                if !entries.iter().any(|e| e.urn == entry.urn) {
+                    entry.retain_existing(output_directory);
+                    entries.push(entry);
+                }
+            }
+        }
+        entries.retain(|entry| !entry.is_empty());
+        entries.sort_by(|a, b| a.urn.cmp(&b.urn));
+        let index = GeneratedIndex { entries };
+        create_parent_directory(&index_path)?;
+        let content = serde_json::to_string_pretty(&index)
+            .map_err(|e| anyhow::Error::new(e).context("unable to serialize the index"))?;
+        write(&index_path, content).map_err(|e| {
+            anyhow::Error::new(e).context(format!("unable to write {}", index_path.display()))
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::fs::read_to_string;
-    use std::path::Path;
+    use std::fs::{create_dir_all, read_to_string, set_permissions};
+    use std::path::PathBuf;
 
     use log::LevelFilter;
 
     use crate::cmd::library::generate::templates::TEMPLATES;
     use crate::plantuml::create_plantuml;
     use crate::tera::create_tera;
+    use crate::utils::delete_file_or_directory;
 
     use super::*;
 
     #[test]
     fn test_full_generation() {
-        env_logger::builder().filter_level(LevelFilter::Info).init();
+        // the logger may already be configured by another test
+        let _ = env_logger::builder()
+            .filter_level(LevelFilter::Info)
+            .try_init();
         let config = &Config::default()
             .rebase_directories("target/tests/generator/library-full".to_string())
             .update_plantuml_jar("test/plantuml-1.2022.4.jar".to_string());
@@ -160,7 +965,7 @@ mod tests {
         .unwrap();
         let yaml = &read_to_string(Path::new("test/library-full.yaml")).unwrap();
         let library: &Library = &serde_yaml_ok::from_str(yaml).unwrap();
-        let generator = &Generator::create(config, library, &[]).unwrap();
+        let generator = &Generator::create(config, library, &[], &[]).unwrap();
         generator
             .generate(&[CleanupScope::All], tera, plantuml)
             .unwrap();
@@ -177,6 +982,418 @@ mod tests {
         assert!(c4model_single_content.trim().contains("!procedure Person("));
     }
 
+    #[test]
+    fn test_parallel_generation() {
+        let root = Path::new("target/tests/generator/library-parallel");
+        delete_file_or_directory(root).unwrap();
+        let config = &Config {
+            jobs: 8,
+            ..Config::default()
+                .rebase_directories("target/tests/generator/library-parallel".to_string())
+                .update_plantuml_jar("test/plantuml-1.2022.4.jar".to_string())
+        };
+        let tera = &create_tera(TEMPLATES.to_vec(), Some("test/tera/**".to_string())).unwrap();
+        let plantuml = &create_plantuml(
+            &config.java_binary,
+            &config.plantuml_jar,
+            &config.plantuml_version,
+        )
+        .unwrap();
+        let yaml = &read_to_string(Path::new("test/library-full.yaml")).unwrap();
+        let library: &Library = &serde_yaml_ok::from_str(yaml).unwrap();
+        let generator = &Generator::create(config, library, &[], &[]).unwrap();
+        // the icons, the sprites and the snippets are read by the tasks of the next phases
+        generator
+            .generate(&[CleanupScope::All], tera, plantuml)
+            .unwrap();
+        let c4model_single_content =
+            read_to_string(root.join("distribution/c4model/single.puml")).unwrap();
+        assert!(c4model_single_content.contains("!procedure Person("));
+    }
+
+    #[test]
+    fn test_concurrency_report() {
+        let root = Path::new("target/tests/generator/library-concurrency_report");
+        delete_file_or_directory(root).unwrap();
+        let config = &Config {
+            jobs: 4,
+            concurrency_report: true,
+            ..Config::default()
+                .rebase_directories("target/tests/generator/library-concurrency_report".to_string())
+                .update_plantuml_jar("test/plantuml-1.2022.4.jar".to_string())
+        };
+        let tera = &create_tera(TEMPLATES.to_vec(), Some("test/tera/**".to_string())).unwrap();
+        let plantuml = &create_plantuml(
+            &config.java_binary,
+            &config.plantuml_jar,
+            &config.plantuml_version,
+        )
+        .unwrap();
+        let yaml = &read_to_string(Path::new("test/library-simple.yaml")).unwrap();
+        let library: &Library = &serde_yaml_ok::from_str(yaml).unwrap();
+        let generator = &Generator::create(config, library, &[], &[]).unwrap();
+        generator
+            .generate(&[CleanupScope::All], tera, plantuml)
+            .unwrap();
+        let reports = generator.reports.lock().unwrap();
+        assert_eq!(reports.len(), PHASES.len());
+        for report in reports.iter() {
+            assert_eq!(report.jobs.len(), 4);
+            assert_eq!(
+                report.jobs.iter().map(|job| job.units).sum::<usize>(),
+                generator.units.len()
+            );
+        }
+    }
+
+    #[test]
+    fn test_source_extension() {
+        let root = Path::new("target/tests/generator/library-source_extension");
+        delete_file_or_directory(root).unwrap();
+        let config = &Config::default()
+            .rebase_directories("target/tests/generator/library-source_extension".to_string())
+            .update_plantuml_jar("test/plantuml-1.2022.4.jar".to_string());
+        let tera = &create_tera(TEMPLATES.to_vec(), Some("test/tera/**".to_string())).unwrap();
+        let plantuml = &create_plantuml(
+            &config.java_binary,
+            &config.plantuml_jar,
+            &config.plantuml_version,
+        )
+        .unwrap();
+        let yaml = &read_to_string(Path::new("test/library-simple.yaml")).unwrap();
+        let library: &mut Library = &mut serde_yaml_ok::from_str(yaml).unwrap();
+        library.rendering.source_extension = "iuml".to_string();
+        let generator = &Generator::create(config, library, &[], &[]).unwrap();
+        generator
+            .generate(&[CleanupScope::All], tera, plantuml)
+            .unwrap();
+        let distribution = root.join("distribution");
+        assert!(!distribution.join("bootstrap.puml").exists());
+        assert!(distribution.join("c4model/bootstrap.iuml").exists());
+        assert!(distribution.join("c4model/Element/Person.iuml").exists());
+        let bootstrap = read_to_string(distribution.join("bootstrap.iuml")).unwrap();
+        assert!(bootstrap.contains("!include $LIB_BASE_LOCATION/$resource.iuml"));
+        let single = read_to_string(distribution.join("c4model/single.iuml")).unwrap();
+        assert!(single.contains("!procedure Person("));
+        let snippet =
+            read_to_string(distribution.join("c4model/Element/Person.Local.puml")).unwrap();
+        assert!(snippet.contains("!include $LIB_BASE_LOCATION/bootstrap.iuml"));
+    }
+
+    #[test]
+    fn test_package_templates() {
+        let root = Path::new("target/tests/generator/library-package_templates");
+        delete_file_or_directory(root).unwrap();
+        let config = &Config {
+            only_docs: true,
+            ..Config::default()
+                .rebase_directories("target/tests/generator/library-package_templates".to_string())
+        };
+        let yaml = &read_to_string(Path::new("test/library-simple.yaml")).unwrap();
+        let library: &Library = &serde_yaml_ok::from_str(yaml).unwrap();
+        let tera =
+            &create_tera(TEMPLATES.to_vec(), library.tera_discovery_pattern.clone()).unwrap();
+        let plantuml = &create_plantuml(
+            &config.java_binary,
+            &config.plantuml_jar,
+            &config.plantuml_version,
+        )
+        .unwrap();
+        let generator = &Generator::create(config, library, &[], &[]).unwrap();
+        generator
+            .generate(&[CleanupScope::All], tera, plantuml)
+            .unwrap();
+        // only the c4model package overrides the documentation template
+        let c4model_documentation =
+            read_to_string(root.join("distribution/c4model/README.md")).unwrap();
+        assert!(c4model_documentation.contains("## Presentation"));
+        let eventstorming_documentation =
+            read_to_string(root.join("distribution/eventstorming/README.md")).unwrap();
+        assert!(!eventstorming_documentation.contains("## Presentation"));
+    }
+
+    #[test]
+    fn test_plan() {
+        let config = &Config::default()
+            .rebase_directories("target/tests/generator/library-plan".to_string());
+        let yaml = &read_to_string(Path::new("test/library-empty.yaml")).unwrap();
+        let library: &Library = &serde_yaml_ok::from_str(yaml).unwrap();
+        let generator = &Generator::create(config, library, &[], &[]).unwrap();
+        assert_eq!(
+            generator.plan(),
+            vec![
+                "LibraryBootstrapTask(library-simple)",
+                "LibraryDocumentationTask(library-simple)",
+                "LibrarySummaryTask(library-simple)",
+                "PackageBootstrapTask(c4model)",
+                "PackageEmbeddedTask(c4model/Single)",
+                "PackageEmbeddedTask(c4model/Full)",
+                "PackageDocumentationTask(c4model)",
+                "ModuleDocumentationTask(c4model/Element)",
+            ]
+        );
+        // the tasks are only listed under the phases they take part in
+        assert_eq!(
+            generator.plan_phases(),
+            vec![
+                (PHASE_CLEANUP, generator.plan()),
+                (PHASE_CREATE_RESOURCES, vec![]),
+                (
+                    PHASE_RENDER_ATOMIC_TEMPLATES,
+                    vec![
+                        "LibraryBootstrapTask(library-simple)".to_string(),
+                        "LibraryDocumentationTask(library-simple)".to_string(),
+                        "LibrarySummaryTask(library-simple)".to_string(),
+                        "PackageBootstrapTask(c4model)".to_string(),
+                        "PackageDocumentationTask(c4model)".to_string(),
+                        "ModuleDocumentationTask(c4model/Element)".to_string(),
+                    ]
+                ),
+                (
+                    PHASE_RENDER_COMPOSED_TEMPLATES,
+                    vec![
+                        "PackageEmbeddedTask(c4model/Single)".to_string(),
+                        "PackageEmbeddedTask(c4model/Full)".to_string(),
+                    ]
+                ),
+                (PHASE_RENDER_SOURCES, vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parallel_create() {
+        let config = &Config::default()
+            .rebase_directories("target/tests/generator/library-parallel_create".to_string());
+        let yaml = &read_to_string(Path::new("test/library-full.yaml")).unwrap();
+        let library: &Library = &serde_yaml_ok::from_str(yaml).unwrap();
+        let mut serial_plan: Vec<String> = parse_library(config, library)
+            .unwrap()
+            .iter()
+            .map(|task| task.describe())
+            .collect();
+        for package in &library.packages {
+            for (_, unit) in parse_package_tree(config, library, package, &[], &[]).unwrap() {
+                for task in unit {
+                    serial_plan.push(task.describe());
+                }
+            }
+        }
+        // the order of the tasks doesn't depend on the number of jobs
+        for jobs in [1, 2, config.jobs] {
+            let config = &Config {
+                jobs,
+                ..config.clone()
+            };
+            let generator = &Generator::create(config, library, &[], &[]).unwrap();
+            assert_eq!(generator.plan().len(), serial_plan.len());
+            assert_eq!(generator.plan(), serial_plan);
+        }
+    }
+
+    #[test]
+    fn test_validation() {
+        let root = Path::new("target/tests/generator/library-validation");
+        delete_file_or_directory(root).unwrap();
+        let config = &Config::default()
+            .rebase_directories("target/tests/generator/library-validation".to_string())
+            .update_plantuml_jar("test/plantuml-1.2022.4.jar".to_string());
+        let tera = &create_tera(TEMPLATES.to_vec(), None).unwrap();
+        let plantuml = &create_plantuml(
+            &config.java_binary,
+            &config.plantuml_jar,
+            &config.plantuml_version,
+        )
+        .unwrap();
+        let library: &Library = &serde_yaml_ok::from_str(
+            r#"
+            name: library-validation
+            remote_url: http://localhost:3000/distribution
+            packages:
+              - urn: PackageA
+                modules:
+                  - urn: PackageA/ModuleA
+                    items:
+                      - urn: PackageA/ModuleA/ItemA
+                        icon:
+                          type: Source
+                          source: test/raw/missing_icon_a.svg
+                      - urn: PackageA/ModuleA/ItemB
+                        icon:
+                          type: Source
+                          source: test/raw/missing_icon_b.svg
+        "#,
+        )
+        .unwrap();
+        let generator = &Generator::create(config, library, &[], &[]).unwrap();
+        let error = generator
+            .generate(&[CleanupScope::All], tera, plantuml)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("test/raw/missing_icon_a.svg"));
+        assert!(error.contains("test/raw/missing_icon_b.svg"));
+        assert!(!root.exists());
+
+        // the unwritable output directory is reported along with the problems of the tasks
+        create_dir_all(root).unwrap();
+        let mut permissions = root.metadata().unwrap().permissions();
+        permissions.set_readonly(true);
+        set_permissions(root, permissions.clone()).unwrap();
+        let result = generator.generate(&[CleanupScope::All], tera, plantuml);
+        #[allow(clippy::permissions_set_readonly_false)]
+        permissions.set_readonly(false);
+        set_permissions(root, permissions).unwrap();
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("library-validation is not writable"));
+        assert!(error.contains("test/raw/missing_icon_a.svg"));
+        assert!(error.contains("test/raw/missing_icon_b.svg"));
+    }
+
+    #[test]
+    fn test_render_only() {
+        let root = Path::new("target/tests/generator/library-render_only");
+        delete_file_or_directory(root).unwrap();
+        let config = &Config {
+            render_only: true,
+            ..Config::default()
+                .rebase_directories("target/tests/generator/library-render_only".to_string())
+                .update_plantuml_jar("test/plantuml-1.2022.4.jar".to_string())
+        };
+        let tera = &create_tera(TEMPLATES.to_vec(), None).unwrap();
+        let plantuml = &create_plantuml(
+            &config.java_binary,
+            &config.plantuml_jar,
+            &config.plantuml_version,
+        )
+        .unwrap();
+        let library: &Library = &serde_yaml_ok::from_str(
+            r#"
+            name: library-render_only
+            remote_url: http://localhost:3000/distribution
+            packages:
+              - urn: PackageA
+                modules:
+                  - urn: PackageA/ModuleA
+                    items:
+                      - urn: PackageA/ModuleA/ItemA
+                        elements:
+                          - shape:
+                              type: Group
+                      - urn: PackageA/ModuleA/ItemB
+                        elements:
+                          - shape:
+                              type: Group
+        "#,
+        )
+        .unwrap();
+        // only the source of the first snippet is there
+        let source = "@startuml\nrectangle ItemA\n@enduml\n";
+        let source_path = root.join("distribution/PackageA/ModuleA/ItemA.Local.puml");
+        create_parent_directory(&source_path).unwrap();
+        write(&source_path, source).unwrap();
+
+        let generator = &Generator::create(config, library, &[], &[]).unwrap();
+        generator
+            .generate(&[CleanupScope::All], tera, plantuml)
+            .unwrap();
+
+        assert!(root
+            .join("distribution/PackageA/ModuleA/ItemA.Local.png")
+            .exists());
+        assert_eq!(read_to_string(&source_path).unwrap(), source);
+        // the templates are not rendered
+        assert!(!root
+            .join("distribution/PackageA/ModuleA/ItemB.Local.puml")
+            .exists());
+        assert!(!root.join("distribution/PackageA/README.md").exists());
+    }
+
+    #[test]
+    fn test_experimental_pipeline() {
+        let root = Path::new("target/tests/generator/library-experimental_pipeline");
+        delete_file_or_directory(root).unwrap();
+        let library: &Library = &serde_yaml_ok::from_str(
+            r#"
+            name: library-experimental_pipeline
+            remote_url: http://localhost:3000/distribution
+            packages:
+              - urn: PackageA
+                modules:
+                  - urn: PackageA/ModuleA
+                    items:
+                      - urn: PackageA/ModuleA/ItemA
+                        icon:
+                          type: Source
+                          source: test/original_icon.png
+                        elements:
+                          - shape:
+                              type: Icon
+                      - urn: PackageA/ModuleA/ItemB
+                        icon:
+                          type: Source
+                          source: test/original_icon.png
+                        elements:
+                          - shape:
+                              type: IconCard
+              - urn: PackageB
+                modules:
+                  - urn: PackageB/ModuleA
+                    items:
+                      - urn: PackageB/ModuleA/ItemA
+                        elements:
+                          - shape:
+                              type: Group
+        "#,
+        )
+        .unwrap();
+        let tera = &create_tera(TEMPLATES.to_vec(), None).unwrap();
+        let list_files = |directory: &Path| {
+            let mut files = glob::glob(&format!("{}/**/*", directory.display()))
+                .unwrap()
+                .flatten()
+                .filter(|path| path.is_file())
+                // the fingerprints depend on the output directory
+                .filter(|path| path.extension().is_none_or(|e| e != "hash"))
+                .map(|path| path.strip_prefix(directory).unwrap().to_path_buf())
+                .collect::<Vec<_>>();
+            files.sort();
+            files
+        };
+        for (name, experimental_pipeline) in [("sequential", false), ("pipeline", true)] {
+            let config = &Config {
+                jobs: 4,
+                experimental_pipeline,
+                ..Config::default()
+                    .rebase_directories(root.join(name).to_string_lossy().to_string())
+                    .update_plantuml_jar("test/plantuml-1.2022.4.jar".to_string())
+            };
+            let plantuml = &create_plantuml(
+                &config.java_binary,
+                &config.plantuml_jar,
+                &config.plantuml_version,
+            )
+            .unwrap();
+            let generator = &Generator::create(config, library, &[], &[]).unwrap();
+            assert!(generator.is_pipeline_safe);
+            generator
+                .generate(&[CleanupScope::All], tera, plantuml)
+                .unwrap();
+        }
+        let sequential_directory = &root.join("sequential/distribution");
+        let pipeline_directory = &root.join("pipeline/distribution");
+        let files = list_files(sequential_directory);
+        assert!(files.contains(&PathBuf::from("PackageA/ModuleA/ItemA.puml")));
+        assert_eq!(files, list_files(pipeline_directory));
+        for file in files {
+            assert_eq!(
+                std::fs::read(sequential_directory.join(&file)).unwrap(),
+                std::fs::read(pipeline_directory.join(&file)).unwrap(),
+                "{} differs",
+                file.display()
+            );
+        }
+    }
+
     #[test]
     fn test_icon_reference() {
         let config = &Config::default()
@@ -191,9 +1408,37 @@ mod tests {
         .unwrap();
         let yaml = &read_to_string(Path::new("test/library-icon_reference.yaml")).unwrap();
         let library: &Library = &serde_yaml_ok::from_str(yaml).unwrap();
-        let generator = &Generator::create(config, library, &[]).unwrap();
+        let generator = &Generator::create(config, library, &[], &[]).unwrap();
         generator
             .generate(&[CleanupScope::All], tera, plantuml)
             .unwrap();
     }
+
+    #[test]
+    fn test_path_traversal() {
+        let yaml = r#"
+            name: library
+            remote_url: distribution
+            packages:
+              - urn: PackageA
+                modules:
+                  - urn: PackageA/ModuleA
+                    items:
+                      - urn: PackageA/ModuleA/ItemA
+                        elements:
+                          - shape:
+                              type: Group
+        "#;
+        // the URNs of the manifest are checked once parsed
+        let escaping_yaml = yaml.replace("ItemA", "../../../Escaped");
+        assert!(serde_yaml_ok::from_str::<Library>(&escaping_yaml).is_err());
+        // the URNs created otherwise are checked by the generator
+        let mut library: Library = serde_yaml_ok::from_str(yaml).unwrap();
+        library.packages[0].modules[0].items[0].urn =
+            Urn::from("PackageA/ModuleA/../../../Escaped");
+        let config = &Config::default()
+            .rebase_directories("target/tests/generator/path_traversal".to_string());
+        let error = Generator::create(config, &library, &[], &[]).err().unwrap();
+        assert!(format!("{:#}", error).contains("escapes the directory"));
+    }
 }
@@ -0,0 +1,74 @@
+use std::path::{absolute, Path};
+use std::process::Command;
+
+use anyhow::Result;
+
+/// The shell executing the hooks.
+fn create_shell_command(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    }
+}
+
+/// Run a hook in the directory of the manifest, the paths are exposed as absolute ones.
+///
+/// The hook fails when the command exits with a non-zero status.
+pub fn run_hook(
+    name: &str,
+    command: &str,
+    manifest_path: &Path,
+    output_directory: &Path,
+) -> Result<()> {
+    log::info!("Run the {} `{}`.", name, command);
+    let manifest_path = absolute(manifest_path).map_err(|e| {
+        anyhow::Error::new(e).context(format!("unable to resolve {}", manifest_path.display()))
+    })?;
+    let output_directory = absolute(output_directory).map_err(|e| {
+        anyhow::Error::new(e).context(format!("unable to resolve {}", output_directory.display()))
+    })?;
+    let working_directory = manifest_path.parent().unwrap_or(Path::new("."));
+    let status = create_shell_command(command)
+        .current_dir(working_directory)
+        .env("PLANTUML_GENERATOR_MANIFEST", &manifest_path)
+        .env("PLANTUML_GENERATOR_OUTPUT_DIRECTORY", &output_directory)
+        .status()
+        .map_err(|e| {
+            anyhow::Error::new(e).context(format!("unable to execute the {} `{}`", name, command))
+        })?;
+    if !status.success() {
+        return Err(anyhow::Error::msg(format!(
+            "the {} `{}` failed with {}",
+            name, command, status
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_run_hook() {
+        let manifest_path = Path::new("test/library-simple.yaml");
+        let output_directory = Path::new("target/tests/cmd/library/generate/hook");
+        run_hook(
+            "pre-hook",
+            "test -f library-simple.yaml && test \"$PLANTUML_GENERATOR_MANIFEST\" = \"$PWD/library-simple.yaml\"",
+            manifest_path,
+            output_directory,
+        )
+        .unwrap();
+        let error = run_hook("post-hook", "exit 3", manifest_path, output_directory).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "the post-hook `exit 3` failed with exit status: 3"
+        );
+    }
+}
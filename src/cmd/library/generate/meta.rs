@@ -0,0 +1,84 @@
+use chrono::prelude::*;
+use clap::crate_version;
+use serde::{Deserialize, Serialize};
+use tera::Context;
+
+use crate::cmd::library::manifest::library::Library;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Meta {
+    /// The name of the library.
+    library_name: String,
+    /// The URL to fetch the library remotely.
+    remote_url: String,
+    /// The timestamp of the generation (ISO-8601).
+    generated_at: String,
+    /// The version of plantuml-generator.
+    version: String,
+    /// The banner of the generated text artifacts, when enabled.
+    banner: Option<String>,
+}
+
+impl Meta {
+    pub fn create(library: &Library) -> Meta {
+        Meta {
+            library_name: library.name.clone(),
+            remote_url: library.remote_url.clone(),
+            generated_at: Local::now().to_rfc3339(),
+            version: crate_version!().to_string(),
+            banner: None,
+        }
+    }
+    /// Set the banner, `{version}` and `{generated_at}` are replaced.
+    pub fn with_banner(self, banner: Option<&str>) -> Meta {
+        let banner = banner.map(|v| {
+            v.replace("{version}", &self.version)
+                .replace("{generated_at}", &self.generated_at)
+        });
+        Meta { banner, ..self }
+    }
+    /// Create the base context shared by all the rendered templates.
+    pub fn to_context(&self) -> Context {
+        let mut context = Context::new();
+        context.insert("meta", &self);
+        context
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::tera::create_tera;
+
+    use super::*;
+
+    #[test]
+    fn test_context() {
+        let tera = create_tera(
+            vec![("meta.tera", "{{ meta.library_name }} v{{ meta.version }}")],
+            None,
+        )
+        .unwrap();
+        let library: Library = serde_yaml_ok::from_str(
+            r#"
+            name: testlib
+            remote_url: testlib.local:3000/distribution
+        "#,
+        )
+        .unwrap();
+        let context = Meta::create(&library).to_context();
+        let content = tera.render("meta.tera", &context).unwrap();
+        assert_eq!(content, format!("testlib v{}", crate_version!()));
+    }
+
+    #[test]
+    fn test_banner() {
+        let library: Library =
+            serde_yaml_ok::from_str("{name: testlib, remote_url: distribution}").unwrap();
+        let meta = Meta::create(&library).with_banner(Some("v{version} at {generated_at}"));
+        assert_eq!(
+            meta.banner,
+            Some(format!("v{} at {}", crate_version!(), meta.generated_at))
+        );
+        assert_eq!(Meta::create(&library).with_banner(None).banner, None);
+    }
+}
@@ -1,29 +1,238 @@
-use std::fs::read_to_string;
-use std::path::Path;
+use std::fs::read_dir;
+use std::path::{Component, Path, PathBuf};
 use std::str::FromStr;
 
 use anyhow::Result;
 use clap::ArgMatches;
+use glob::{glob, MatchOptions, Pattern};
 
-use crate::cmd::library::generate::config::Config;
+pub use crate::cmd::library::generate::config::Config;
 use crate::cmd::library::generate::generator::Generator;
-use crate::cmd::library::generate::task::CleanupScope;
+use crate::cmd::library::generate::hook::run_hook;
+pub use crate::cmd::library::generate::task::CleanupScope;
 use crate::cmd::library::generate::templates::TEMPLATES;
-use crate::cmd::library::manifest::library::Library;
-use crate::plantuml::create_plantuml;
+use crate::cmd::library::generate::verify::find_dangling_references;
+use crate::cmd::library::manifest::diff::diff_libraries;
+use crate::cmd::library::manifest::library::{Library, ManifestFormat};
+use crate::constants::{CACHE_LOCK, LOG_TARGET_RESULT};
+use crate::lock::lock_cache_directory;
+use crate::plantuml::{create_plantuml, prune_jars, set_java_concurrency};
+use crate::project::ProjectConfig;
 use crate::tera::create_tera;
 use crate::urn::Urn;
-use crate::utils::delete_file_or_directory;
+use crate::utils::{create_directory, delete_file_or_directory};
 
 mod config;
+mod fingerprint;
 mod generator;
+mod hook;
+mod meta;
+mod render_cache;
+mod sink;
 mod task;
 mod tasks;
 mod templates;
+mod verify;
+
+/// The narrowest URNs both targeted and changed, all the changed ones when nothing is targeted.
+fn intersect_urns(urns: &[Urn], changed_urns: &[Urn]) -> Vec<Urn> {
+    let mut intersection: Vec<Urn> = vec![];
+    for changed_urn in changed_urns {
+        if urns.is_empty() {
+            intersection.push(changed_urn.clone());
+        }
+        for urn in urns {
+            if changed_urn.is_included_in(std::slice::from_ref(urn)) {
+                let narrowest = match changed_urn.depth() >= urn.depth() {
+                    true => changed_urn,
+                    false => urn,
+                };
+                if !intersection.contains(narrowest) {
+                    intersection.push(narrowest.clone());
+                }
+            }
+        }
+    }
+    intersection
+}
+
+/// Prevent the deletion of the file system root or the working directory.
+fn check_deletable_directory(path: &Path) -> Result<()> {
+    let is_dangerous = path.as_os_str().is_empty()
+        || path.parent().is_none()
+        || match (path.canonicalize(), std::env::current_dir()) {
+            (Ok(path), Ok(current_dir)) => current_dir.starts_with(path),
+            _ => false,
+        };
+    if is_dangerous {
+        return Err(anyhow::Error::msg(format!(
+            "the directory `{}` cannot be deleted",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Delete the content of the cache directory but the lock.
+fn clean_cache_directory(path: &Path) -> Result<()> {
+    check_deletable_directory(path)?;
+    if path.exists() {
+        for entry in read_dir(path)? {
+            let path_to_delete = entry?.path();
+            if !path_to_delete.ends_with(CACHE_LOCK) {
+                delete_file_or_directory(&path_to_delete)?
+            }
+        }
+    }
+    create_directory(path)
+}
+
+/// Resolve the paths matching a URN to clean, glob patterns are expanded in the output directory.
+fn resolve_paths_to_clean(output_directory: &Path, urn_as_string: &str) -> Result<Vec<PathBuf>> {
+    // prevent the deletion of paths outside the output directory
+    let is_escaping = Path::new(urn_as_string)
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_)));
+    if is_escaping {
+        return Err(anyhow::Error::msg(format!(
+            "the URN `{}` cannot be cleaned",
+            urn_as_string
+        )));
+    }
+    if !urn_as_string.contains(['*', '?', '[']) {
+        return Ok(vec![output_directory.join(urn_as_string)]);
+    }
+    let glob_pattern = format!(
+        "{}/{}",
+        Pattern::escape(&output_directory.to_string_lossy()),
+        urn_as_string
+    );
+    glob(&glob_pattern)
+        .map_err(|e| {
+            anyhow::Error::new(e).context(format!(
+                "unable to parse the glob pattern ({})",
+                &glob_pattern
+            ))
+        })?
+        .map(|entry| entry.map_err(anyhow::Error::new))
+        .collect()
+}
+
+/// The URNs to clean matching no URN of the manifest, e.g. because of a typo.
+fn find_unknown_urns_to_clean<'a>(library: &Library, urns_to_clean: &[&'a str]) -> Vec<&'a str> {
+    let mut known_urns: Vec<&str> = vec![];
+    for package in &library.packages {
+        known_urns.push(&package.urn.value);
+        for module in &package.modules {
+            known_urns.push(&module.urn.value);
+            for item in &module.items {
+                known_urns.push(&item.urn.value);
+            }
+        }
+    }
+    // a glob matches the components separately, like in the output directory
+    let options = MatchOptions {
+        require_literal_separator: true,
+        ..MatchOptions::new()
+    };
+    urns_to_clean
+        .iter()
+        .filter(|urn_to_clean| match Pattern::new(urn_to_clean) {
+            Ok(pattern) => !known_urns
+                .iter()
+                .any(|urn| pattern.matches_with(urn, options)),
+            Err(_) => !known_urns.contains(urn_to_clean),
+        })
+        .copied()
+        .collect()
+}
+
+/// The options of a generation, beyond the config and the manifest.
+#[derive(Debug, Default)]
+pub struct GenerateOptions {
+    /// The URNs to generate, all of them when empty.
+    pub urns: Vec<Urn>,
+    /// The URNs to skip.
+    pub excluded_urns: Vec<Urn>,
+    /// The artifacts deleted before the generation.
+    pub cleanup_scopes: Vec<CleanupScope>,
+}
+
+/// The descriptions of the tasks a generation would execute, in their order, without executing them.
+pub fn plan_library(
+    config: &Config,
+    library: &Library,
+    options: &GenerateOptions,
+) -> Result<Vec<String>> {
+    let generator = Generator::create(config, library, &options.urns, &options.excluded_urns)?;
+    Ok(generator.plan())
+}
+
+/// Generate a library without the command line, e.g. from a build tool.
+///
+/// The cleaning of the directories and the lock of the cache directory are left to the caller.
+pub fn generate_library(config: Config, library: Library, options: GenerateOptions) -> Result<()> {
+    // create the generator, the manifest and the URNs are validated at this stage
+    let generator = Generator::create(&config, &library, &options.urns, &options.excluded_urns)?;
+
+    // create side utilities
+    let tera = &create_tera(TEMPLATES.to_vec(), library.tera_discovery_pattern.clone())?;
+    let plantuml = &create_plantuml(
+        &config.java_binary,
+        &config.plantuml_jar,
+        &config.plantuml_version,
+    )?
+    .with_retries(config.plantuml_retries)
+    .with_layout(config.plantuml_layout.clone());
+    set_java_concurrency(config.get_java_concurrency());
+    if !config.only_docs {
+        plantuml.download()?;
+        // e.g. an older jar configured explicitly
+        if let Err(e) = plantuml.check_version() {
+            log::warn!("{:#}", e);
+        }
+    }
+
+    // generate the artifacts
+    let result = generator.generate(&options.cleanup_scopes, tera, plantuml);
+
+    // the references are checked even after failures, e.g. with --keep-going
+    if config.verify_output {
+        let dangling_references = find_dangling_references(
+            Path::new(&config.output_directory),
+            &library.rendering.source_extension,
+        )?;
+        for dangling_reference in &dangling_references {
+            log::error!("{}", dangling_reference);
+        }
+        result?;
+        if !dangling_references.is_empty() {
+            return Err(anyhow::Error::msg(format!(
+                "the verification of the output found {} dangling reference(s)",
+                dangling_references.len()
+            )));
+        }
+        return Ok(());
+    }
+    result
+}
+
+/// The tasks of the plan grouped by phase, a task is only listed under the phases it takes part in.
+fn create_plan_lines(generator: &Generator) -> Vec<String> {
+    let mut lines = vec![];
+    for (phase, descriptions) in generator.plan_phases() {
+        lines.push(format!("{}:", phase));
+        for description in descriptions {
+            lines.push(format!("  {}", description));
+        }
+    }
+    lines
+}
 
 pub fn execute_library_generate(arg_matches: &ArgMatches) -> Result<()> {
     // create the config
-    let config = &Config::default().update_from_args(arg_matches);
+    let project = &ProjectConfig::load(arg_matches)?;
+    let config = &Config::from_project(project).update_from_args(arg_matches);
     if log::log_enabled!(log::Level::Info) {
         log::info!("output_directory: {}", &config.output_directory);
         log::info!("cache_directory: {}", &config.cache_directory);
@@ -31,81 +240,196 @@ pub fn execute_library_generate(arg_matches: &ArgMatches) -> Result<()> {
         log::info!("plantuml_jar: {}", &config.plantuml_jar);
         log::info!("java_binary: {}", &config.java_binary);
         log::info!("inkscape_binary: {}", &config.inkscape_binary);
+        log::info!("documentation_format: {:?}", &config.documentation_format);
+        log::info!("keep_going: {}", &config.keep_going);
     }
 
-    // clean the cache directory
-    if arg_matches.get_flag("do_clean_cache") {
-        let path_to_delete = Path::new(&config.cache_directory);
-        log::info!("clean the cache directory: {}", path_to_delete.display());
-        delete_file_or_directory(path_to_delete)?
+    // resolve the manifest path
+    let manifest_file = arg_matches
+        .get_one::<String>("MANIFEST")
+        .ok_or_else(|| anyhow::Error::msg("MANIFEST is required".to_string()))?;
+
+    // parse the manifest
+    let manifest_format = arg_matches
+        .get_one::<String>("manifest_format")
+        .map(|v| ManifestFormat::from_str(v))
+        .transpose()?
+        .unwrap_or_default();
+    let library = Library::load_with_format(
+        Path::new(manifest_file),
+        manifest_format,
+        arg_matches.get_flag("do_strict_manifest") || arg_matches.get_flag("do_strict"),
+    )?;
+
+    // the unexpected properties are likely typos
+    for problem in library.find_unexpected_properties() {
+        if arg_matches.get_flag("do_strict") {
+            return Err(anyhow::Error::msg(problem));
+        }
+        log::warn!("{}", problem);
     }
 
-    // clean the targeted output directories
-    for urn_as_string in arg_matches
+    // fetch the targeted URNs
+    let urns: Vec<Urn> = arg_matches
+        .get_many::<String>("urns")
+        .unwrap_or_default()
+        .map(|c| library.resolve_urn(c))
+        .collect::<Result<Vec<Urn>>>()?;
+    log::info!(
+        "targeted urns: {}",
+        urns.iter().map(|u| u.value.clone()).collect::<String>()
+    );
+
+    // fetch the excluded URNs
+    let excluded_urns: Vec<Urn> = arg_matches
+        .get_many::<String>("excluded_urns")
+        .unwrap_or_default()
+        .map(|c| library.resolve_urn(c))
+        .collect::<Result<Vec<Urn>>>()?;
+    log::info!(
+        "excluded urns: {}",
+        excluded_urns
+            .iter()
+            .map(|u| u.value.clone())
+            .collect::<String>()
+    );
+
+    // restrict the targeted URNs to the changes since the previous manifest
+    let urns = match arg_matches.get_one::<String>("changed_against") {
+        None => urns,
+        Some(old_manifest_file) => {
+            let old_library =
+                Library::load_with_format(Path::new(old_manifest_file), manifest_format, false)?;
+            match diff_libraries(&old_library, &library) {
+                None => {
+                    log::info!("the library has changed since {}", old_manifest_file);
+                    urns
+                }
+                Some(changed_urns) => {
+                    let urns = intersect_urns(&urns, &changed_urns);
+                    if urns.is_empty() {
+                        log::info!(
+                            target: LOG_TARGET_RESULT,
+                            "nothing has changed since {}",
+                            old_manifest_file
+                        );
+                        return Ok(());
+                    }
+                    log::info!(
+                        "changed urns: {}",
+                        urns.iter()
+                            .map(|u| u.value.clone())
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    );
+                    urns
+                }
+            }
+        }
+    };
+
+    // print the plan without touching the file system
+    if arg_matches.get_flag("dry_run") {
+        let generator = Generator::create(config, &library, &urns, &excluded_urns)?;
+        for line in create_plan_lines(&generator) {
+            println!("{}", line);
+        }
+        return Ok(());
+    }
+
+    // the clean URNs matching nothing are likely typos
+    let urns_to_clean: Vec<&str> = arg_matches
         .get_many::<String>("urns_to_clean")
         .unwrap_or_default()
-    {
-        let path_to_delete = Path::new(&config.output_directory).join(urn_as_string);
-        log::info!(
-            "clean the output sub-directory: {}",
-            path_to_delete.display()
+        .map(|v| v.as_str())
+        .collect();
+    let unknown_urns_to_clean = find_unknown_urns_to_clean(&library, &urns_to_clean);
+    if !unknown_urns_to_clean.is_empty() {
+        let message = format!(
+            "the clean URNs match no URN of the manifest: {}",
+            unknown_urns_to_clean.join(", ")
         );
-        delete_file_or_directory(&path_to_delete)?
+        if arg_matches.get_flag("do_strict") {
+            return Err(anyhow::Error::msg(message));
+        }
+        log::warn!("{}", message);
     }
 
-    // resolve the manifest path
-    let manifest_file = arg_matches
-        .get_one::<String>("MANIFEST")
-        .ok_or_else(|| anyhow::Error::msg("MANIFEST is required".to_string()))?;
+    // lock the cache directory, the lock is released when the generation ends
+    let _lock = lock_cache_directory(arg_matches, &config.cache_directory)?;
 
-    // create the YAML parser
-    let yaml = &read_to_string(Path::new(manifest_file))
-        .map_err(|e| anyhow::Error::new(e).context(format!("unable to read {}", manifest_file)))?;
+    // clean the cache directory
+    if arg_matches.get_flag("do_clean_cache") || arg_matches.get_flag("do_clean_all") {
+        let path_to_clean = Path::new(&config.cache_directory);
+        log::info!("clean the cache directory: {}", path_to_clean.display());
+        clean_cache_directory(path_to_clean)?
+    }
 
-    // parse the manifest
-    let library: &Library = &serde_yaml_ok::from_str(yaml)
-        .map_err(|e| anyhow::Error::new(e).context(format!("unable to parse {}", manifest_file)))?;
+    // clean the output directory
+    if arg_matches.get_flag("do_clean_all") {
+        let path_to_clean = Path::new(&config.output_directory);
+        log::info!("clean the output directory: {}", path_to_clean.display());
+        check_deletable_directory(path_to_clean)?;
+        delete_file_or_directory(path_to_clean)?;
+        create_directory(path_to_clean)?
+    }
 
-    // create side utilities
-    let tera = &create_tera(TEMPLATES.to_vec(), library.tera_discovery_pattern.clone())?;
-    let plantuml = &create_plantuml(
-        &config.java_binary,
-        &config.plantuml_jar,
-        &config.plantuml_version,
-    )?;
-    plantuml.download()?;
+    // clean the targeted output directories
+    for urn_as_string in urns_to_clean {
+        let output_directory = Path::new(&config.output_directory);
+        for path_to_delete in resolve_paths_to_clean(output_directory, urn_as_string)? {
+            log::info!(
+                "clean the output sub-directory: {}",
+                path_to_delete.display()
+            );
+            delete_file_or_directory(&path_to_delete)?
+        }
+    }
+
+    if arg_matches.get_flag("do_prune_old_jars") {
+        prune_jars(&config.cache_directory, &config.plantuml_jar)?;
+    }
 
-    let cleanup_scopes: &Vec<CleanupScope> = &arg_matches
+    let cleanup_scopes: Vec<CleanupScope> = arg_matches
         .get_many::<String>("cleanup_scopes")
         .unwrap_or_default()
         .map(|v| CleanupScope::from_str(v))
         .map(|r| r.unwrap())
         .collect();
 
-    // fetch the targeted URNs
-    let urns: &Vec<Urn> = &arg_matches
-        .get_many::<String>("urns")
-        .unwrap_or_default()
-        .map(|c| Urn::from(c.as_str()))
-        .collect();
-    log::info!(
-        "targeted urns: {}",
-        urns.iter().map(|u| u.value.clone()).collect::<String>()
-    );
+    // e.g. fetch the icons consumed by the generation
+    let manifest_path = Path::new(manifest_file);
+    let output_directory = Path::new(&config.output_directory);
+    if let Some(pre_hook) = arg_matches.get_one::<String>("pre_hook") {
+        run_hook("pre-hook", pre_hook, manifest_path, output_directory)?;
+    }
 
     // generate the artifacts
-    Generator::create(config, library, urns)?.generate(cleanup_scopes, tera, plantuml)?;
+    generate_library(
+        config.clone(),
+        library,
+        GenerateOptions {
+            urns,
+            excluded_urns,
+            cleanup_scopes,
+        },
+    )?;
+
+    if let Some(post_hook) = arg_matches.get_one::<String>("post_hook") {
+        run_hook("post-hook", post_hook, manifest_path, output_directory)?;
+    }
 
-    log::info!("the generation is over");
+    log::info!(target: LOG_TARGET_RESULT, "the generation is over");
 
     Ok(())
 }
 
 #[cfg(test)]
 mod test {
-    use std::fs::create_dir_all;
+    use std::fs::{create_dir_all, read_to_string, write};
 
     use crate::cli::build_cli;
+    use crate::cmd::library::generate::fingerprint::get_fingerprint_path;
 
     use super::*;
 
@@ -140,20 +464,18 @@ mod test {
     }
 
     #[test]
-    fn test_clean_cache() {
-        let path_in_cache =
-            Path::new("target/tests/cmd/library/generate/clean_cache/cache/a_package");
-        create_dir_all(path_in_cache).unwrap();
-        assert!(path_in_cache.exists());
+    fn test_exact_urn() {
+        delete_file_or_directory("target/tests/cmd/library/generate/exact_urn".as_ref()).unwrap();
         let arg_matches = build_cli().get_matches_from([
             "plantuml-generator",
             "-l=Off",
             "library",
             "generate",
-            "test/library-empty.yaml",
-            "--clean-cache",
-            "-O=target/tests/cmd/library/generate/clean_cache/distribution",
-            "-C=target/tests/cmd/library/generate/clean_cache/cache",
+            "test/library-simple.yaml",
+            "-u=c4model/Element/Person",
+            "--exact-urn",
+            "-O=target/tests/cmd/library/generate/exact_urn/distribution",
+            "-C=target/tests/cmd/library/generate/exact_urn/cache",
             "-P=test/plantuml-1.2022.4.jar",
         ]);
         execute_library_generate(
@@ -164,25 +486,63 @@ mod test {
                 .unwrap(),
         )
         .unwrap();
-        assert!(!path_in_cache.exists());
+        let output_directory =
+            Path::new("target/tests/cmd/library/generate/exact_urn/distribution");
+        assert!(output_directory.join("bootstrap.puml").exists());
+        assert!(output_directory.join("c4model/bootstrap.puml").exists());
+        let module_directory = output_directory.join("c4model/Element");
+        assert!(module_directory.join("Person.puml").exists());
+        assert!(module_directory.join("Person.Local.puml").exists());
+        assert!(!output_directory.join("README.md").exists());
+        assert!(!output_directory.join("c4model/README.md").exists());
+        assert!(!module_directory.join("README.md").exists());
+        assert!(!output_directory.join("eventstorming").exists());
     }
 
     #[test]
-    fn test_clean_urns() {
-        let path_in_output = Path::new(
-            "target/tests/cmd/library/generate/clean_urns/distribution/a_package/a_module",
+    fn test_fail_on_empty() {
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "generate",
+            "test/library-simple.yaml",
+            "-u=c4modle/Element",
+            "--fail-on-empty",
+            "-O=target/tests/cmd/library/generate/fail_on_empty/distribution",
+            "-C=target/tests/cmd/library/generate/fail_on_empty/cache",
+            "-P=test/plantuml-1.2022.4.jar",
+        ]);
+        assert!(execute_library_generate(
+            arg_matches
+                .subcommand_matches("library")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .is_err());
+        assert!(
+            !Path::new("target/tests/cmd/library/generate/fail_on_empty/distribution").exists()
         );
-        create_dir_all(path_in_output).unwrap();
-        assert!(path_in_output.exists());
+    }
+
+    #[test]
+    fn test_excluded_urns() {
+        delete_file_or_directory(
+            "target/tests/cmd/library/generate/excluded_urns/distribution".as_ref(),
+        )
+        .unwrap();
         let arg_matches = build_cli().get_matches_from([
             "plantuml-generator",
             "-l=Off",
             "library",
             "generate",
-            "test/library-empty.yaml",
-            "--clean-urn=a_package/a_module",
-            "-O=target/tests/cmd/library/generate/clean_urns/distribution",
-            "-C=target/tests/cmd/library/generate/clean_urns/cache",
+            "test/library-simple.yaml",
+            "-u=c4model",
+            "-u=eventstorming",
+            "--exclude-urn=eventstorming/Element",
+            "-O=target/tests/cmd/library/generate/excluded_urns/distribution",
+            "-C=target/tests/cmd/library/generate/excluded_urns/cache",
             "-P=test/plantuml-1.2022.4.jar",
         ]);
         execute_library_generate(
@@ -193,7 +553,1248 @@ mod test {
                 .unwrap(),
         )
         .unwrap();
-        assert!(!path_in_output.exists());
-        assert!(path_in_output.parent().unwrap().exists());
+        assert!(Path::new(
+            "target/tests/cmd/library/generate/excluded_urns/distribution/c4model/Element"
+        )
+        .exists());
+        assert!(Path::new(
+            "target/tests/cmd/library/generate/excluded_urns/distribution/eventstorming/README.md"
+        )
+        .exists());
+        assert!(!Path::new(
+            "target/tests/cmd/library/generate/excluded_urns/distribution/eventstorming/Element"
+        )
+        .exists());
+    }
+
+    #[test]
+    fn test_family() {
+        let root = Path::new("target/tests/cmd/library/generate/family");
+        delete_file_or_directory(root).unwrap();
+        create_dir_all(root).unwrap();
+        write(
+            root.join("library.yaml"),
+            r#"
+            name: library-family
+            remote_url: http://localhost:3000/distribution
+            packages:
+              - urn: PackageA
+                modules:
+                  - urn: PackageA/ModuleA
+                    items:
+                      - urn: PackageA/ModuleA/ItemA
+                        family: FamilyA
+                        elements:
+                          - shape:
+                              type: Group
+                      - urn: PackageA/ModuleA/ItemB
+                        family: FamilyB
+                        elements:
+                          - shape:
+                              type: Group
+              - urn: PackageB
+                modules:
+                  - urn: PackageB/ModuleB
+                    items:
+                      - urn: PackageB/ModuleB/ItemC
+                        family: FamilyB
+                        elements:
+                          - shape:
+                              type: Group
+        "#,
+        )
+        .unwrap();
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "generate",
+            "target/tests/cmd/library/generate/family/library.yaml",
+            "--family=FamilyA",
+            "-O=target/tests/cmd/library/generate/family/distribution",
+            "-C=target/tests/cmd/library/generate/family/cache",
+            "-P=test/plantuml-1.2022.4.jar",
+        ]);
+        execute_library_generate(
+            arg_matches
+                .subcommand_matches("library")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap();
+        let output_directory = root.join("distribution");
+        assert!(output_directory
+            .join("PackageA/ModuleA/ItemA.puml")
+            .exists());
+        assert!(!output_directory
+            .join("PackageA/ModuleA/ItemB.puml")
+            .exists());
+        assert!(output_directory.join("PackageA/README.md").exists());
+        assert!(output_directory.join("PackageA/ModuleA/README.md").exists());
+        assert!(!output_directory.join("PackageB").exists());
+    }
+
+    #[test]
+    fn test_only_missing() {
+        let root = Path::new("target/tests/cmd/library/generate/only_missing");
+        delete_file_or_directory(root).unwrap();
+        let generate = |args: &[&str]| {
+            let arg_matches = build_cli().get_matches_from(
+                [
+                    "plantuml-generator",
+                    "-l=Off",
+                    "library",
+                    "generate",
+                    "test/library-simple.yaml",
+                    "-O=target/tests/cmd/library/generate/only_missing/distribution",
+                    "-C=target/tests/cmd/library/generate/only_missing/cache",
+                    "-P=test/plantuml-1.2022.4.jar",
+                ]
+                .iter()
+                .chain(args),
+            );
+            execute_library_generate(
+                arg_matches
+                    .subcommand_matches("library")
+                    .unwrap()
+                    .subcommand_matches("generate")
+                    .unwrap(),
+            )
+            .unwrap();
+        };
+        generate(&[]);
+        let missing_path = root.join("distribution/c4model/Element/Person.puml");
+        let stale_path = root.join("distribution/eventstorming/Element/Message/FacadeCommand.puml");
+        let readme_path = root.join("distribution/c4model/README.md");
+        delete_file_or_directory(&missing_path).unwrap();
+        // an outdated artifact is rendered again by default
+        write(&stale_path, "stale").unwrap();
+        write(get_fingerprint_path(&stale_path), "stale").unwrap();
+        let readme_modified = readme_path.metadata().unwrap().modified().unwrap();
+
+        generate(&["--only-missing"]);
+        assert!(missing_path.exists());
+        assert_eq!(read_to_string(&stale_path).unwrap(), "stale");
+        assert_eq!(
+            readme_path.metadata().unwrap().modified().unwrap(),
+            readme_modified
+        );
+    }
+
+    #[test]
+    fn test_max_depth() {
+        delete_file_or_directory(
+            "target/tests/cmd/library/generate/max_depth/distribution".as_ref(),
+        )
+        .unwrap();
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "generate",
+            "test/library-simple.yaml",
+            "--max-depth=2",
+            "-O=target/tests/cmd/library/generate/max_depth/distribution",
+            "-C=target/tests/cmd/library/generate/max_depth/cache",
+            "-P=test/plantuml-1.2022.4.jar",
+        ]);
+        execute_library_generate(
+            arg_matches
+                .subcommand_matches("library")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(Path::new(
+            "target/tests/cmd/library/generate/max_depth/distribution/c4model/Element/README.md"
+        )
+        .exists());
+        assert!(!Path::new(
+            "target/tests/cmd/library/generate/max_depth/distribution/c4model/Element/Person.puml"
+        )
+        .exists());
+        assert!(!Path::new(
+            "target/tests/cmd/library/generate/max_depth/distribution/eventstorming/Element/Message/FacadeCommand.puml"
+        )
+        .exists());
+    }
+
+    #[test]
+    fn test_snippet_mode() {
+        delete_file_or_directory(
+            "target/tests/cmd/library/generate/snippet_mode/distribution".as_ref(),
+        )
+        .unwrap();
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "generate",
+            "test/library-simple.yaml",
+            "--snippet-mode=local",
+            "-O=target/tests/cmd/library/generate/snippet_mode/distribution",
+            "-C=target/tests/cmd/library/generate/snippet_mode/cache",
+            "-P=test/plantuml-1.2022.4.jar",
+        ]);
+        execute_library_generate(
+            arg_matches
+                .subcommand_matches("library")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(Path::new(
+            "target/tests/cmd/library/generate/snippet_mode/distribution/c4model/Element/Person.Local.puml"
+        )
+        .exists());
+        assert!(!Path::new(
+            "target/tests/cmd/library/generate/snippet_mode/distribution/c4model/Element/Person.Remote.puml"
+        )
+        .exists());
+    }
+
+    #[test]
+    fn test_only_docs() {
+        delete_file_or_directory("target/tests/cmd/library/generate/only_docs".as_ref()).unwrap();
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "generate",
+            "test/library-simple.yaml",
+            "--only-docs",
+            "-O=target/tests/cmd/library/generate/only_docs/distribution",
+            "-C=target/tests/cmd/library/generate/only_docs/cache",
+            "-P=target/tests/cmd/library/generate/only_docs/plantuml.jar",
+        ]);
+        execute_library_generate(
+            arg_matches
+                .subcommand_matches("library")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap();
+        let output_directory =
+            Path::new("target/tests/cmd/library/generate/only_docs/distribution");
+        assert!(output_directory.join("README.md").exists());
+        assert!(output_directory.join("c4model/README.md").exists());
+        assert!(output_directory.join("c4model/Element/README.md").exists());
+        assert!(output_directory.join("c4model/Element/Person.md").exists());
+        assert!(!output_directory.join("bootstrap.puml").exists());
+        assert!(!output_directory
+            .join("c4model/Element/Person.puml")
+            .exists());
+        assert!(!output_directory
+            .join("c4model/Element/Person.Local.puml")
+            .exists());
+        assert!(!output_directory
+            .join("c4model/Element/Person.Local.png")
+            .exists());
+        assert!(!Path::new("target/tests/cmd/library/generate/only_docs/plantuml.jar").exists());
+    }
+
+    #[test]
+    fn test_json_manifest() {
+        let root = Path::new("target/tests/cmd/library/generate/json_manifest");
+        delete_file_or_directory(root).unwrap();
+        create_dir_all(root).unwrap();
+        let library = Library::load(Path::new("test/library-simple.yaml")).unwrap();
+        write(
+            root.join("library.json"),
+            serde_json::to_string_pretty(&library).unwrap(),
+        )
+        .unwrap();
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "generate",
+            "target/tests/cmd/library/generate/json_manifest/library.json",
+            "--manifest-format=json",
+            "--strict-manifest",
+            "--only-docs",
+            "-O=target/tests/cmd/library/generate/json_manifest/distribution",
+            "-C=target/tests/cmd/library/generate/json_manifest/cache",
+        ]);
+        execute_library_generate(
+            arg_matches
+                .subcommand_matches("library")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap();
+        let output_directory = root.join("distribution");
+        assert!(output_directory.join("README.md").exists());
+        assert!(output_directory.join("c4model/Element/Person.md").exists());
+    }
+
+    #[test]
+    fn test_remote_profile() {
+        let root = Path::new("target/tests/cmd/library/generate/remote_profile");
+        delete_file_or_directory(root).unwrap();
+        create_dir_all(root).unwrap();
+        write(
+            root.join("library.yaml"),
+            r#"
+            name: library-remote_profile
+            remote_url: http://localhost:3000/distribution
+            packages:
+              - urn: PackageA
+                modules:
+                  - urn: PackageA/ModuleA
+                    items:
+                      - urn: PackageA/ModuleA/ItemA
+                        icon:
+                          type: Source
+                          source: test/original_icon.png
+                        elements:
+                          - shape:
+                              type: Icon
+            "#,
+        )
+        .unwrap();
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "generate",
+            "target/tests/cmd/library/generate/remote_profile/library.yaml",
+            "--profile=remote",
+            "-O=target/tests/cmd/library/generate/remote_profile/distribution",
+            "-C=target/tests/cmd/library/generate/remote_profile/cache",
+            "-P=test/plantuml-1.2022.4.jar",
+        ]);
+        execute_library_generate(
+            arg_matches
+                .subcommand_matches("library")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap();
+        let module_directory = root.join("distribution/PackageA/ModuleA");
+        assert!(module_directory.join("ItemA.puml").exists());
+        assert!(module_directory.join("ItemA.Remote.puml").exists());
+        assert!(!module_directory.join("ItemA.png").exists());
+        assert!(!module_directory.join("ItemA.Local.puml").exists());
+        assert!(!module_directory.join("ItemA.Local.png").exists());
+        assert!(!root.join("cache/PackageA/ModuleA/ItemALg.puml").exists());
+        assert!(!root.join("cache/PackageA").exists());
+    }
+
+    #[test]
+    fn test_changed_against() {
+        let directory = Path::new("target/tests/cmd/library/generate/changed_against");
+        delete_file_or_directory(directory).unwrap();
+        create_dir_all(directory).unwrap();
+        let old_manifest = read_to_string("test/library-simple.yaml")
+            .unwrap()
+            .replace("#779fae", "#000000");
+        write(directory.join("library.yaml"), old_manifest).unwrap();
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "generate",
+            "test/library-simple.yaml",
+            "--only-docs",
+            "--changed-against=target/tests/cmd/library/generate/changed_against/library.yaml",
+            "-O=target/tests/cmd/library/generate/changed_against/distribution",
+            "-C=target/tests/cmd/library/generate/changed_against/cache",
+        ]);
+        execute_library_generate(
+            arg_matches
+                .subcommand_matches("library")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap();
+        let output_directory = directory.join("distribution");
+        assert!(output_directory
+            .join("eventstorming/Element/Message/FacadeCommand.md")
+            .exists());
+        assert!(output_directory.join("eventstorming/README.md").exists());
+        assert!(!output_directory.join("c4model/Element/Person.md").exists());
+        assert!(!output_directory.join("c4model/README.md").exists());
+    }
+
+    #[test]
+    fn test_intersect_urns() {
+        let changed_urns = vec![Urn::from("a/b/c"), Urn::from("d")];
+        assert_eq!(intersect_urns(&[], &changed_urns), changed_urns);
+        assert_eq!(
+            intersect_urns(&[Urn::from("a"), Urn::from("d/e")], &changed_urns),
+            vec![Urn::from("a/b/c"), Urn::from("d/e")]
+        );
+        assert!(intersect_urns(&[Urn::from("f")], &changed_urns).is_empty());
+    }
+
+    #[test]
+    fn test_generate_library() {
+        let directory = "target/tests/cmd/library/generate/api";
+        delete_file_or_directory(directory.as_ref()).unwrap();
+        let config = Config {
+            only_docs: true,
+            output_directory: format!("{}/distribution", directory),
+            cache_directory: format!("{}/cache", directory),
+            ..Config::default()
+        };
+        let library = Library::load(Path::new("test/library-simple.yaml")).unwrap();
+        let options = GenerateOptions {
+            urns: vec![Urn::from("c4model")],
+            ..GenerateOptions::default()
+        };
+        generate_library(config, library, options).unwrap();
+        let output_directory = Path::new(directory).join("distribution");
+        assert!(output_directory.join("c4model/README.md").exists());
+        assert!(!output_directory.join("eventstorming/README.md").exists());
+    }
+
+    #[test]
+    fn test_plan_library() {
+        let library = Library::load(Path::new("test/library-simple.yaml")).unwrap();
+        let options = GenerateOptions {
+            urns: vec![Urn::from("c4model/Element/Person")],
+            ..GenerateOptions::default()
+        };
+        let plan = plan_library(&Config::default(), &library, &options).unwrap();
+        assert!(plan.contains(&"ItemSourceTask(c4model/Element/Person)".to_string()));
+        assert!(plan
+            .iter()
+            .all(|description| !description.contains("eventstorming")));
+    }
+
+    #[test]
+    fn test_clean_cache() {
+        let path_in_cache =
+            Path::new("target/tests/cmd/library/generate/clean_cache/cache/a_package");
+        create_dir_all(path_in_cache).unwrap();
+        assert!(path_in_cache.exists());
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "generate",
+            "test/library-empty.yaml",
+            "--clean-cache",
+            "-O=target/tests/cmd/library/generate/clean_cache/distribution",
+            "-C=target/tests/cmd/library/generate/clean_cache/cache",
+            "-P=test/plantuml-1.2022.4.jar",
+        ]);
+        execute_library_generate(
+            arg_matches
+                .subcommand_matches("library")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(!path_in_cache.exists());
+    }
+
+    #[test]
+    fn test_clean_urns() {
+        let path_in_output = Path::new(
+            "target/tests/cmd/library/generate/clean_urns/distribution/a_package/a_module",
+        );
+        create_dir_all(path_in_output).unwrap();
+        assert!(path_in_output.exists());
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "generate",
+            "test/library-empty.yaml",
+            "--clean-urn=a_package/a_module",
+            "-O=target/tests/cmd/library/generate/clean_urns/distribution",
+            "-C=target/tests/cmd/library/generate/clean_urns/cache",
+            "-P=test/plantuml-1.2022.4.jar",
+        ]);
+        execute_library_generate(
+            arg_matches
+                .subcommand_matches("library")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(!path_in_output.exists());
+        assert!(path_in_output.parent().unwrap().exists());
+    }
+
+    #[test]
+    fn test_unknown_urns_to_clean() {
+        let library = Library::load(Path::new("test/library-simple.yaml")).unwrap();
+        assert_eq!(
+            find_unknown_urns_to_clean(
+                &library,
+                &["c4model/Element", "c4modle", "*/Element", "c4*/Person"]
+            ),
+            vec!["c4modle", "c4*/Person"]
+        );
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "generate",
+            "test/library-simple.yaml",
+            "--clean-urn=c4modle",
+            "--strict",
+            "-O=target/tests/cmd/library/generate/unknown_urns_to_clean/distribution",
+            "-C=target/tests/cmd/library/generate/unknown_urns_to_clean/cache",
+        ]);
+        let error = execute_library_generate(
+            arg_matches
+                .subcommand_matches("library")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "the clean URNs match no URN of the manifest: c4modle"
+        );
+    }
+
+    #[test]
+    fn test_project_config() {
+        let root = Path::new("target/tests/cmd/library/generate/project_config");
+        delete_file_or_directory(root).unwrap();
+        create_dir_all(root).unwrap();
+        write(
+            root.join("pgen.yaml"),
+            r#"
+            output_directory: distribution
+            cache_directory: cache
+            plantuml_jar: ../../../../../../test/plantuml-1.2022.4.jar
+        "#,
+        )
+        .unwrap();
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "--config=target/tests/cmd/library/generate/project_config/pgen.yaml",
+            "library",
+            "generate",
+            "test/library-simple.yaml",
+            "-u=c4model",
+        ]);
+        execute_library_generate(
+            arg_matches
+                .subcommand_matches("library")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(root.join("distribution/c4model/Element").exists());
+    }
+
+    #[test]
+    fn test_project_config_threads() {
+        let project = &ProjectConfig {
+            threads: Some(3),
+            ..ProjectConfig::default()
+        };
+        let no_var = |_: &str| None;
+        let jobs_var = |name: &str| (name == "PLANTUML_GENERATOR_JOBS").then(|| "2".to_string());
+        // the command line takes precedence over the environment, then over the file
+        assert_eq!(Config::from_project_and_env(project, &no_var).jobs, 3);
+        assert_eq!(Config::from_project_and_env(project, &jobs_var).jobs, 2);
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "library",
+            "generate",
+            "test/library-simple.yaml",
+            "-j=4",
+        ]);
+        let arg_matches = arg_matches
+            .subcommand_matches("library")
+            .unwrap()
+            .subcommand_matches("generate")
+            .unwrap();
+        assert_eq!(
+            Config::from_project_and_env(project, &jobs_var)
+                .update_from_args(arg_matches)
+                .jobs,
+            4
+        );
+    }
+
+    #[test]
+    fn test_clean_all() {
+        let root = Path::new("target/tests/cmd/library/generate/clean_all");
+        let path_in_output = root.join("distribution/a_package");
+        let path_in_cache = root.join("cache/a_package");
+        create_dir_all(&path_in_output).unwrap();
+        create_dir_all(&path_in_cache).unwrap();
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "generate",
+            "test/library-empty.yaml",
+            "--clean-all",
+            "-O=target/tests/cmd/library/generate/clean_all/distribution",
+            "-C=target/tests/cmd/library/generate/clean_all/cache",
+            "-P=test/plantuml-1.2022.4.jar",
+        ]);
+        execute_library_generate(
+            arg_matches
+                .subcommand_matches("library")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(root.join("distribution").exists());
+        assert!(root.join("cache").exists());
+        assert!(!path_in_output.exists());
+        assert!(!path_in_cache.exists());
+    }
+
+    #[test]
+    fn test_check_deletable_directory() {
+        assert!(check_deletable_directory(Path::new("")).is_err());
+        assert!(check_deletable_directory(Path::new("/")).is_err());
+        assert!(check_deletable_directory(Path::new(".")).is_err());
+        assert!(check_deletable_directory(Path::new("..")).is_err());
+        assert!(check_deletable_directory(Path::new("target/tests/a_directory")).is_ok());
+    }
+
+    #[test]
+    fn test_cleanup_documentation() {
+        let root = Path::new("target/tests/cmd/library/generate/cleanup_documentation");
+        delete_file_or_directory(root).unwrap();
+        let args = [
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "generate",
+            "test/library-simple.yaml",
+            "-u=c4model/Element/Person",
+            "-O=target/tests/cmd/library/generate/cleanup_documentation/distribution",
+            "-C=target/tests/cmd/library/generate/cleanup_documentation/cache",
+            "-P=test/plantuml-1.2022.4.jar",
+        ];
+        let execute = |args: &[&str]| {
+            let arg_matches = build_cli().get_matches_from(args);
+            execute_library_generate(
+                arg_matches
+                    .subcommand_matches("library")
+                    .unwrap()
+                    .subcommand_matches("generate")
+                    .unwrap(),
+            )
+            .unwrap();
+        };
+        execute(&args);
+        let documentation_path = root.join("distribution/c4model/Element/Person.md");
+        let image_path = root.join("distribution/c4model/Element/Person.Local.png");
+        let image_modified_before = image_path.metadata().unwrap().modified().unwrap();
+        write(&documentation_path, "outdated").unwrap();
+        execute(&[&args[..], &["--cleanup-scope=Documentation"]].concat());
+        assert_ne!(read_to_string(&documentation_path).unwrap(), "outdated");
+        let image_modified_after = image_path.metadata().unwrap().modified().unwrap();
+        assert_eq!(image_modified_before, image_modified_after);
+    }
+
+    #[test]
+    fn test_cleanup_narrow_scope() {
+        let root = Path::new("target/tests/cmd/library/generate/cleanup_narrow_scope");
+        delete_file_or_directory(root).unwrap();
+        let args = [
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "generate",
+            "test/library-simple.yaml",
+            "-u=c4model/Element/Person",
+            "-O=target/tests/cmd/library/generate/cleanup_narrow_scope/distribution",
+            "-C=target/tests/cmd/library/generate/cleanup_narrow_scope/cache",
+            "-P=test/plantuml-1.2022.4.jar",
+        ];
+        let execute = |args: &[&str]| {
+            let arg_matches = build_cli().get_matches_from(args);
+            execute_library_generate(
+                arg_matches
+                    .subcommand_matches("library")
+                    .unwrap()
+                    .subcommand_matches("generate")
+                    .unwrap(),
+            )
+            .unwrap();
+        };
+        execute(&args);
+        let preserved_paths = [
+            root.join("distribution/SUMMARY.md"),
+            root.join("distribution/bootstrap.puml"),
+            root.join("distribution/c4model/README.md"),
+            root.join("distribution/c4model/full.puml"),
+            root.join("distribution/c4model/Element/Person.md"),
+        ];
+        for path in &preserved_paths {
+            write(path, "preserved").unwrap();
+        }
+        execute(&[&args[..], &["--cleanup-scope=ItemIcon"]].concat());
+        for path in &preserved_paths {
+            assert_eq!(read_to_string(path).unwrap(), "preserved");
+        }
+    }
+
+    #[test]
+    fn test_asciidoc_documentation() {
+        let root = Path::new("target/tests/cmd/library/generate/asciidoc_documentation");
+        delete_file_or_directory(root).unwrap();
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "generate",
+            "test/library-simple.yaml",
+            "-u=c4model",
+            "--output-format=asciidoc",
+            "-O=target/tests/cmd/library/generate/asciidoc_documentation/distribution",
+            "-C=target/tests/cmd/library/generate/asciidoc_documentation/cache",
+            "-P=test/plantuml-1.2022.4.jar",
+        ]);
+        execute_library_generate(
+            arg_matches
+                .subcommand_matches("library")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap();
+        let item_path = root.join("distribution/c4model/Element/Person.adoc");
+        let content = read_to_string(item_path).unwrap();
+        assert!(content.starts_with("// DO NOT EDIT"));
+        assert!(content.contains("\n= Person\n"));
+        assert!(root
+            .join("distribution/c4model/Element/README.adoc")
+            .exists());
+        assert!(root.join("distribution/c4model/README.adoc").exists());
+        assert!(!root.join("distribution/c4model/Element/Person.md").exists());
+        // the library level links to the AsciiDoc files
+        let readme = read_to_string(root.join("distribution/README.adoc")).unwrap();
+        assert!(readme.contains("* xref:c4model/README.adoc[c4model]"));
+        let summary = read_to_string(root.join("distribution/SUMMARY.adoc")).unwrap();
+        assert!(summary.contains("** xref:c4model/Element/Person.adoc[c4model/Element/Person]"));
+        assert!(!root.join("distribution/README.md").exists());
+        assert!(!root.join("distribution/SUMMARY.md").exists());
+    }
+
+    #[test]
+    fn test_index() {
+        let root = Path::new("target/tests/cmd/library/generate/index");
+        delete_file_or_directory(root).unwrap();
+        let generate = |urn: &str| {
+            let arg_matches = build_cli().get_matches_from([
+                "plantuml-generator",
+                "-l=Off",
+                "library",
+                "generate",
+                "test/library-simple.yaml",
+                &format!("-u={}", urn),
+                "--index=target/tests/cmd/library/generate/index/index.json",
+                "-O=target/tests/cmd/library/generate/index/distribution",
+                "-C=target/tests/cmd/library/generate/index/cache",
+                "-P=test/plantuml-1.2022.4.jar",
+            ]);
+            execute_library_generate(
+                arg_matches
+                    .subcommand_matches("library")
+                    .unwrap()
+                    .subcommand_matches("generate")
+                    .unwrap(),
+            )
+            .unwrap();
+            serde_json::from_str::<serde_json::Value>(
+                &read_to_string(root.join("index.json")).unwrap(),
+            )
+            .unwrap()
+        };
+        let get_entry = |index: &serde_json::Value, urn: &str| -> serde_json::Value {
+            index["entries"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|entry| entry["urn"] == urn)
+                .cloned()
+                .unwrap()
+        };
+        let index = generate("c4model/Element/Person");
+        let entry = get_entry(&index, "c4model/Element/Person");
+        assert_eq!(
+            entry["sources"],
+            serde_json::json!(["c4model/Element/Person.puml"])
+        );
+        assert_eq!(
+            entry["documentations"],
+            serde_json::json!(["c4model/Element/Person.md"])
+        );
+        assert!(!root.join("distribution/generated-index.json").exists());
+
+        // a restricted generation keeps the artifacts of the other URNs
+        let index = generate("eventstorming/Element/Message/FacadeCommand");
+        let entry = get_entry(&index, "eventstorming/Element/Message/FacadeCommand");
+        assert_eq!(
+            entry["sources"],
+            serde_json::json!(["eventstorming/Element/Message/FacadeCommand.puml"])
+        );
+        assert_eq!(
+            get_entry(&index, "c4model/Element/Person")["sources"],
+            serde_json::json!(["c4model/Element/Person.puml"])
+        );
+    }
+
+    #[test]
+    fn test_dry_run() {
+        let root = Path::new("target/tests/cmd/library/generate/dry_run");
+        delete_file_or_directory(root).unwrap();
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "generate",
+            "test/library-simple.yaml",
+            "-u=c4model",
+            "--dry-run",
+            "-O=target/tests/cmd/library/generate/dry_run/distribution",
+            "-C=target/tests/cmd/library/generate/dry_run/cache",
+            "-P=target/tests/cmd/library/generate/dry_run/plantuml.jar",
+        ]);
+        execute_library_generate(
+            arg_matches
+                .subcommand_matches("library")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(!root.exists());
+
+        // the tasks are only listed under the phases they take part in
+        let config = &Config::default();
+        let library = Library::load(Path::new("test/library-simple.yaml")).unwrap();
+        let urns = &[Urn::from("c4model")];
+        let generator = Generator::create(config, &library, urns, &[]).unwrap();
+        let lines = create_plan_lines(&generator);
+        let get_phase_lines = |phase: &str| -> Vec<String> {
+            lines
+                .iter()
+                .skip_while(|line| *line != &format!("{}:", phase))
+                .skip(1)
+                .take_while(|line| line.starts_with("  "))
+                .cloned()
+                .collect()
+        };
+        let cleanup_lines = get_phase_lines("Cleanup");
+        let create_resources_lines = get_phase_lines("Create Resources");
+        let atomic_templates_lines = get_phase_lines("Render Atomic Templates");
+        // every task cleans up its artifacts
+        assert_eq!(
+            cleanup_lines,
+            generator
+                .plan()
+                .iter()
+                .map(|description| format!("  {}", description))
+                .collect::<Vec<String>>()
+        );
+        assert!(create_resources_lines
+            .iter()
+            .all(|line| line.contains("IconTask") || line.contains("SpriteValueTask")));
+        assert!(create_resources_lines
+            .iter()
+            .all(|line| cleanup_lines.contains(line)));
+        assert!(atomic_templates_lines
+            .iter()
+            .all(|line| !create_resources_lines.contains(line)));
+        assert!(atomic_templates_lines
+            .iter()
+            .any(|line| line.contains("ItemSourceTask")));
+    }
+
+    #[test]
+    fn test_outdated_bootstrap() {
+        let root = Path::new("target/tests/cmd/library/generate/outdated_bootstrap");
+        delete_file_or_directory(root).unwrap();
+        create_dir_all(root).unwrap();
+        let manifest_path = root.join("library.yaml");
+        let execute = |font_size_lg: u32| {
+            write(
+                &manifest_path,
+                format!(
+                    r#"
+                    name: library-outdated
+                    remote_url: http://localhost:3000/distribution
+                    customization:
+                      font_size_lg: {}
+                    packages: []
+                "#,
+                    font_size_lg
+                ),
+            )
+            .unwrap();
+            let arg_matches = build_cli().get_matches_from([
+                "plantuml-generator",
+                "-l=Off",
+                "library",
+                "generate",
+                "target/tests/cmd/library/generate/outdated_bootstrap/library.yaml",
+                "-O=target/tests/cmd/library/generate/outdated_bootstrap/distribution",
+                "-C=target/tests/cmd/library/generate/outdated_bootstrap/cache",
+                "-P=test/plantuml-1.2022.4.jar",
+            ]);
+            execute_library_generate(
+                arg_matches
+                    .subcommand_matches("library")
+                    .unwrap()
+                    .subcommand_matches("generate")
+                    .unwrap(),
+            )
+            .unwrap();
+            read_to_string(root.join("distribution/bootstrap.puml")).unwrap()
+        };
+        assert!(execute(20).contains("!global $FONT_SIZE_LG=20"));
+        assert!(execute(30).contains("!global $FONT_SIZE_LG=30"));
+    }
+
+    #[test]
+    fn test_clean_urns_with_glob() {
+        let root = Path::new("target/tests/cmd/library/generate/clean_urns_with_glob/distribution");
+        delete_file_or_directory(root).unwrap();
+        let removed_paths = [
+            root.join("aws-q1-2022/Architecture/Analytics"),
+            root.join("aws-q2-2022/Architecture"),
+        ];
+        let preserved_paths = [
+            root.join("aws-q1-2022/Category"),
+            root.join("gcp/Architecture"),
+        ];
+        for path in removed_paths.iter().chain(preserved_paths.iter()) {
+            create_dir_all(path).unwrap();
+        }
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "generate",
+            "test/library-empty.yaml",
+            "--clean-urn=aws-*/Architecture",
+            "-O=target/tests/cmd/library/generate/clean_urns_with_glob/distribution",
+            "-C=target/tests/cmd/library/generate/clean_urns_with_glob/cache",
+            "-P=test/plantuml-1.2022.4.jar",
+        ]);
+        execute_library_generate(
+            arg_matches
+                .subcommand_matches("library")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap();
+        for path in &removed_paths {
+            assert!(!path.exists());
+        }
+        for path in &preserved_paths {
+            assert!(path.exists());
+        }
+        assert!(root.join("aws-q1-2022").exists());
+    }
+
+    #[test]
+    fn test_resolve_paths_to_clean() {
+        let output_directory = Path::new("target/tests/a_directory");
+        assert_eq!(
+            resolve_paths_to_clean(output_directory, "a_package/a_module").unwrap(),
+            vec![output_directory.join("a_package/a_module")]
+        );
+        assert!(resolve_paths_to_clean(output_directory, "../a_package").is_err());
+        assert!(resolve_paths_to_clean(output_directory, "*/../../a_package").is_err());
+        assert!(resolve_paths_to_clean(output_directory, "/a_package").is_err());
+    }
+
+    #[test]
+    fn test_keep_going() {
+        let root = Path::new("target/tests/cmd/library/generate/keep_going");
+        delete_file_or_directory(root).unwrap();
+        create_dir_all(root).unwrap();
+        write(
+            root.join("library.yaml"),
+            r#"
+            name: library-keep_going
+            remote_url: http://localhost:3000/distribution
+            packages:
+              - urn: PackageA
+                modules:
+                  - urn: PackageA/ModuleA
+                    items:
+                      - urn: PackageA/ModuleA/ItemA
+                        icon:
+                          type: Source
+                          source: test/raw/missing_icon.svg
+                      - urn: PackageA/ModuleA/ItemB
+                        icon:
+                          type: Source
+                          source: test/original_icon.png
+        "#,
+        )
+        .unwrap();
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "generate",
+            "target/tests/cmd/library/generate/keep_going/library.yaml",
+            "--keep-going",
+            "-O=target/tests/cmd/library/generate/keep_going/distribution",
+            "-C=target/tests/cmd/library/generate/keep_going/cache",
+            "-P=test/plantuml-1.2022.4.jar",
+        ]);
+        let error = execute_library_generate(
+            arg_matches
+                .subcommand_matches("library")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(error.contains("Validation:\n- ItemIconTask(PackageA/ModuleA/ItemA)"));
+        assert!(!error.contains("PackageA/ModuleA/ItemB"));
+        assert!(root
+            .join("distribution/PackageA/ModuleA/ItemB.puml")
+            .exists());
+        assert!(!root
+            .join("distribution/PackageA/ModuleA/ItemA.puml")
+            .exists());
+        assert!(root.join("distribution/PackageA/README.md").exists());
+    }
+
+    #[test]
+    fn test_report() {
+        let root = Path::new("target/tests/cmd/library/generate/report");
+        delete_file_or_directory(root).unwrap();
+        create_dir_all(root).unwrap();
+        write(
+            root.join("library.yaml"),
+            r#"
+            name: library-report
+            remote_url: http://localhost:3000/distribution
+            packages:
+              - urn: PackageA
+                modules:
+                  - urn: PackageA/ModuleA
+                    items:
+                      - urn: PackageA/ModuleA/ItemA
+                        icon:
+                          type: Source
+                          source: test/raw/missing_icon.svg
+        "#,
+        )
+        .unwrap();
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "generate",
+            "target/tests/cmd/library/generate/report/library.yaml",
+            "--keep-going",
+            "--report=target/tests/cmd/library/generate/report/reports/report.json",
+            "-O=target/tests/cmd/library/generate/report/distribution",
+            "-C=target/tests/cmd/library/generate/report/cache",
+            "-P=test/plantuml-1.2022.4.jar",
+        ]);
+        assert!(execute_library_generate(
+            arg_matches
+                .subcommand_matches("library")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .is_err());
+        let report: serde_json::Value =
+            serde_json::from_str(&read_to_string(root.join("reports/report.json")).unwrap())
+                .unwrap();
+        assert_eq!(report["succeeded"], false);
+        assert_eq!(report["phases"][0]["phase"], "Validation");
+        assert_eq!(
+            report["failed"].as_u64().unwrap() as usize,
+            report["failures"].as_array().unwrap().len()
+        );
+        assert!(report["generated"].as_u64().unwrap() > 0);
+        let failure = &report["failures"][0];
+        assert_eq!(failure["task"], "ItemIconTask(PackageA/ModuleA/ItemA)");
+        assert_eq!(failure["urn"], "PackageA/ModuleA/ItemA");
+        assert_eq!(failure["phase"], "Validation");
+        assert!(failure["message"]
+            .as_str()
+            .unwrap()
+            .contains("missing_icon.svg"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hooks() {
+        let root = Path::new("target/tests/cmd/library/generate/hooks");
+        delete_file_or_directory(root).unwrap();
+        create_dir_all(root).unwrap();
+        write(
+            root.join("library.yaml"),
+            r#"
+            name: library-hooks
+            remote_url: http://localhost:3000/distribution
+            packages:
+              - urn: PackageA
+                modules:
+                  - urn: PackageA/ModuleA
+                    items:
+                      - urn: PackageA/ModuleA/ItemA
+                        icon:
+                          type: Source
+                          source: target/tests/cmd/library/generate/hooks/icons/ItemA.png
+        "#,
+        )
+        .unwrap();
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "generate",
+            "target/tests/cmd/library/generate/hooks/library.yaml",
+            "--pre-hook=mkdir icons && cp ../../../../../../test/original_icon.png icons/ItemA.png",
+            "--post-hook=echo \"$PLANTUML_GENERATOR_OUTPUT_DIRECTORY\" > post-hook.txt",
+            "-O=target/tests/cmd/library/generate/hooks/distribution",
+            "-C=target/tests/cmd/library/generate/hooks/cache",
+            "-P=test/plantuml-1.2022.4.jar",
+        ]);
+        execute_library_generate(
+            arg_matches
+                .subcommand_matches("library")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(root
+            .join("distribution/PackageA/ModuleA/ItemA.png")
+            .exists());
+        assert!(read_to_string(root.join("post-hook.txt"))
+            .unwrap()
+            .trim()
+            .ends_with("target/tests/cmd/library/generate/hooks/distribution"));
+
+        // the generation is aborted when the pre-hook fails
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "generate",
+            "target/tests/cmd/library/generate/hooks/library.yaml",
+            "--pre-hook=exit 1",
+            "--post-hook=touch post-hook.failed",
+            "-O=target/tests/cmd/library/generate/hooks/distribution",
+            "-C=target/tests/cmd/library/generate/hooks/cache",
+            "-P=test/plantuml-1.2022.4.jar",
+        ]);
+        let error = execute_library_generate(
+            arg_matches
+                .subcommand_matches("library")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("the pre-hook `exit 1` failed"));
+        assert!(!root.join("post-hook.failed").exists());
+    }
+
+    #[test]
+    fn test_verify_output() {
+        let root = Path::new("target/tests/cmd/library/generate/verify_output");
+        delete_file_or_directory(root).unwrap();
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "generate",
+            "test/library-simple.yaml",
+            "--verify-output",
+            "-O=target/tests/cmd/library/generate/verify_output/distribution",
+            "-C=target/tests/cmd/library/generate/verify_output/cache",
+            "-P=test/plantuml-1.2022.4.jar",
+        ]);
+        execute_library_generate(
+            arg_matches
+                .subcommand_matches("library")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap();
+
+        // the illustration of the module documentation goes missing
+        let distribution = root.join("distribution");
+        delete_file_or_directory(&distribution.join("c4model/Element/Person.Local.png")).unwrap();
+        let dangling_references = find_dangling_references(&distribution, "puml").unwrap();
+        assert!(dangling_references.iter().any(|dangling_reference| {
+            dangling_reference.referring_file == Path::new("c4model/Element/README.md")
+                && dangling_reference.reference.ends_with("Person.Local.png")
+        }));
+        assert!(dangling_references
+            .iter()
+            .all(|dangling_reference| dangling_reference.reference.ends_with("Person.Local.png")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_output_mode() {
+        use std::os::unix::fs::PermissionsExt;
+        let root = Path::new("target/tests/cmd/library/generate/output_mode");
+        delete_file_or_directory(root).unwrap();
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "generate",
+            "test/library-simple.yaml",
+            "--only-docs",
+            "--output-mode=640",
+            "-O=target/tests/cmd/library/generate/output_mode/distribution",
+            "-C=target/tests/cmd/library/generate/output_mode/cache",
+        ]);
+        execute_library_generate(
+            arg_matches
+                .subcommand_matches("library")
+                .unwrap()
+                .subcommand_matches("generate")
+                .unwrap(),
+        )
+        .unwrap();
+        let get_mode =
+            |path: &str| root.join(path).metadata().unwrap().permissions().mode() & 0o7777;
+        assert_eq!(get_mode("distribution/c4model/README.md"), 0o640);
+        assert_eq!(get_mode("distribution/c4model/README.md.hash"), 0o640);
+        assert_eq!(get_mode("distribution/c4model"), 0o750);
+        assert_eq!(get_mode("distribution"), 0o750);
+        assert!(build_cli()
+            .try_get_matches_from([
+                "plantuml-generator",
+                "library",
+                "generate",
+                "test/library-simple.yaml",
+                "--output-mode=rw-r--r--",
+            ])
+            .is_err());
     }
 }
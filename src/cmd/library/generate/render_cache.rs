@@ -0,0 +1,138 @@
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+
+use crate::cmd::library::generate::config::Config;
+use crate::constants::RENDER_CACHE_DIRECTORY;
+use crate::utils::{create_parent_directory, write_file_atomically};
+
+/// The rendered artifacts addressed by the content of their sources, it survives the cleanups.
+///
+/// The key covers the source and the files it includes, a change in a file included indirectly,
+/// e.g. by a custom template, requires `--no-render-cache` or `--clean-cache`.
+#[derive(Clone, Debug)]
+pub struct RenderCache {
+    /// The directory of the cached artifacts.
+    directory: PathBuf,
+}
+
+impl RenderCache {
+    /// The render cache of the cache directory, none when it is disabled.
+    pub fn from_config(config: &Config) -> Option<RenderCache> {
+        match config.no_render_cache {
+            true => None,
+            false => Some(RenderCache {
+                directory: Path::new(&config.cache_directory).join(RENDER_CACHE_DIRECTORY),
+            }),
+        }
+    }
+    /// The key of an artifact, the SHA-256 of its sources, its format and its rendering arguments.
+    pub fn compute_key<S: AsRef<str>>(sources: &[&[u8]], format: &str, args: &[S]) -> String {
+        let mut hasher = Sha256::new();
+        let values =
+            std::iter::once(format.as_bytes()).chain(args.iter().map(|a| a.as_ref().as_bytes()));
+        // each value is prefixed by its length, so the boundaries cannot be shifted
+        for value in sources.iter().copied().chain(values) {
+            hasher.update((value.len() as u64).to_le_bytes());
+            hasher.update(value);
+        }
+        format!("{:x}.{}", hasher.finalize(), format)
+    }
+    /// Copy the cached artifact to the destination, false when the artifact is not cached.
+    pub fn restore(&self, key: &str, destination_path: &Path) -> Result<bool> {
+        let cached_path = self.directory.join(key);
+        if !cached_path.exists() {
+            return Ok(false);
+        }
+        log::debug!(
+            "restore {} from the render cache",
+            destination_path.display()
+        );
+        create_parent_directory(destination_path)?;
+        std::fs::copy(&cached_path, destination_path).map_err(|e| {
+            anyhow::Error::new(e).context(format!("unable to restore {}", cached_path.display()))
+        })?;
+        Ok(true)
+    }
+    /// Store a rendered artifact, an interrupted copy is never cached.
+    pub fn store(&self, key: &str, artifact_path: &Path) -> Result<()> {
+        let cached_path = self.directory.join(key);
+        create_parent_directory(&cached_path)?;
+        let mut artifact_file = File::open(artifact_path).map_err(|e| {
+            anyhow::Error::new(e).context(format!("unable to open {}", artifact_path.display()))
+        })?;
+        write_file_atomically(&cached_path, |writer| {
+            io::copy(&mut artifact_file, writer).map_err(|e| {
+                anyhow::Error::new(e)
+                    .context(format!("unable to store {}", artifact_path.display()))
+            })?;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::{read_to_string, write};
+
+    use crate::utils::{create_directory, delete_file_or_directory};
+
+    use super::*;
+
+    #[test]
+    fn test_compute_key() {
+        let source: &[u8] = b"@startuml\n@enduml";
+        let key = RenderCache::compute_key(&[source], "png", &["-Playout=smetana"]);
+        assert!(key.ends_with(".png"));
+        assert_eq!(key.len(), 64 + ".png".len());
+        assert_eq!(
+            key,
+            RenderCache::compute_key(&[source], "png", &["-Playout=smetana"])
+        );
+        assert_ne!(
+            key,
+            RenderCache::compute_key(&[source], "png", &["-Playout=dot"])
+        );
+        assert_ne!(
+            key,
+            RenderCache::compute_key(&[source], "svg", &["-Playout=smetana"])
+        );
+        assert_ne!(
+            key,
+            RenderCache::compute_key(
+                &[&b"@startuml\n\n@enduml"[..]],
+                "png",
+                &["-Playout=smetana"]
+            )
+        );
+        assert_ne!(
+            key,
+            RenderCache::compute_key(&[source, b"included"], "png", &["-Playout=smetana"])
+        );
+        assert_ne!(
+            RenderCache::compute_key(&[&b"ab"[..], b"c"], "png", &[] as &[&str]),
+            RenderCache::compute_key(&[&b"a"[..], b"bc"], "png", &[] as &[&str])
+        );
+    }
+
+    #[test]
+    fn test_restore_and_store() {
+        let directory = Path::new("target/tests/render_cache");
+        delete_file_or_directory(directory).unwrap();
+        create_directory(directory).unwrap();
+        let render_cache = RenderCache {
+            directory: directory.join("cache"),
+        };
+        let artifact_path = directory.join("artifact.png");
+        let key = RenderCache::compute_key::<&str>(&[&b"source"[..]], "png", &[]);
+        assert!(!render_cache.restore(&key, &artifact_path).unwrap());
+        write(&artifact_path, "content").unwrap();
+        render_cache.store(&key, &artifact_path).unwrap();
+        let restored_path = directory.join("restored/artifact.png");
+        assert!(render_cache.restore(&key, &restored_path).unwrap());
+        assert_eq!(read_to_string(restored_path).unwrap(), "content");
+    }
+}
@@ -0,0 +1,138 @@
+use std::fmt::Debug;
+use std::fs::read;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::utils::{create_parent_directory, write_file_atomically};
+
+/// The destination of the rendered artifacts, addressed by their path from the output directory.
+pub trait OutputSink: Debug + Send + Sync {
+    /// Write an artifact, the previous content is replaced.
+    fn write(&self, relative_path: &Path, bytes: &[u8]) -> Result<()>;
+    /// Read an artifact, none when it has not been written.
+    fn read(&self, relative_path: &Path) -> Result<Option<Vec<u8>>>;
+}
+
+/// The sink writing the artifacts in the output directory.
+#[derive(Debug)]
+pub struct FsSink {
+    /// The path to the output directory.
+    output_directory: PathBuf,
+}
+
+impl FsSink {
+    pub fn new(output_directory: &str) -> FsSink {
+        FsSink {
+            output_directory: PathBuf::from(output_directory),
+        }
+    }
+}
+
+impl OutputSink for FsSink {
+    fn write(&self, relative_path: &Path, bytes: &[u8]) -> Result<()> {
+        let path = self.output_directory.join(relative_path);
+        create_parent_directory(&path)?;
+        write_file_atomically(&path, |file| {
+            file.write_all(bytes).map_err(|e| {
+                anyhow::Error::new(e).context(format!("unable to write {}", path.display()))
+            })
+        })
+    }
+    fn read(&self, relative_path: &Path) -> Result<Option<Vec<u8>>> {
+        let path = self.output_directory.join(relative_path);
+        if !path.exists() {
+            return Ok(None);
+        }
+        read(&path).map(Some).map_err(|e| {
+            anyhow::Error::new(e).context(format!("unable to read {}", path.display()))
+        })
+    }
+}
+
+/// The sink of a task, by default the output directory.
+pub fn resolve_sink(
+    sink: &Option<Arc<dyn OutputSink>>,
+    output_directory: &str,
+) -> Arc<dyn OutputSink> {
+    match sink {
+        Some(sink) => sink.clone(),
+        None => Arc::new(FsSink::new(output_directory)),
+    }
+}
+
+/// The sink keeping the artifacts in memory, i.e. the tests don't need an output directory.
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct MemorySink {
+    /// The artifacts indexed by their relative paths.
+    files: std::sync::Mutex<std::collections::HashMap<PathBuf, Vec<u8>>>,
+}
+
+#[cfg(test)]
+impl MemorySink {
+    /// The content of an artifact as a string, none when it has not been written.
+    pub fn get_content(&self, relative_path: &str) -> Option<String> {
+        self.files
+            .lock()
+            .unwrap()
+            .get(Path::new(relative_path))
+            .map(|bytes| String::from_utf8_lossy(bytes).to_string())
+    }
+}
+
+#[cfg(test)]
+impl OutputSink for MemorySink {
+    fn write(&self, relative_path: &Path, bytes: &[u8]) -> Result<()> {
+        self.files
+            .lock()
+            .map_err(|_| anyhow::Error::msg("unable to lock the memory sink"))?
+            .insert(relative_path.to_path_buf(), bytes.to_vec());
+        Ok(())
+    }
+    fn read(&self, relative_path: &Path) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .files
+            .lock()
+            .map_err(|_| anyhow::Error::msg("unable to lock the memory sink"))?
+            .get(relative_path)
+            .cloned())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::utils::delete_file_or_directory;
+
+    use super::*;
+
+    #[test]
+    fn test_fs_sink() {
+        let directory = "target/tests/sink/fs";
+        delete_file_or_directory(Path::new(directory)).unwrap();
+        let sink = FsSink::new(directory);
+        let relative_path = Path::new("Package/Module/Item.puml");
+        assert_eq!(sink.read(relative_path).unwrap(), None);
+        sink.write(relative_path, b"content").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(Path::new(directory).join(relative_path)).unwrap(),
+            "content"
+        );
+        assert_eq!(sink.read(relative_path).unwrap(), Some(b"content".to_vec()));
+    }
+
+    #[test]
+    fn test_memory_sink() {
+        let sink = MemorySink::default();
+        let relative_path = Path::new("Package/Module/Item.puml");
+        assert_eq!(sink.read(relative_path).unwrap(), None);
+        sink.write(relative_path, b"content").unwrap();
+        assert_eq!(sink.read(relative_path).unwrap(), Some(b"content".to_vec()));
+        assert_eq!(
+            sink.get_content("Package/Module/Item.puml"),
+            Some("content".to_string())
+        );
+    }
+}
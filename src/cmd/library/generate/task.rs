@@ -1,13 +1,18 @@
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
 use std::str::FromStr;
 
 use anyhow::Result;
-use tera::Tera;
+use tera::{Context, Tera};
 
 use crate::plantuml::PlantUML;
 
-#[derive(Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq)]
 pub enum CleanupScope {
     All,
+    Bootstrap,
+    Documentation,
+    Embedded,
     Example,
     Item,
     ItemIcon,
@@ -26,6 +31,9 @@ impl FromStr for CleanupScope {
     fn from_str(s: &str) -> Result<Self> {
         match s {
             "All" => Ok(CleanupScope::All),
+            "Bootstrap" => Ok(CleanupScope::Bootstrap),
+            "Documentation" => Ok(CleanupScope::Documentation),
+            "Embedded" => Ok(CleanupScope::Embedded),
             "Example" => Ok(CleanupScope::Example),
             "Item" => Ok(CleanupScope::Item),
             "ItemIcon" => Ok(CleanupScope::ItemIcon),
@@ -51,6 +59,12 @@ impl CleanupScope {
             return true;
         }
         match self {
+            CleanupScope::Embedded => {
+                // the embedded files are composed of the item sources
+                scopes.contains(&CleanupScope::All)
+                    || scopes.contains(&CleanupScope::Item)
+                    || scopes.contains(&CleanupScope::ItemSource)
+            }
             CleanupScope::ItemIcon => {
                 scopes.contains(&CleanupScope::All) || scopes.contains(&CleanupScope::Item)
             }
@@ -82,17 +96,77 @@ impl CleanupScope {
     }
 }
 
-pub trait Task {
+/// The kind of the artifacts of a task, e.g. to group them in the index of the generation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OutputKind {
+    /// The PlantUML sources, e.g. the items and the bootstraps.
+    Source,
+    /// The icons of the items.
+    Icon,
+    /// The debug icons of the sprites.
+    Sprite,
+    /// The sources and the images of the snippets.
+    Snippet,
+    /// The documentation, e.g. the README files.
+    Documentation,
+    /// The sources and the images of the examples.
+    Example,
+    /// The artifacts of the other kinds.
+    Other,
+}
+
+/// The phase deleting the artifacts, e.g. when they are outdated.
+pub const PHASE_CLEANUP: &str = "Cleanup";
+/// The phase creating the resources, e.g. the icons and the sprites.
+pub const PHASE_CREATE_RESOURCES: &str = "Create Resources";
+/// The phase rendering the templates standing on their own.
+pub const PHASE_RENDER_ATOMIC_TEMPLATES: &str = "Render Atomic Templates";
+/// The phase rendering the templates composed of the atomic ones.
+pub const PHASE_RENDER_COMPOSED_TEMPLATES: &str = "Render Composed Templates";
+/// The phase rendering the PlantUML sources.
+pub const PHASE_RENDER_SOURCES: &str = "Render Sources";
+
+/// The tasks are created and executed in parallel, so they must be shareable across threads.
+pub trait Task: Send + Sync {
+    /// A short description of the task, e.g. `ItemSourceTask(Package/Module/Item)`.
+    fn describe(&self) -> String {
+        let type_name = std::any::type_name::<Self>();
+        type_name
+            .rsplit("::")
+            .next()
+            .unwrap_or(type_name)
+            .to_string()
+    }
+    /// The phases in which the task does something, every task cleans up its artifacts.
+    fn phases(&self) -> Vec<&'static str> {
+        vec![PHASE_CLEANUP]
+    }
+    /// Check cheaply the preconditions of the task before any work is done, e.g. the cleanup.
+    fn validate(&self, _scopes: &[CleanupScope]) -> Result<()> {
+        Ok(())
+    }
+    /// The paths of the artifacts produced in the output directory.
+    fn outputs(&self) -> Vec<PathBuf> {
+        vec![]
+    }
+    /// The kind of the artifacts produced in the output directory.
+    fn output_kind(&self) -> OutputKind {
+        OutputKind::Other
+    }
+    /// The paths of the PlantUML sources rendered by `render_sources`.
+    fn sources(&self) -> Vec<PathBuf> {
+        vec![]
+    }
     fn cleanup(&self, _scopes: &[CleanupScope]) -> Result<()> {
         Ok(())
     }
     fn create_resources(&self) -> Result<()> {
         Ok(())
     }
-    fn render_atomic_templates(&self, _tera: &Tera) -> Result<()> {
+    fn render_atomic_templates(&self, _tera: &Tera, _context: &Context) -> Result<()> {
         Ok(())
     }
-    fn render_composed_templates(&self, _tera: &Tera) -> Result<()> {
+    fn render_composed_templates(&self, _tera: &Tera, _context: &Context) -> Result<()> {
         Ok(())
     }
     fn render_sources(&self, _plantuml: &PlantUML) -> Result<()> {
@@ -100,10 +174,69 @@ pub trait Task {
     }
 }
 
+/// The failure of a task during a phase of the generation.
+#[derive(Debug)]
+pub struct TaskError {
+    /// The description of the failed task, e.g. `ItemSourceTask(Package/Module/Item)`.
+    pub task: String,
+    /// The name of the phase, e.g. `Render Sources`.
+    pub phase: String,
+    /// The cause of the failure.
+    pub cause: anyhow::Error,
+}
+
+impl TaskError {
+    /// Wrap the failure of a task with the task and the phase.
+    pub fn wrap<T>(task: &dyn Task, phase: &str, result: Result<T>) -> Result<T, TaskError> {
+        result.map_err(|cause| TaskError {
+            task: task.describe(),
+            phase: phase.to_string(),
+            cause,
+        })
+    }
+}
+
+impl Display for TaskError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // the cause is the source of the error, i.e. it is rendered by the chain
+        write!(f, "{} failed during the {} phase", self.task, self.phase)
+    }
+}
+
+impl std::error::Error for TaskError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.cause.as_ref())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    struct FailingTask;
+
+    impl Task for FailingTask {
+        fn describe(&self) -> String {
+            "FailingTask(PackageA/ModuleA/ItemA)".to_string()
+        }
+    }
+
+    #[test]
+    fn test_task_error() {
+        let result: Result<()> = Err(anyhow::Error::msg("unable to render ItemA"));
+        let error = TaskError::wrap(&FailingTask, "Render Sources", result).unwrap_err();
+        assert_eq!(error.phase, "Render Sources");
+        let error = anyhow::Error::new(error);
+        assert_eq!(
+            error.to_string(),
+            "FailingTask(PackageA/ModuleA/ItemA) failed during the Render Sources phase"
+        );
+        assert_eq!(
+            format!("{:#}", error),
+            "FailingTask(PackageA/ModuleA/ItemA) failed during the Render Sources phase: unable to render ItemA"
+        );
+    }
+
     #[test]
     fn test_scope() {
         assert!(CleanupScope::All.is_included_in(&[CleanupScope::All]));
@@ -113,5 +246,10 @@ mod tests {
         assert!(CleanupScope::ItemSource.is_included_in(&[CleanupScope::Item]));
         assert!(CleanupScope::ItemIcon.is_included_in(&[CleanupScope::Item]));
         assert!(!CleanupScope::Item.is_included_in(&[CleanupScope::ItemSource]));
+        assert!(CleanupScope::Documentation.is_included_in(&[CleanupScope::All]));
+        assert!(!CleanupScope::Documentation.is_included_in(&[CleanupScope::Item]));
+        assert!(CleanupScope::Embedded.is_included_in(&[CleanupScope::ItemSource]));
+        assert!(!CleanupScope::Embedded.is_included_in(&[CleanupScope::ItemIcon]));
+        assert!(!CleanupScope::Bootstrap.is_included_in(&[CleanupScope::Item]));
     }
 }
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 use std::fmt;
-use std::fs::File;
-use std::path::Path;
+use std::fs::read;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use heck::{ToTitleCase, ToUpperCamelCase};
@@ -10,13 +10,20 @@ use serde_json::Value;
 use tera::{Context, Tera};
 
 use crate::cmd::library::generate::config::Config;
-use crate::cmd::library::generate::task::{CleanupScope, Task};
+use crate::cmd::library::generate::fingerprint::{
+    compute_fingerprint, is_outdated, write_fingerprint,
+};
+use crate::cmd::library::generate::render_cache::RenderCache;
+use crate::cmd::library::generate::task::{
+    CleanupScope, OutputKind, Task, PHASE_CLEANUP, PHASE_RENDER_ATOMIC_TEMPLATES,
+    PHASE_RENDER_SOURCES,
+};
 use crate::cmd::library::manifest::element::{Element, Shape};
 use crate::cmd::library::manifest::item::Item;
 use crate::cmd::library::manifest::library::Library;
 use crate::cmd::library::manifest::package::Package;
 use crate::plantuml::PlantUML;
-use crate::utils::{create_parent_directory, delete_file};
+use crate::utils::{create_parent_directory, delete_file, write_file_atomically};
 
 #[derive(Debug, Clone, Eq, Deserialize, Serialize)]
 pub enum SnippetMode {
@@ -43,6 +50,8 @@ impl fmt::Display for SnippetMode {
 pub struct ElementSnippetTask {
     /// The URL to fetch the library remotely.
     remote_url: String,
+    /// The extension of the generated sources.
+    source_extension: String,
     /// The URN of the package
     package_urn: String,
     /// The URN of the Item.
@@ -71,6 +80,12 @@ pub struct ElementSnippetTask {
     full_destination_image_path: String,
     /// A set of custom properties.
     properties: HashMap<String, Value>,
+    /// The paths of the sources included by the snippet, i.e. the bootstraps and the item.
+    #[serde(skip)]
+    full_included_source_paths: Vec<String>,
+    /// The cache of the rendered images, when enabled.
+    #[serde(skip)]
+    render_cache: Option<RenderCache>,
 }
 
 impl ElementSnippetTask {
@@ -149,6 +164,23 @@ impl ElementSnippetTask {
                 },
             };
 
+        let full_included_source_paths = [
+            format!("bootstrap.{}", library.rendering.source_extension),
+            format!(
+                "{}/bootstrap.{}",
+                package.urn.value, library.rendering.source_extension
+            ),
+            format!("{}.{}", item.urn.value, library.rendering.source_extension),
+        ]
+        .iter()
+        .map(|path| {
+            Path::new(&config.output_directory)
+                .join(path)
+                .to_string_lossy()
+                .to_string()
+        })
+        .collect();
+
         let properties = match &element.shape {
             Shape::Custom { properties } => properties.clone(),
             _ => HashMap::default(),
@@ -156,6 +188,7 @@ impl ElementSnippetTask {
 
         Ok(ElementSnippetTask {
             remote_url: library.remote_url.clone(),
+            source_extension: library.rendering.source_extension.clone(),
             package_urn: package.urn.value.clone(),
             item_urn: item.urn.value.clone(),
             path_to_base: item.urn.get_parent().path_to_base,
@@ -170,11 +203,47 @@ impl ElementSnippetTask {
             full_destination_source_path,
             full_destination_image_path,
             properties,
+            full_included_source_paths,
+            render_cache: RenderCache::from_config(config),
         })
     }
 }
 
 impl Task for ElementSnippetTask {
+    fn describe(&self) -> String {
+        format!(
+            "ElementSnippetTask({}/{}/{})",
+            self.item_urn, self.element_shape, self.snippet_mode
+        )
+    }
+
+    fn phases(&self) -> Vec<&'static str> {
+        vec![
+            PHASE_CLEANUP,
+            PHASE_RENDER_ATOMIC_TEMPLATES,
+            PHASE_RENDER_SOURCES,
+        ]
+    }
+
+    fn outputs(&self) -> Vec<PathBuf> {
+        vec![
+            PathBuf::from(&self.full_destination_source_path),
+            PathBuf::from(&self.full_destination_image_path),
+        ]
+    }
+
+    fn output_kind(&self) -> OutputKind {
+        OutputKind::Snippet
+    }
+
+    fn sources(&self) -> Vec<PathBuf> {
+        // the remote snippets are not rendered
+        match self.snippet_mode {
+            SnippetMode::Local => vec![PathBuf::from(&self.full_destination_source_path)],
+            SnippetMode::Remote => vec![],
+        }
+    }
+
     fn cleanup(&self, _scopes: &[CleanupScope]) -> Result<()> {
         log::debug!(
             "{}/{}/{} - ElementSnippetTask - cleanup",
@@ -191,7 +260,7 @@ impl Task for ElementSnippetTask {
         Ok(())
     }
 
-    fn render_atomic_templates(&self, _tera: &Tera) -> Result<()> {
+    fn render_atomic_templates(&self, _tera: &Tera, _context: &Context) -> Result<()> {
         log::debug!(
             "{}/{}/{} - ElementSnippetTask - render templates",
             &self.item_urn,
@@ -202,25 +271,27 @@ impl Task for ElementSnippetTask {
         let destination_path = Path::new(&self.full_destination_source_path);
 
         // skip early when generation not required
-        if destination_path.exists() {
+        let fingerprint = compute_fingerprint(self, _tera, &self.template)?;
+        if !is_outdated(destination_path, &fingerprint) {
             return Ok(());
         }
 
         // create the destination directory
         create_parent_directory(destination_path)?;
 
-        // create the destination file
-        let destination_file = File::create(destination_path).map_err(|e| {
-            anyhow::Error::new(e).context("unable to create the destination file".to_string())
-        })?;
-
-        let mut context = Context::new();
+        let mut context = _context.clone();
         context.insert("data", &self);
-        _tera
-            .render_to(&self.template, &context, destination_file)
-            .map_err(|e| {
-                anyhow::Error::new(e).context(format!("unable to render {}", &self.template))
-            })
+        // the destination file is only created once the rendering is complete
+        write_file_atomically(destination_path, |destination_file| {
+            _tera
+                .render_to(&self.template, &context, destination_file)
+                .map_err(|e| {
+                    anyhow::Error::new(e).context(format!("unable to render {}", &self.template))
+                })
+        })?;
+        // the image is rendered again from the new source
+        delete_file(Path::new(&self.full_destination_image_path))?;
+        write_fingerprint(destination_path, &fingerprint)
     }
 
     fn render_sources(&self, plantuml: &PlantUML) -> Result<()> {
@@ -242,23 +313,56 @@ impl Task for ElementSnippetTask {
             return Ok(());
         }
 
-        // render the snippet
+        // restore the image when the same sources have already been rendered
         let source_path = Path::new(&self.full_destination_source_path);
+        let cache_entry = match &self.render_cache {
+            None => None,
+            Some(render_cache) => {
+                let mut sources = vec![read(source_path).map_err(|e| {
+                    anyhow::Error::new(e)
+                        .context(format!("unable to read {}", source_path.display()))
+                })?];
+                // a missing included source is part of the key as an empty one
+                for included_source_path in &self.full_included_source_paths {
+                    sources.push(read(included_source_path).unwrap_or_default());
+                }
+                let sources: Vec<&[u8]> = sources.iter().map(|source| source.as_slice()).collect();
+                let format = destination_path
+                    .extension()
+                    .map(|e| e.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let key = RenderCache::compute_key(&sources, &format, &plantuml.get_cache_args());
+                Some((render_cache, key))
+            }
+        };
+        if let Some((render_cache, key)) = &cache_entry {
+            if render_cache.restore(key, destination_path)? {
+                return Ok(());
+            }
+        }
+
+        // render the snippet
         plantuml.render(source_path, None)?;
 
+        if let Some((render_cache, key)) = &cache_entry {
+            render_cache.store(key, destination_path)?;
+        }
+
         Ok(())
     }
 }
 
 #[cfg(test)]
 mod test {
-    use std::fs::read_to_string;
+    use std::fs::{read_to_string, write};
 
     use crate::cmd::library::generate::tasks::item::element_snippet::SnippetMode::{Local, Remote};
     use crate::cmd::library::generate::templates::TEMPLATES;
     use crate::constants::get_default_template_item_snippet;
+    use crate::plantuml::create_plantuml;
     use crate::tera::create_tera;
     use crate::urn::Urn;
+    use crate::utils::delete_file_or_directory;
 
     use super::*;
 
@@ -270,6 +374,7 @@ mod test {
             for &snippet_mode in [&Remote, &Local].iter() {
                 let generator = ElementSnippetTask {
                     remote_url: "a remote url".to_string(),
+                    source_extension: "puml".to_string(),
                     package_urn: "PackageA".to_string(),
                     item_urn: String::from(&item_urn.value),
                     path_to_base: String::from(&item_urn.path_to_base),
@@ -290,9 +395,13 @@ mod test {
                         shape
                     ),
                     properties: HashMap::default(),
+                    full_included_source_paths: vec![],
+                    render_cache: None,
                 };
                 generator.cleanup(&[CleanupScope::All]).unwrap();
-                generator.render_atomic_templates(tera).unwrap();
+                generator
+                    .render_atomic_templates(tera, &Context::new())
+                    .unwrap();
                 let content = read_to_string(generator.full_destination_source_path).unwrap();
                 if snippet_mode.eq(&Remote) {
                     assert!(content.contains(r##"!global $LIB_BASE_LOCATION="a remote url""##));
@@ -305,6 +414,74 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_render_sources_from_render_cache() {
+        let directory = Path::new("target/tests/element_snippet/render_cache");
+        delete_file_or_directory(directory).unwrap();
+        create_parent_directory(&directory.join("snippet.puml")).unwrap();
+        write(
+            directory.join("snippet.puml"),
+            "@startuml\n!include Item.puml\n@enduml\n",
+        )
+        .unwrap();
+        write(directory.join("Item.puml"), "A -> B\n").unwrap();
+        let config = &Config {
+            cache_directory: "target/tests/element_snippet/render_cache/cache".to_string(),
+            ..Config::default()
+        };
+        let generator = ElementSnippetTask {
+            remote_url: "a remote url".to_string(),
+            source_extension: "puml".to_string(),
+            package_urn: "PackageA".to_string(),
+            item_urn: "PackageA/ModuleB/Item".to_string(),
+            path_to_base: "../..".to_string(),
+            element_shape: "Icon".to_string(),
+            snippet_mode: Local,
+            procedure_name: "ItemIcon".to_string(),
+            variable_name: "item".to_string(),
+            primary_label: "Item".to_string(),
+            technical_label: None,
+            description_label: None,
+            template: get_default_template_item_snippet(),
+            full_destination_source_path: "target/tests/element_snippet/render_cache/snippet.puml"
+                .to_string(),
+            full_destination_image_path: "target/tests/element_snippet/render_cache/snippet.png"
+                .to_string(),
+            properties: HashMap::default(),
+            full_included_source_paths: vec![
+                "target/tests/element_snippet/render_cache/Item.puml".to_string()
+            ],
+            render_cache: RenderCache::from_config(config),
+        };
+        let image_path = Path::new(&generator.full_destination_image_path);
+        let plantuml = &create_plantuml(
+            &config.java_binary,
+            "test/plantuml-1.2022.4.jar",
+            &config.plantuml_version,
+        )
+        .unwrap();
+        generator.render_sources(plantuml).unwrap();
+        assert!(image_path.exists());
+        delete_file(image_path).unwrap();
+        // PlantUML cannot be executed, so the image can only come from the render cache
+        let missing_plantuml = &create_plantuml(
+            "target/tests/element_snippet/a_missing_java",
+            "test/plantuml-1.2022.4.jar",
+            &config.plantuml_version,
+        )
+        .unwrap()
+        .with_retries(0);
+        generator.render_sources(missing_plantuml).unwrap();
+        assert!(image_path.exists());
+        // the included item changes, so the image must be rendered again
+        delete_file(image_path).unwrap();
+        write(directory.join("Item.puml"), "A -> C\n").unwrap();
+        assert!(generator.render_sources(missing_plantuml).is_err());
+        assert!(!image_path.exists());
+        generator.render_sources(plantuml).unwrap();
+        assert!(image_path.exists());
+    }
+
     #[test]
     fn test_render_templates_custom() {
         let tera = &create_tera(TEMPLATES.to_vec(), Some("test/tera/**".to_string())).unwrap();
@@ -312,6 +489,7 @@ mod test {
         for &snippet_mode in [&Remote, &Local].iter() {
             let generator = ElementSnippetTask {
                 remote_url: "a remote url".to_string(),
+                source_extension: "puml".to_string(),
                 package_urn: "PackageA".to_string(),
                 item_urn: String::from(&item_urn.value),
                 path_to_base: String::from(&item_urn.path_to_base),
@@ -328,9 +506,13 @@ mod test {
                 full_destination_image_path: "target/tests/element_snippet/source.Custom.png"
                     .to_string(),
                 properties: HashMap::default(),
+                full_included_source_paths: vec![],
+                render_cache: None,
             };
             generator.cleanup(&[CleanupScope::All]).unwrap();
-            generator.render_atomic_templates(tera).unwrap();
+            generator
+                .render_atomic_templates(tera, &Context::new())
+                .unwrap();
             let content = read_to_string(generator.full_destination_source_path).unwrap();
             if snippet_mode.eq(&Remote) {
                 assert!(content.contains(r##"!global $LIB_BASE_LOCATION="a remote url""##));
@@ -1,15 +1,23 @@
-use std::fs::File;
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use tera::{Context, Tera};
 
 use crate::cmd::library::generate::config::Config;
-use crate::cmd::library::generate::task::{CleanupScope, Task};
+use crate::cmd::library::generate::fingerprint::{
+    compute_fingerprint, is_outdated, write_fingerprint,
+};
+use crate::cmd::library::generate::task::{
+    CleanupScope, OutputKind, Task, PHASE_CLEANUP, PHASE_RENDER_ATOMIC_TEMPLATES,
+};
 use crate::cmd::library::manifest::item::Item;
 use crate::cmd::library::manifest::library::Library;
-use crate::utils::{create_parent_directory, delete_file};
+use crate::constants::{TEMPLATE_ITEM_DOCUMENTATION, TEMPLATE_ITEM_DOCUMENTATION_ASCIIDOC};
+use crate::utils::{create_parent_directory, delete_file, write_file_atomically};
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(tag = "type")]
@@ -19,16 +27,18 @@ pub enum Object {
         name: String,
         /// The relative path to the illustration from the Item directory.
         illustration_path: String,
+        /// The relative path to the `@2x` illustration from the Item directory, when generated.
+        retina_illustration_path: Option<String>,
     },
     Element {
         /// The name of the element.
         name: String,
         /// The relative path to the illustration from the Item directory.
         illustration_path: String,
-        /// The path to the local snippet.
-        full_snippet_local_path: String,
-        /// The path to the remote snippet.
-        full_snippet_remote_path: String,
+        /// The path to the local snippet, unless it is not generated.
+        full_snippet_local_path: Option<String>,
+        /// The path to the remote snippet, unless it is not generated.
+        full_snippet_remote_path: Option<String>,
     },
 }
 
@@ -46,6 +56,12 @@ pub struct ItemDocumentationTask {
     output_directory: String,
     /// The name of the Tera template
     template: String,
+    /// The extension of the documentation file.
+    extension: String,
+    /// When true, the sources of the local snippets are inlined.
+    inline_snippets: bool,
+    /// The metadata of the Item, sorted by key.
+    metadata: BTreeMap<String, Value>,
 }
 
 impl ItemDocumentationTask {
@@ -61,31 +77,58 @@ impl ItemDocumentationTask {
                 name: "Illustration".to_string(),
                 illustration_path: icon
                     .get_icon_path(&item.urn, &library.customization.icon_format),
+                retina_illustration_path: match library.customization.retina_icons {
+                    false => None,
+                    true => Some(icon.get_scaled_icon_path(
+                        &item.urn,
+                        &library.customization.icon_format,
+                        2,
+                    )),
+                },
             })
         }
 
+        // the snippets are not generated along with the documentation only
+        let has_local_snippet = config.snippet_mode.has_local()
+            && config.profile.has_local_assets()
+            && !config.only_docs;
+        let has_remote_snippet = config.snippet_mode.has_remote() && !config.only_docs;
         for element in &item.elements {
             objects.push(Object::Element {
                 name: element.shape.get_element_name(&item.urn),
                 illustration_path: element
                     .shape
                     .get_local_snippet_image_path(&item.urn, &library.customization.icon_format),
-                full_snippet_local_path: Path::new(&config.output_directory)
-                    .join(element.shape.get_local_snippet_puml_path(&item.urn))
-                    .as_path()
-                    .to_str()
-                    .map(|v| v.to_string())
-                    .ok_or_else(|| {
-                        anyhow::Error::msg("unable to get full_snippet_local_path".to_string())
-                    })?,
-                full_snippet_remote_path: Path::new(&config.output_directory)
-                    .join(element.shape.get_remote_snippet_puml_path(&item.urn))
-                    .as_path()
-                    .to_str()
-                    .map(|v| v.to_string())
-                    .ok_or_else(|| {
-                        anyhow::Error::msg("unable to get full_snippet_remote_path".to_string())
-                    })?,
+                full_snippet_local_path: match has_local_snippet {
+                    false => None,
+                    true => Some(
+                        Path::new(&config.output_directory)
+                            .join(element.shape.get_local_snippet_puml_path(&item.urn))
+                            .as_path()
+                            .to_str()
+                            .map(|v| v.to_string())
+                            .ok_or_else(|| {
+                                anyhow::Error::msg(
+                                    "unable to get full_snippet_local_path".to_string(),
+                                )
+                            })?,
+                    ),
+                },
+                full_snippet_remote_path: match has_remote_snippet {
+                    false => None,
+                    true => Some(
+                        Path::new(&config.output_directory)
+                            .join(element.shape.get_remote_snippet_puml_path(&item.urn))
+                            .as_path()
+                            .to_str()
+                            .map(|v| v.to_string())
+                            .ok_or_else(|| {
+                                anyhow::Error::msg(
+                                    "unable to get full_snippet_remote_path".to_string(),
+                                )
+                            })?,
+                    ),
+                },
             });
         }
 
@@ -95,27 +138,79 @@ impl ItemDocumentationTask {
             objects,
             path_to_base: item.urn.get_parent().path_to_base,
             output_directory: config.output_directory.clone(),
-            template: item.templates.documentation.clone(),
+            template: config.documentation_format.resolve_template(
+                &item.templates.documentation,
+                TEMPLATE_ITEM_DOCUMENTATION,
+                TEMPLATE_ITEM_DOCUMENTATION_ASCIIDOC,
+            ),
+            extension: config.documentation_format.get_extension(),
+            inline_snippets: config.inline_snippets,
+            metadata: item
+                .metadata
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
         })
     }
     pub fn get_relative_documentation_path(&self) -> Box<Path> {
-        Box::from(Path::new(format!("{}.md", self.item_urn, ).as_str()))
+        Box::from(Path::new(
+            format!("{}.{}", self.item_urn, self.extension).as_str(),
+        ))
     }
     fn get_full_documentation_path(&self) -> Box<Path> {
         Path::new(&self.output_directory)
             .join(self.get_relative_documentation_path())
             .into_boxed_path()
     }
+    /// The sources of the local snippets indexed by the element names.
+    fn read_snippet_sources(&self) -> BTreeMap<String, String> {
+        let mut snippet_sources = BTreeMap::new();
+        for object in &self.objects {
+            if let Object::Element {
+                name,
+                full_snippet_local_path: Some(path),
+                ..
+            } = object
+            {
+                // the snippets are rendered before the documentation of their item
+                match read_to_string(path) {
+                    Ok(source) => {
+                        snippet_sources.insert(name.clone(), source);
+                    }
+                    Err(_) => log::warn!("unable to inline {}, it is not generated", path),
+                }
+            }
+        }
+        snippet_sources
+    }
 }
 
 impl Task for ItemDocumentationTask {
+    fn outputs(&self) -> Vec<PathBuf> {
+        vec![self.get_full_documentation_path().to_path_buf()]
+    }
+
+    fn output_kind(&self) -> OutputKind {
+        OutputKind::Documentation
+    }
+
+    fn describe(&self) -> String {
+        format!("ItemDocumentationTask({})", self.item_urn)
+    }
+
+    fn phases(&self) -> Vec<&'static str> {
+        vec![PHASE_CLEANUP, PHASE_RENDER_ATOMIC_TEMPLATES]
+    }
+
     fn cleanup(&self, _scopes: &[CleanupScope]) -> Result<()> {
         log::debug!("{} - ItemDocumentationTask - cleanup", &self.item_urn);
-        delete_file(self.get_full_documentation_path().as_ref())?;
+        if CleanupScope::Documentation.is_included_in(_scopes) {
+            delete_file(self.get_full_documentation_path().as_ref())?;
+        }
         Ok(())
     }
 
-    fn render_atomic_templates(&self, _tera: &Tera) -> Result<()> {
+    fn render_atomic_templates(&self, _tera: &Tera, _context: &Context) -> Result<()> {
         log::debug!(
             "{} - ItemDocumentationTask - render templates",
             &self.item_urn
@@ -123,37 +218,48 @@ impl Task for ItemDocumentationTask {
 
         let destination_path = self.get_full_documentation_path();
 
+        // the inlined sources are part of the fingerprint
+        let snippet_sources = match self.inline_snippets {
+            true => Some(self.read_snippet_sources()),
+            false => None,
+        };
+
         // skip early when generation not required
-        if destination_path.exists() {
+        let fingerprint = compute_fingerprint(&(self, &snippet_sources), _tera, &self.template)?;
+        if !is_outdated(&destination_path, &fingerprint) {
             return Ok(());
         }
 
         // create the destination directory
         create_parent_directory(&destination_path)?;
 
-        // create the destination file
-        let destination_file = File::create(&destination_path).map_err(|e| {
-            anyhow::Error::new(e).context("unable to create the destination file".to_string())
-        })?;
-
-        let mut context = Context::new();
+        let mut context = _context.clone();
         context.insert("data", &self);
-        _tera
-            .render_to(&self.template, &context, destination_file)
-            .map_err(|e| {
-                anyhow::Error::new(e).context(format!("unable to render {}", &self.template))
-            })
+        context.insert("metadata", &self.metadata);
+        if let Some(snippet_sources) = &snippet_sources {
+            context.insert("snippet_sources", snippet_sources);
+        }
+        // the destination file is only created once the rendering is complete
+        write_file_atomically(&destination_path, |destination_file| {
+            _tera
+                .render_to(&self.template, &context, destination_file)
+                .map_err(|e| {
+                    anyhow::Error::new(e).context(format!("unable to render {}", &self.template))
+                })
+        })?;
+        write_fingerprint(&destination_path, &fingerprint)
     }
 }
 
 #[cfg(test)]
 mod test {
-    use std::fs::read_to_string;
+    use std::fs::write;
 
     use crate::cmd::library::generate::templates::TEMPLATES;
     use crate::constants::get_default_template_item_documentation;
     use crate::tera::create_tera;
     use crate::urn::Urn;
+    use crate::utils::delete_file_or_directory;
 
     use super::*;
 
@@ -169,32 +275,44 @@ mod test {
                 Object::Icon {
                     name: "Illustration".to_string(),
                     illustration_path: "./Icon.png".to_string(),
+                    retina_illustration_path: None,
                 },
                 Object::Element {
                     name: "Icon".to_string(),
                     illustration_path: "./Item.png".to_string(),
-                    full_snippet_local_path: "test/full_snippet_local_path.puml".to_string(),
-                    full_snippet_remote_path: "test/full_snippet_remote_path.puml".to_string(),
+                    full_snippet_local_path: Some("test/full_snippet_local_path.puml".to_string()),
+                    full_snippet_remote_path: Some(
+                        "test/full_snippet_remote_path.puml".to_string(),
+                    ),
                 },
                 Object::Element {
                     name: "Card".to_string(),
                     illustration_path: "./ItemCard.png".to_string(),
-                    full_snippet_local_path: "test/full_snippet_local_path.puml".to_string(),
-                    full_snippet_remote_path: "test/full_snippet_remote_path.puml".to_string(),
+                    full_snippet_local_path: Some("test/full_snippet_local_path.puml".to_string()),
+                    full_snippet_remote_path: Some(
+                        "test/full_snippet_remote_path.puml".to_string(),
+                    ),
                 },
                 Object::Element {
                     name: "Group".to_string(),
                     illustration_path: "./ItemGroup.png".to_string(),
-                    full_snippet_local_path: "test/full_snippet_local_path.puml".to_string(),
-                    full_snippet_remote_path: "test/full_snippet_remote_path.puml".to_string(),
+                    full_snippet_local_path: Some("test/full_snippet_local_path.puml".to_string()),
+                    full_snippet_remote_path: Some(
+                        "test/full_snippet_remote_path.puml".to_string(),
+                    ),
                 },
             ],
             path_to_base: urn.get_parent().path_to_base,
             output_directory: "target/tests/item_documentation".to_string(),
             template: get_default_template_item_documentation(),
+            extension: "md".to_string(),
+            inline_snippets: false,
+            metadata: BTreeMap::new(),
         };
         generator.cleanup(&[CleanupScope::All]).unwrap();
-        generator.render_atomic_templates(&tera).unwrap();
+        generator
+            .render_atomic_templates(&tera, &Context::new())
+            .unwrap();
         let content = read_to_string(format!(
             "{}/{}.md",
             generator.output_directory, generator.item_urn,
@@ -207,4 +325,103 @@ mod test {
         assert!(content.contains(r"## Card"));
         assert!(content.contains(r"## Group"));
     }
+
+    #[test]
+    fn test_inline_snippets() {
+        let tera = create_tera(TEMPLATES.to_vec(), None).unwrap();
+        let output_directory = "target/tests/item_documentation/inline_snippets";
+        delete_file_or_directory(Path::new(output_directory)).unwrap();
+        let snippet_path = format!("{}/Package/Module/Item.Local.puml", output_directory);
+        create_parent_directory(Path::new(&snippet_path)).unwrap();
+        write(&snippet_path, "@startuml\nItem(item, 'Item')\n@enduml").unwrap();
+        let generator = ItemDocumentationTask {
+            item_urn: "Package/Module/Item".to_string(),
+            item_name: "Item".to_string(),
+            objects: vec![
+                Object::Element {
+                    name: "Icon".to_string(),
+                    illustration_path: "./Item.png".to_string(),
+                    full_snippet_local_path: Some(snippet_path.clone()),
+                    full_snippet_remote_path: None,
+                },
+                Object::Element {
+                    name: "Card".to_string(),
+                    illustration_path: "./ItemCard.png".to_string(),
+                    full_snippet_local_path: Some(format!("{}/missing.puml", output_directory)),
+                    full_snippet_remote_path: None,
+                },
+            ],
+            path_to_base: "../..".to_string(),
+            output_directory: output_directory.to_string(),
+            template: get_default_template_item_documentation(),
+            extension: "md".to_string(),
+            inline_snippets: true,
+            metadata: BTreeMap::new(),
+        };
+        generator
+            .render_atomic_templates(&tera, &Context::new())
+            .unwrap();
+        let content =
+            read_to_string(format!("{}/Package/Module/Item.md", output_directory)).unwrap();
+        assert!(content.contains("```plantuml\n@startuml\nItem(item, 'Item')\n@enduml\n```"));
+        assert!(content.contains("## Card"));
+    }
+
+    #[test]
+    fn test_retina_illustration() {
+        let tera = create_tera(TEMPLATES.to_vec(), None).unwrap();
+        let output_directory = "target/tests/item_documentation/retina_illustration";
+        delete_file_or_directory(Path::new(output_directory)).unwrap();
+        let generator = ItemDocumentationTask {
+            item_urn: "Package/Module/Item".to_string(),
+            item_name: "Item".to_string(),
+            objects: vec![Object::Icon {
+                name: "Illustration".to_string(),
+                illustration_path: "Package/Module/Item.png".to_string(),
+                retina_illustration_path: Some("Package/Module/Item@2x.png".to_string()),
+            }],
+            path_to_base: "../..".to_string(),
+            output_directory: output_directory.to_string(),
+            template: get_default_template_item_documentation(),
+            extension: "md".to_string(),
+            inline_snippets: false,
+            metadata: BTreeMap::new(),
+        };
+        generator
+            .render_atomic_templates(&tera, &Context::new())
+            .unwrap();
+        let content =
+            read_to_string(format!("{}/Package/Module/Item.md", output_directory)).unwrap();
+        assert!(content.contains(
+            r#"srcset="../../Package/Module/Item.png 1x, ../../Package/Module/Item@2x.png 2x""#
+        ));
+    }
+
+    #[test]
+    fn test_metadata() {
+        let tera = create_tera(TEMPLATES.to_vec(), None).unwrap();
+        let output_directory = "target/tests/item_documentation/metadata";
+        delete_file_or_directory(Path::new(output_directory)).unwrap();
+        let generator = ItemDocumentationTask {
+            item_urn: "Package/Module/Item".to_string(),
+            item_name: "Item".to_string(),
+            objects: vec![],
+            path_to_base: "../..".to_string(),
+            output_directory: output_directory.to_string(),
+            template: get_default_template_item_documentation(),
+            extension: "md".to_string(),
+            inline_snippets: false,
+            metadata: BTreeMap::from([
+                ("deprecated".to_string(), Value::Bool(true)),
+                ("owner".to_string(), Value::String("team-a".to_string())),
+            ]),
+        };
+        generator
+            .render_atomic_templates(&tera, &Context::new())
+            .unwrap();
+        let content =
+            read_to_string(format!("{}/Package/Module/Item.md", output_directory)).unwrap();
+        assert!(content.contains("## Metadata"));
+        assert!(content.contains("| deprecated | true |\n| owner | team-a |"));
+    }
 }
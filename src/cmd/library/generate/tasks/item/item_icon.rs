@@ -1,18 +1,23 @@
 use std::io;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use anyhow::Result;
 use image::imageops::FilterType;
-use image::{GenericImageView, ImageReader};
+use image::{DynamicImage, GenericImageView, ImageFormat, ImageReader};
 
 use crate::cmd::library::generate::config::Config;
-use crate::cmd::library::generate::task::{CleanupScope, Task};
+use crate::cmd::library::generate::task::{
+    CleanupScope, OutputKind, Task, PHASE_CLEANUP, PHASE_CREATE_RESOURCES,
+};
 use crate::cmd::library::manifest::icon::Icon;
 use crate::cmd::library::manifest::item::Item;
+use crate::cmd::library::manifest::library::customization::ResizeFilter;
 use crate::cmd::library::manifest::library::Library;
-use crate::utils::{create_parent_directory, delete_file};
+use crate::png::optimize_png_file;
+use crate::svg::optimize_svg_file;
+use crate::utils::{check_writable_path, create_parent_directory, delete_file};
 
 pub struct ItemIconTask {
     /// The URN of the Item.
@@ -23,8 +28,16 @@ pub struct ItemIconTask {
     pub full_destination_image: String,
     /// The height of the destination icon.
     destination_icon_height: u32,
+    /// The scale of the icon, e.g. 2 for the `@2x` variant.
+    scale: u32,
     /// The command/path of the inkscape binary.
     inkscape_binary: String,
+    /// The optimizer of the SVG icons, when set.
+    svg_optimizer: Option<String>,
+    /// The optimizer of the PNG icons, when set.
+    png_optimizer: Option<String>,
+    /// The filter resizing the raster icons.
+    resize_filter: ResizeFilter,
 }
 
 impl ItemIconTask {
@@ -34,9 +47,14 @@ impl ItemIconTask {
         item: &Item,
         icon: &Icon,
         full_source_image: &str,
+        scale: u32,
     ) -> Result<ItemIconTask> {
+        let icon_path = match scale {
+            1 => icon.get_icon_path(&item.urn, &library.customization.icon_format),
+            _ => icon.get_scaled_icon_path(&item.urn, &library.customization.icon_format, scale),
+        };
         let full_destination_image = match Path::new(&config.output_directory)
-            .join(icon.get_icon_path(&item.urn, &library.customization.icon_format))
+            .join(icon_path)
             .as_path()
             .to_str()
         {
@@ -51,8 +69,14 @@ impl ItemIconTask {
             item_urn: item.urn.value.clone(),
             full_source_image: full_source_image.to_string(),
             full_destination_image,
-            destination_icon_height: library.customization.icon_height,
+            destination_icon_height: library.customization.icon_height * scale,
+            scale,
             inkscape_binary: config.inkscape_binary.clone(),
+            svg_optimizer: config.svg_optimizer.clone(),
+            png_optimizer: config.png_optimizer.clone(),
+            resize_filter: config
+                .resize_filter
+                .unwrap_or(library.customization.resize_filter),
         })
     }
     fn generate_icon_with_inkscape(&self) -> Result<()> {
@@ -114,14 +138,26 @@ impl ItemIconTask {
         let (width, height) = image.dimensions();
         let destination_icon_width = self.destination_icon_height * width / height;
 
+        // resolve the format from the extension, e.g. webp
+        let format = ImageFormat::from_path(&self.full_destination_image).map_err(|e| {
+            anyhow::Error::new(e).context(format!(
+                "unable to get the format of {}",
+                &self.full_destination_image
+            ))
+        })?;
+
         // generate the sprite icon
-        image
-            .resize(
-                destination_icon_width,
-                self.destination_icon_height,
-                FilterType::Triangle,
-            )
-            .save(&self.full_destination_image)
+        let icon = image.resize(
+            destination_icon_width,
+            self.destination_icon_height,
+            FilterType::from(self.resize_filter),
+        );
+        // the WebP and AVIF encoders only accept RGB(A) images
+        let icon = match format {
+            ImageFormat::WebP | ImageFormat::Avif => DynamicImage::ImageRgba8(icon.to_rgba8()),
+            _ => icon,
+        };
+        icon.save_with_format(&self.full_destination_image, format)
             .map_err(|e| {
                 anyhow::Error::new(e)
                     .context(format!("unable to save {}", &self.full_destination_image))
@@ -132,6 +168,39 @@ impl ItemIconTask {
 }
 
 impl Task for ItemIconTask {
+    fn describe(&self) -> String {
+        match self.scale {
+            1 => format!("ItemIconTask({})", self.item_urn),
+            _ => format!("ItemIconTask({}@{}x)", self.item_urn, self.scale),
+        }
+    }
+
+    fn phases(&self) -> Vec<&'static str> {
+        vec![PHASE_CLEANUP, PHASE_CREATE_RESOURCES]
+    }
+
+    fn validate(&self, _scopes: &[CleanupScope]) -> Result<()> {
+        let icon_destination_path = Path::new(&self.full_destination_image);
+        // the source is only required when the icon has to be generated, e.g. once cleaned up
+        let is_generated =
+            CleanupScope::ItemIcon.is_included_in(_scopes) || !icon_destination_path.exists();
+        if is_generated && !Path::new(&self.full_source_image).exists() {
+            return Err(anyhow::Error::msg(format!(
+                "{} - the icon source {} doesn't exist",
+                &self.item_urn, &self.full_source_image
+            )));
+        }
+        check_writable_path(icon_destination_path)
+    }
+
+    fn outputs(&self) -> Vec<PathBuf> {
+        vec![PathBuf::from(&self.full_destination_image)]
+    }
+
+    fn output_kind(&self) -> OutputKind {
+        OutputKind::Icon
+    }
+
     fn cleanup(&self, _scopes: &[CleanupScope]) -> Result<()> {
         log::debug!("{} - ItemIconTask - cleanup", &self.item_urn);
         if CleanupScope::ItemIcon.is_included_in(_scopes) {
@@ -170,12 +239,32 @@ impl Task for ItemIconTask {
             self.generate_icon_with_builtin_library()?;
         }
 
+        // optimize the icon when expected
+        if let Some(svg_optimizer) = &self.svg_optimizer {
+            if icon_destination_path
+                .extension()
+                .is_some_and(|e| e == "svg")
+            {
+                optimize_svg_file(icon_destination_path, svg_optimizer)?;
+            }
+        }
+        if let Some(png_optimizer) = &self.png_optimizer {
+            if icon_destination_path
+                .extension()
+                .is_some_and(|e| e == "png")
+            {
+                optimize_png_file(icon_destination_path, png_optimizer)?;
+            }
+        }
+
         Ok(())
     }
 }
 
 #[cfg(test)]
 mod test {
+    use std::fs::write;
+
     use crate::cmd::library::generate::config::Config;
 
     use super::*;
@@ -189,7 +278,11 @@ mod test {
                 .to_string(),
             full_destination_image: "target/tests/item_icon/output.png".to_string(),
             destination_icon_height: 50,
+            scale: 1,
             inkscape_binary: config.inkscape_binary,
+            svg_optimizer: None,
+            png_optimizer: None,
+            resize_filter: ResizeFilter::default(),
         };
         generator.cleanup(&[CleanupScope::All]).unwrap();
         generator.create_resources().unwrap();
@@ -208,7 +301,11 @@ mod test {
             full_source_image: "test/original_icon.png".to_string(),
             full_destination_image: "target/tests/item_icon/output_with_builtin.png".to_string(),
             destination_icon_height: 50,
+            scale: 1,
             inkscape_binary: config.inkscape_binary,
+            svg_optimizer: None,
+            png_optimizer: None,
+            resize_filter: ResizeFilter::default(),
         };
         generator.cleanup(&[CleanupScope::All]).unwrap();
         generator.create_resources().unwrap();
@@ -218,4 +315,122 @@ mod test {
         generator.create_resources().unwrap();
         assert!(Path::new("target/tests/item_icon/output_with_builtin.png").exists());
     }
+
+    #[test]
+    fn test_create_resources_to_webp() {
+        let config = Config::default();
+        let generator = ItemIconTask {
+            item_urn: "PackageA/ModuleB/FamilyC/ItemD".to_string(),
+            full_source_image: "test/original_icon.png".to_string(),
+            full_destination_image: "target/tests/item_icon/output_with_builtin.webp".to_string(),
+            destination_icon_height: 50,
+            scale: 1,
+            inkscape_binary: config.inkscape_binary,
+            svg_optimizer: None,
+            png_optimizer: None,
+            resize_filter: ResizeFilter::default(),
+        };
+        generator.cleanup(&[CleanupScope::All]).unwrap();
+        generator.create_resources().unwrap();
+        let reader = ImageReader::open("target/tests/item_icon/output_with_builtin.webp")
+            .unwrap()
+            .with_guessed_format()
+            .unwrap();
+        assert_eq!(reader.format(), Some(ImageFormat::WebP));
+        assert_eq!(reader.decode().unwrap().height(), 50);
+    }
+
+    #[test]
+    fn test_validate_missing_source() {
+        let config = Config::default();
+        let generator = ItemIconTask {
+            item_urn: "PackageA/ModuleB/FamilyC/ItemD".to_string(),
+            full_source_image: "test/raw/missing_icon.svg".to_string(),
+            full_destination_image: "target/tests/item_icon/output_missing.png".to_string(),
+            destination_icon_height: 50,
+            scale: 1,
+            inkscape_binary: config.inkscape_binary,
+            svg_optimizer: None,
+            png_optimizer: None,
+            resize_filter: ResizeFilter::default(),
+        };
+        generator.cleanup(&[CleanupScope::All]).unwrap();
+        let error = generator.validate(&[]).unwrap_err();
+        assert!(error.to_string().contains("test/raw/missing_icon.svg"));
+        // an existing icon is only kept when it is not cleaned up
+        create_parent_directory(Path::new(&generator.full_destination_image)).unwrap();
+        write(&generator.full_destination_image, "an icon").unwrap();
+        assert!(generator.validate(&[]).is_ok());
+        assert!(generator.validate(&[CleanupScope::Item]).is_err());
+    }
+
+    #[test]
+    fn test_create_retina_resources() {
+        let config = Config {
+            output_directory: "target/tests/item_icon/retina".to_string(),
+            ..Config::default()
+        };
+        let library: Library = serde_yaml_ok::from_str(
+            "{name: library, remote_url: distribution, customization: {icon_height: 50}}",
+        )
+        .unwrap();
+        let item: Item = serde_yaml_ok::from_str("urn: PackageA/ModuleB/ItemD").unwrap();
+        let icon = Icon::Source {
+            source: "test/original_icon.png".to_string(),
+        };
+        for (scale, file_name, height) in [(1, "ItemD.png", 50), (2, "ItemD@2x.png", 100)] {
+            let generator = ItemIconTask::create(
+                &config,
+                &library,
+                &item,
+                &icon,
+                "test/original_icon.png",
+                scale,
+            )
+            .unwrap();
+            let path = format!(
+                "target/tests/item_icon/retina/PackageA/ModuleB/{}",
+                file_name
+            );
+            assert_eq!(generator.full_destination_image, path);
+            generator.cleanup(&[CleanupScope::All]).unwrap();
+            generator.create_resources().unwrap();
+            let image = ImageReader::open(&path).unwrap().decode().unwrap();
+            assert_eq!(image.height(), height);
+        }
+    }
+
+    #[test]
+    fn test_create_resources_with_resize_filter() {
+        let config = Config {
+            output_directory: "target/tests/item_icon/resize_filter".to_string(),
+            resize_filter: Some(ResizeFilter::Lanczos3),
+            ..Config::default()
+        };
+        let library: Library = serde_yaml_ok::from_str(
+            "{name: library, remote_url: distribution, customization: {icon_height: 32, resize_filter: catmullrom}}",
+        )
+        .unwrap();
+        assert_eq!(
+            library.customization.resize_filter,
+            ResizeFilter::CatmullRom
+        );
+        let item: Item = serde_yaml_ok::from_str("urn: PackageA/ModuleB/ItemD").unwrap();
+        let icon = Icon::Source {
+            source: "test/original_icon.png".to_string(),
+        };
+        let generator =
+            ItemIconTask::create(&config, &library, &item, &icon, "test/original_icon.png", 1)
+                .unwrap();
+        // the filter of the config takes precedence over the one of the manifest
+        assert_eq!(generator.resize_filter, ResizeFilter::Lanczos3);
+        generator.cleanup(&[CleanupScope::All]).unwrap();
+        generator.create_resources().unwrap();
+        let image = ImageReader::open(&generator.full_destination_image)
+            .unwrap()
+            .decode()
+            .unwrap();
+        // the source icon is 79x50
+        assert_eq!(image.dimensions(), (50, 32));
+    }
 }
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
-use std::fs::{read_to_string, File};
-use std::path::Path;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -8,11 +9,18 @@ use serde_json::Value;
 use tera::{Context, Tera};
 
 use crate::cmd::library::generate::config::Config;
-use crate::cmd::library::generate::task::{CleanupScope, Task};
+use crate::cmd::library::generate::fingerprint::{
+    compute_fingerprint, is_outdated_in, write_fingerprint_in,
+};
+use crate::cmd::library::generate::sink::{resolve_sink, OutputSink};
+use crate::cmd::library::generate::task::{
+    CleanupScope, OutputKind, Task, PHASE_CLEANUP, PHASE_RENDER_ATOMIC_TEMPLATES,
+};
 use crate::cmd::library::manifest::element::Shape;
 use crate::cmd::library::manifest::item::Item;
+use crate::cmd::library::manifest::library::Library;
 use crate::constants::{SPRITES, SPRITE_LG};
-use crate::utils::{create_parent_directory, delete_file};
+use crate::utils::delete_file;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(tag = "type")]
@@ -77,17 +85,27 @@ pub struct ItemSourceTask {
     cached_sprite_paths: Vec<String>,
     /// The elements of the Item.
     elements: Vec<Element>,
+    /// The extension of the generated sources.
+    source_extension: String,
     /// The path to the output directory.
     output_directory: String,
     /// The name of the Tera template
     template: String,
+    /// The destination of the source, by default the output directory.
+    #[serde(skip)]
+    sink: Option<Arc<dyn OutputSink>>,
 }
 
 impl ItemSourceTask {
-    pub fn create(config: &Config, item: &Item) -> Result<ItemSourceTask> {
+    pub fn create(config: &Config, library: &Library, item: &Item) -> Result<ItemSourceTask> {
         let mut cached_sprite_paths: Vec<String> = vec![];
 
-        if let Some(icon) = &item.icon {
+        // the sprites are not generated with the remote profile
+        let icon = item
+            .icon
+            .as_ref()
+            .filter(|_| config.profile.has_local_assets());
+        if let Some(icon) = icon {
             // if the item defines an icon, then sprites will be generated
             for size in &SPRITES {
                 cached_sprite_paths.push(
@@ -166,12 +184,16 @@ impl ItemSourceTask {
                     }
                 })
                 .collect(),
+            source_extension: library.rendering.source_extension.clone(),
             output_directory: config.output_directory.clone(),
             template: item.templates.source.clone(),
+            sink: None,
         })
     }
     fn get_relative_source_path(&self) -> Box<Path> {
-        Box::from(Path::new(format!("{}.puml", self.item_urn,).as_str()))
+        Box::from(Path::new(
+            format!("{}.{}", self.item_urn, self.source_extension).as_str(),
+        ))
     }
     fn get_full_source_path(&self) -> Box<Path> {
         Path::new(&self.output_directory)
@@ -181,6 +203,22 @@ impl ItemSourceTask {
 }
 
 impl Task for ItemSourceTask {
+    fn describe(&self) -> String {
+        format!("ItemSourceTask({})", self.item_urn)
+    }
+
+    fn phases(&self) -> Vec<&'static str> {
+        vec![PHASE_CLEANUP, PHASE_RENDER_ATOMIC_TEMPLATES]
+    }
+
+    fn outputs(&self) -> Vec<PathBuf> {
+        vec![self.get_full_source_path().to_path_buf()]
+    }
+
+    fn output_kind(&self) -> OutputKind {
+        OutputKind::Source
+    }
+
     fn cleanup(&self, _scopes: &[CleanupScope]) -> Result<()> {
         log::debug!("{} - ItemIconTask - cleanup", &self.item_urn);
         if CleanupScope::ItemSource.is_included_in(_scopes) {
@@ -189,23 +227,32 @@ impl Task for ItemSourceTask {
         Ok(())
     }
 
-    fn render_atomic_templates(&self, _tera: &Tera) -> Result<()> {
+    fn render_atomic_templates(&self, _tera: &Tera, _context: &Context) -> Result<()> {
         log::debug!("{} - ItemIconTask - render templates", &self.item_urn);
 
-        let destination_path = self.get_full_source_path();
+        let sink = resolve_sink(&self.sink, &self.output_directory);
+        let relative_path = self.get_relative_source_path();
 
         // skip early when generation not required
-        if destination_path.exists() {
+        let fingerprint = compute_fingerprint(self, _tera, &self.template)?;
+        if !is_outdated_in(sink.as_ref(), &relative_path, &fingerprint) {
             return Ok(());
         }
 
-        // create the destination directory
-        create_parent_directory(&destination_path)?;
-
-        // create the destination file
-        let destination_file = File::create(&destination_path).map_err(|e| {
-            anyhow::Error::new(e).context("unable to create the destination file".to_string())
-        })?;
+        // the sprite values are produced in the cache directory by the previous phase
+        let missing_sprite_paths = self
+            .cached_sprite_paths
+            .iter()
+            .filter(|path| !Path::new(path).exists())
+            .map(|path| path.as_str())
+            .collect::<Vec<&str>>();
+        if !missing_sprite_paths.is_empty() {
+            return Err(anyhow::Error::msg(format!(
+                "{} - the cached sprite files are missing: {}, the cache directory may have been cleaned during the generation, please generate the library again",
+                &self.item_urn,
+                missing_sprite_paths.join(", ")
+            )));
+        }
 
         // get the sprite value from the cached files
         let mut sprites: Vec<String> = vec![];
@@ -221,14 +268,15 @@ impl Task for ItemSourceTask {
             sprites.push(cached_sprite_value);
         }
 
-        let mut context = Context::new();
+        let mut context = _context.clone();
         context.insert("sprites", &sprites);
         context.insert("data", &self);
-        _tera
-            .render_to(&self.template, &context, destination_file)
-            .map_err(|e| {
-                anyhow::Error::new(e).context(format!("unable to render {}", &self.template))
-            })
+        // the artifact is only written once the rendering is complete
+        let content = _tera.render(&self.template, &context).map_err(|e| {
+            anyhow::Error::new(e).context(format!("unable to render {}", &self.template))
+        })?;
+        sink.write(&relative_path, content.as_bytes())?;
+        write_fingerprint_in(sink.as_ref(), &relative_path, &fingerprint)
     }
 }
 
@@ -236,12 +284,18 @@ impl Task for ItemSourceTask {
 mod test {
     use std::fs::read_to_string;
 
+    use clap::crate_version;
+
+    use crate::cmd::library::generate::meta::Meta;
+    use crate::cmd::library::generate::sink::MemorySink;
     use crate::cmd::library::generate::templates::TEMPLATES;
+    use crate::cmd::library::manifest::library::Library;
     use crate::constants::{
         get_default_icon_card_element_stereotype, get_default_icon_element_stereotype,
         get_default_icon_group_element_stereotype, get_default_template_item_source,
     };
     use crate::tera::create_tera;
+    use crate::utils::create_parent_directory;
 
     use super::*;
 
@@ -288,12 +342,16 @@ mod test {
                     properties: HashMap::default(),
                 },
             ],
+            source_extension: "puml".to_string(),
             output_directory: "target/tests/item_source".to_string(),
             template: get_default_template_item_source(),
+            sink: None,
         };
         let tera = &create_tera(TEMPLATES.to_vec(), None).unwrap();
         generator.cleanup(&[CleanupScope::All]).unwrap();
-        generator.render_atomic_templates(tera).unwrap();
+        generator
+            .render_atomic_templates(tera, &Context::new())
+            .unwrap();
         let content = read_to_string(format!(
             "{}/{}.puml",
             generator.output_directory, generator.item_urn,
@@ -312,6 +370,36 @@ mod test {
         assert!(content.contains(r"GroupElement($id, 'SimpleGroup', $name, $tech)"));
     }
 
+    #[test]
+    fn test_missing_cached_sprite() {
+        let cached_sprite_path = "target/tests/item_source/missing_sprite/ItemLg.txt";
+        create_parent_directory(Path::new(cached_sprite_path)).unwrap();
+        std::fs::copy("test/sprite_value_B.puml", cached_sprite_path).unwrap();
+        // e.g. the cache directory is cleaned between the phases
+        delete_file(Path::new(cached_sprite_path)).unwrap();
+        let generator = ItemSourceTask {
+            item_urn: "Package/Module/MissingSpriteItem".to_string(),
+            cached_sprite_paths: vec![
+                "test/sprite_value_A.puml".to_string(),
+                cached_sprite_path.to_string(),
+            ],
+            elements: vec![],
+            source_extension: "puml".to_string(),
+            output_directory: "target/tests/item_source".to_string(),
+            template: get_default_template_item_source(),
+            sink: Some(Arc::new(MemorySink::default())),
+        };
+        let tera = &create_tera(TEMPLATES.to_vec(), None).unwrap();
+        let error = generator
+            .render_atomic_templates(tera, &Context::new())
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Package/Module/MissingSpriteItem"));
+        assert!(error.contains(&format!("missing: {},", cached_sprite_path)));
+        assert!(!error.contains("sprite_value_A"));
+        assert!(error.contains("generate the library again"));
+    }
+
     #[test]
     fn test_custom_elements() {
         let properties: HashMap<String, Value> = serde_yaml_ok::from_str(
@@ -328,12 +416,16 @@ mod test {
                 procedure_name: "CustomItem".to_string(),
                 properties,
             }],
+            source_extension: "puml".to_string(),
             output_directory: "target/tests/item_source".to_string(),
             template: "custom_item_source.tera".to_string(),
+            sink: None,
         };
         let tera = &create_tera(TEMPLATES.to_vec(), Some("test/tera/**".to_string())).unwrap();
         generator.cleanup(&[CleanupScope::All]).unwrap();
-        generator.render_atomic_templates(tera).unwrap();
+        generator
+            .render_atomic_templates(tera, &Context::new())
+            .unwrap();
         let content = read_to_string(format!(
             "{}/{}.puml",
             generator.output_directory, generator.item_urn,
@@ -343,4 +435,72 @@ mod test {
         assert!(content.contains("' itemA,itemB"));
         assert!(content.contains("!procedure CustomItem($id)"));
     }
+
+    #[test]
+    fn test_source_to_sink() {
+        let sink = Arc::new(MemorySink::default());
+        let generator = ItemSourceTask {
+            item_urn: "Package/Module/Family/SinkItem".to_string(),
+            cached_sprite_paths: vec![],
+            elements: vec![Element::Group {
+                procedure_name: "SinkItem".to_string(),
+                stereotype_name: "SinkItem".to_string(),
+                default_label: "Sink Item".to_string(),
+                properties: HashMap::default(),
+            }],
+            source_extension: "puml".to_string(),
+            output_directory: "target/tests/item_source_sink".to_string(),
+            template: get_default_template_item_source(),
+            sink: Some(sink.clone()),
+        };
+        let tera = &create_tera(TEMPLATES.to_vec(), None).unwrap();
+        generator
+            .render_atomic_templates(tera, &Context::new())
+            .unwrap();
+        let content = sink
+            .get_content("Package/Module/Family/SinkItem.puml")
+            .unwrap();
+        assert!(content.contains(r"GroupElement($id, 'SinkItem', $name, $tech)"));
+        assert!(!Path::new("target/tests/item_source_sink").exists());
+    }
+
+    #[test]
+    fn test_banner() {
+        let sink = Arc::new(MemorySink::default());
+        let generator = ItemSourceTask {
+            item_urn: "Package/Module/Family/BannerItem".to_string(),
+            cached_sprite_paths: vec![],
+            elements: vec![],
+            source_extension: "puml".to_string(),
+            output_directory: "target/tests/item_source_banner".to_string(),
+            template: get_default_template_item_source(),
+            sink: Some(sink.clone()),
+        };
+        let tera = &create_tera(TEMPLATES.to_vec(), None).unwrap();
+        let library: Library =
+            serde_yaml_ok::from_str("{name: library, remote_url: distribution}").unwrap();
+        let context = Meta::create(&library)
+            .with_banner(Config::default().get_banner())
+            .to_context();
+        generator.render_atomic_templates(tera, &context).unwrap();
+        let content = sink
+            .get_content("Package/Module/Family/BannerItem.puml")
+            .unwrap();
+        assert!(content.starts_with(&format!(
+            "' DO NOT EDIT - generated by plantuml-generator v{}\n",
+            crate_version!()
+        )));
+        // the banner is disabled
+        let sink = Arc::new(MemorySink::default());
+        let generator = ItemSourceTask {
+            sink: Some(sink.clone()),
+            ..generator
+        };
+        let context = Meta::create(&library).with_banner(None).to_context();
+        generator.render_atomic_templates(tera, &context).unwrap();
+        let content = sink
+            .get_content("Package/Module/Family/BannerItem.puml")
+            .unwrap();
+        assert!(content.starts_with("' definition of the Item Package/Module/Family/BannerItem"));
+    }
 }
@@ -6,6 +6,7 @@ use crate::cmd::library::generate::tasks::item::element_snippet::{
 use crate::cmd::library::generate::tasks::item::item_documentation::ItemDocumentationTask;
 use crate::cmd::library::generate::tasks::item::item_icon::ItemIconTask;
 use crate::cmd::library::generate::tasks::item::item_source::ItemSourceTask;
+use crate::cmd::library::generate::tasks::item::sprite::SpriteTask;
 use crate::cmd::library::generate::tasks::item::sprite_icon::SpriteIconTask;
 use crate::cmd::library::generate::tasks::item::sprite_value::SpriteValueTask;
 use crate::cmd::library::manifest::icon::Icon;
@@ -18,6 +19,7 @@ mod element_snippet;
 mod item_documentation;
 mod item_icon;
 mod item_source;
+mod sprite;
 mod sprite_icon;
 mod sprite_value;
 
@@ -32,20 +34,41 @@ pub fn parse_item(
 
     let mut tasks: Vec<Box<dyn Task>> = vec![];
 
-    if let Some(ref icon) = &_item.icon {
+    // the documentation is rendered without the snippets
+    if _config.only_docs {
+        tasks.push(Box::from(ItemDocumentationTask::create(
+            _config, _library, _item,
+        )?));
+        return Ok(tasks);
+    }
+
+    // the icons and the sprites are local assets
+    let icon = _item
+        .icon
+        .as_ref()
+        .filter(|_| _config.profile.has_local_assets());
+    if let Some(icon) = icon {
         match icon {
             Icon::Source { source } => {
                 // create the task to generate the icon
-                let item_icon_task = ItemIconTask::create(_config, _library, _item, icon, source)?;
+                let item_icon_task =
+                    ItemIconTask::create(_config, _library, _item, icon, source, 1)?;
                 let sprite_icon_source = item_icon_task.full_destination_image.clone();
                 tasks.push(Box::from(item_icon_task));
+                // create the task to generate the icon for the high-density displays
+                if _library.customization.retina_icons {
+                    tasks.push(Box::from(ItemIconTask::create(
+                        _config, _library, _item, icon, source, 2,
+                    )?));
+                }
                 // create the tasks to generate the sprite values
                 for (sprite_size_name, sprite_size_value) in
-                _library.customization.list_sprite_sizes()
+                    _library.customization.list_sprite_sizes()
                 {
                     // create the task to generate the icon used as input of the sprite value
                     let sprite_icon_task = SpriteIconTask::create(
                         _config,
+                        _library,
                         _item,
                         icon,
                         &sprite_icon_source,
@@ -59,8 +82,11 @@ pub fn parse_item(
                         &sprite_icon_task.full_destination_icon.clone(),
                         sprite_size_name,
                     )?;
-                    tasks.push(Box::from(sprite_icon_task));
-                    tasks.push(Box::from(sprite_value_task));
+                    // the sprite value depends on its icon, both are grouped in a single task
+                    tasks.push(Box::from(SpriteTask::create(
+                        sprite_icon_task,
+                        sprite_value_task,
+                    )));
                 }
             }
             Icon::Reference { .. } => {}
@@ -70,23 +96,27 @@ pub fn parse_item(
     // create the snippet for each element
     for element in _item.elements.iter() {
         // create the local snippet
-        tasks.push(Box::from(ElementSnippetTask::create(
-            _config,
-            _library,
-            _package,
-            _item,
-            element,
-            SnippetMode::Local,
-        )?));
+        if _config.snippet_mode.has_local() && _config.profile.has_local_assets() {
+            tasks.push(Box::from(ElementSnippetTask::create(
+                _config,
+                _library,
+                _package,
+                _item,
+                element,
+                SnippetMode::Local,
+            )?));
+        }
         // create the remote snippet
-        tasks.push(Box::from(ElementSnippetTask::create(
-            _config,
-            _library,
-            _package,
-            _item,
-            element,
-            SnippetMode::Remote,
-        )?));
+        if _config.snippet_mode.has_remote() {
+            tasks.push(Box::from(ElementSnippetTask::create(
+                _config,
+                _library,
+                _package,
+                _item,
+                element,
+                SnippetMode::Remote,
+            )?));
+        }
     }
 
     // create the task to generate the documentation
@@ -95,7 +125,7 @@ pub fn parse_item(
     )?));
 
     // create the task to generate the puml file of the item
-    tasks.push(Box::from(ItemSourceTask::create(_config, _item)?));
+    tasks.push(Box::from(ItemSourceTask::create(_config, _library, _item)?));
 
     Ok(tasks)
 }
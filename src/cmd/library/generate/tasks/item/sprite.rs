@@ -0,0 +1,114 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::cmd::library::generate::task::{
+    CleanupScope, Task, PHASE_CLEANUP, PHASE_CREATE_RESOURCES,
+};
+use crate::cmd::library::generate::tasks::item::sprite_icon::SpriteIconTask;
+use crate::cmd::library::generate::tasks::item::sprite_value::SpriteValueTask;
+
+/// The sprite of a given size.
+///
+/// The value is encoded from the resized icon, so the icon is always produced before its value.
+/// The sprites of the other sizes use distinct cache paths and are independent work units.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SpriteTask {
+    /// The task resizing the icon used as input of the sprite value.
+    icon: SpriteIconTask,
+    /// The task encoding and caching the sprite value.
+    value: SpriteValueTask,
+}
+
+impl SpriteTask {
+    pub fn create(icon: SpriteIconTask, value: SpriteValueTask) -> SpriteTask {
+        SpriteTask { icon, value }
+    }
+}
+
+impl Task for SpriteTask {
+    fn describe(&self) -> String {
+        format!(
+            "SpriteTask({}/{})",
+            self.icon.item_urn, self.icon.destination_icon_height
+        )
+    }
+
+    fn phases(&self) -> Vec<&'static str> {
+        vec![PHASE_CLEANUP, PHASE_CREATE_RESOURCES]
+    }
+
+    fn cleanup(&self, _scopes: &[CleanupScope]) -> Result<()> {
+        self.icon.cleanup(_scopes)?;
+        self.value.cleanup(_scopes)
+    }
+
+    fn create_resources(&self) -> Result<()> {
+        self.icon.create_resources()?;
+        self.value.create_resources()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+    use std::thread;
+
+    use crate::cmd::library::generate::config::Config;
+    use crate::cmd::library::manifest::icon::Icon;
+    use crate::cmd::library::manifest::item::Item;
+    use crate::cmd::library::manifest::library::customization::Customization;
+    use crate::cmd::library::manifest::library::Library;
+    use crate::utils::delete_file_or_directory;
+
+    use super::*;
+
+    #[test]
+    fn test_parallel_create_resources() {
+        let config = Config {
+            cache_directory: "target/tests/sprite/parallel".to_string(),
+            plantuml_jar: "test/plantuml-1.2022.4.jar".to_string(),
+            ..Default::default()
+        };
+        delete_file_or_directory(Path::new(&config.cache_directory)).unwrap();
+        let library: Library =
+            serde_yaml_ok::from_str("{name: library, remote_url: distribution}").unwrap();
+        let item: Item = serde_yaml_ok::from_str("urn: Package/Module/Family/Item").unwrap();
+        let icon = Icon::Source {
+            source: "test/original_icon.png".to_string(),
+        };
+        let tasks = Customization::default()
+            .list_sprite_sizes()
+            .into_iter()
+            .map(|size| {
+                let sprite_icon_task = SpriteIconTask::create(
+                    &config,
+                    &library,
+                    &item,
+                    &icon,
+                    "test/original_icon.png",
+                    size,
+                )
+                .unwrap();
+                let sprite_value_task = SpriteValueTask::create(
+                    &config,
+                    &item,
+                    &icon,
+                    &sprite_icon_task.full_destination_icon.clone(),
+                    size.0,
+                )
+                .unwrap();
+                SpriteTask::create(sprite_icon_task, sprite_value_task)
+            })
+            .collect::<Vec<_>>();
+        thread::scope(|s| {
+            for task in &tasks {
+                s.spawn(|| task.create_resources().unwrap());
+            }
+        });
+        for (size, _) in Customization::default().list_sprite_sizes() {
+            let sprite_value_path = Path::new(&config.cache_directory)
+                .join(icon.get_sprite_value_path(&item.urn, size));
+            assert!(sprite_value_path.exists());
+        }
+    }
+}
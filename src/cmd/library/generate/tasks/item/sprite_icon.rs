@@ -1,30 +1,44 @@
-use std::path::Path;
+use std::fs::copy;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
+use heck::ToUpperCamelCase;
 use raster::{BlendMode, Color, Image, PositionMode, ResizeMode};
 use serde::{Deserialize, Serialize};
 
 use crate::cmd::library::generate::config::Config;
-use crate::cmd::library::generate::task::{CleanupScope, Task};
+use crate::cmd::library::generate::task::{
+    CleanupScope, OutputKind, Task, PHASE_CLEANUP, PHASE_CREATE_RESOURCES,
+};
 use crate::cmd::library::manifest::icon::Icon;
 use crate::cmd::library::manifest::item::Item;
+use crate::cmd::library::manifest::library::Library;
+use crate::constants::{DEBUG_DIRECTORY, SPRITE_BACKGROUND_TRANSPARENT};
+use crate::png::optimize_png_file;
 use crate::utils::{create_parent_directory, delete_file};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct SpriteIconTask {
     /// The URN of the Item.
-    item_urn: String,
+    pub item_urn: String,
     /// The path of the source icon file.
     full_source_icon: String,
     /// The path of the destination icon file.
     pub full_destination_icon: String,
     /// The height of the destination icon.
-    destination_icon_height: u32,
+    pub destination_icon_height: u32,
+    /// The background of the destination icon, a hex color or `transparent`.
+    sprite_background: String,
+    /// The copy of the destination icon in the debug directory, when the intermediates are kept.
+    debug_icon: Option<String>,
+    /// The optimizer of the destination icon, when the PNG icons are optimized.
+    png_optimizer: Option<String>,
 }
 
 impl SpriteIconTask {
     pub fn create(
         config: &Config,
+        library: &Library,
         item: &Item,
         icon: &Icon,
         full_source_icon: &str,
@@ -45,16 +59,51 @@ impl SpriteIconTask {
             Some(v) => v.to_string(),
         };
 
+        // the copy is named after the item, even when its icon references another item
+        let debug_icon = config.keep_intermediates.then(|| {
+            Path::new(&config.output_directory)
+                .join(DEBUG_DIRECTORY)
+                .join(format!(
+                    "{}{}.png",
+                    item.urn.value,
+                    sprite_size_name.to_upper_camel_case()
+                ))
+                .to_string_lossy()
+                .to_string()
+        });
+
         Ok(SpriteIconTask {
             item_urn: item.urn.value.clone(),
             full_source_icon: full_source_icon.to_string(),
             full_destination_icon,
             destination_icon_height: sprite_size_value,
+            sprite_background: library.customization.sprite_background.clone(),
+            png_optimizer: config.png_optimizer.clone(),
+            debug_icon,
         })
     }
 }
 
 impl Task for SpriteIconTask {
+    fn describe(&self) -> String {
+        format!(
+            "SpriteIconTask({}/{})",
+            self.item_urn, self.destination_icon_height
+        )
+    }
+
+    fn phases(&self) -> Vec<&'static str> {
+        vec![PHASE_CLEANUP, PHASE_CREATE_RESOURCES]
+    }
+
+    fn outputs(&self) -> Vec<PathBuf> {
+        self.debug_icon.iter().map(PathBuf::from).collect()
+    }
+
+    fn output_kind(&self) -> OutputKind {
+        OutputKind::Sprite
+    }
+
     fn cleanup(&self, _scopes: &[CleanupScope]) -> Result<()> {
         log::debug!(
             "{} - SpriteIconTask - cleanup {}",
@@ -63,6 +112,9 @@ impl Task for SpriteIconTask {
         );
         if CleanupScope::SpriteIcon.is_included_in(_scopes) {
             delete_file(Path::new(&self.full_destination_icon))?;
+            if let Some(debug_icon) = &self.debug_icon {
+                delete_file(Path::new(debug_icon))?;
+            }
         }
         Ok(())
     }
@@ -78,7 +130,7 @@ impl Task for SpriteIconTask {
 
         // skip early when generation not required
         if destination_icon_path.exists() {
-            return Ok(());
+            return self.copy_debug_icon();
         }
 
         // create the destination directory
@@ -110,34 +162,46 @@ impl Task for SpriteIconTask {
                 ))
             })?;
 
-        // create the destination image
-        let mut background_image =
-            Image::blank(destination_icon_width, self.destination_icon_height as i32);
+        // the resized source keeps its transparency
+        let destination_image = match self.sprite_background.as_str() {
+            SPRITE_BACKGROUND_TRANSPARENT => source_image,
+            sprite_background => {
+                // create the destination image
+                let mut background_image =
+                    Image::blank(destination_icon_width, self.destination_icon_height as i32);
 
-        // fill destination image with white
-        raster::editor::fill(&mut background_image, Color::white()).map_err(|e| {
-            anyhow::Error::msg(format!(
-                "unable to fill {}: {:?}",
-                &self.full_destination_icon, e
-            ))
-        })?;
+                // fill destination image with the background color
+                let background_color = Color::hex(sprite_background).map_err(|e| {
+                    anyhow::Error::msg(format!(
+                        "unable to parse the sprite background {}: {:?}",
+                        sprite_background, e
+                    ))
+                })?;
+                raster::editor::fill(&mut background_image, background_color).map_err(|e| {
+                    anyhow::Error::msg(format!(
+                        "unable to fill {}: {:?}",
+                        &self.full_destination_icon, e
+                    ))
+                })?;
 
-        // blend resized source and destination
-        let destination_image = raster::editor::blend(
-            &background_image,
-            &source_image,
-            BlendMode::Normal,
-            1.0,
-            PositionMode::Center,
-            0,
-            0,
-        )
-            .map_err(|e| {
-                anyhow::Error::msg(format!(
-                    "unable to blend {} in {}: {:?}",
-                    &self.full_source_icon, &self.full_destination_icon, e
-                ))
-            })?;
+                // blend resized source and destination
+                raster::editor::blend(
+                    &background_image,
+                    &source_image,
+                    BlendMode::Normal,
+                    1.0,
+                    PositionMode::Center,
+                    0,
+                    0,
+                )
+                .map_err(|e| {
+                    anyhow::Error::msg(format!(
+                        "unable to blend {} in {}: {:?}",
+                        &self.full_source_icon, &self.full_destination_icon, e
+                    ))
+                })?
+            }
+        };
 
         // generate the sprite icon
         raster::save(&destination_image, &self.full_destination_icon).map_err(|e| {
@@ -146,12 +210,37 @@ impl Task for SpriteIconTask {
                 &self.full_destination_icon, e
             ))
         })?;
+
+        // optimize the sprite icon when expected
+        if let Some(png_optimizer) = &self.png_optimizer {
+            optimize_png_file(Path::new(&self.full_destination_icon), png_optimizer)?;
+        }
+
+        self.copy_debug_icon()
+    }
+}
+
+impl SpriteIconTask {
+    /// Copy the destination icon in the debug directory, when the intermediates are kept.
+    fn copy_debug_icon(&self) -> Result<()> {
+        if let Some(debug_icon) = &self.debug_icon {
+            let debug_icon_path = Path::new(debug_icon);
+            create_parent_directory(debug_icon_path)?;
+            copy(&self.full_destination_icon, debug_icon_path).map_err(|e| {
+                anyhow::Error::new(e).context(format!(
+                    "unable to copy {} to {}",
+                    &self.full_destination_icon, debug_icon
+                ))
+            })?;
+        }
         Ok(())
     }
 }
 
 #[cfg(test)]
 mod test {
+    use crate::utils::delete_file_or_directory;
+
     use super::*;
 
     #[test]
@@ -161,6 +250,9 @@ mod test {
             full_source_icon: "test/original_icon.png".to_string(),
             full_destination_icon: "target/tests/sprite_icon/test_generate.png".to_string(),
             destination_icon_height: 16,
+            sprite_background: "#FFFFFF".to_string(),
+            debug_icon: None,
+            png_optimizer: None,
         };
         generator.cleanup(&[CleanupScope::All]).unwrap();
         generator.create_resources().unwrap();
@@ -170,4 +262,69 @@ mod test {
         generator.create_resources().unwrap();
         assert!(Path::new(&generator.full_destination_icon).exists());
     }
+
+    #[test]
+    fn test_transparent_background() {
+        let generator = SpriteIconTask {
+            item_urn: "a/urn".to_string(),
+            full_source_icon: "test/original_icon.png".to_string(),
+            full_destination_icon: "target/tests/sprite_icon/test_transparent.png".to_string(),
+            destination_icon_height: 16,
+            sprite_background: SPRITE_BACKGROUND_TRANSPARENT.to_string(),
+            debug_icon: None,
+            png_optimizer: None,
+        };
+        generator.cleanup(&[CleanupScope::All]).unwrap();
+        generator.create_resources().unwrap();
+        let image = raster::open(&generator.full_destination_icon).unwrap();
+        assert_eq!(image.height, 16);
+        for (x, y) in [
+            (0, 0),
+            (image.width - 1, 0),
+            (0, image.height - 1),
+            (image.width - 1, image.height - 1),
+        ] {
+            assert_eq!(image.get_pixel(x, y).unwrap().a, 0);
+        }
+    }
+
+    #[test]
+    fn test_keep_intermediates() {
+        let config = Config {
+            cache_directory: "target/tests/sprite_icon/keep_intermediates/cache".to_string(),
+            output_directory: "target/tests/sprite_icon/keep_intermediates/distribution"
+                .to_string(),
+            keep_intermediates: true,
+            ..Default::default()
+        };
+        delete_file_or_directory(Path::new("target/tests/sprite_icon/keep_intermediates")).unwrap();
+        let library: Library =
+            serde_yaml_ok::from_str("{name: library, remote_url: distribution}").unwrap();
+        let item: Item = serde_yaml_ok::from_str("urn: Package/Module/Item").unwrap();
+        let icon = Icon::Source {
+            source: "test/original_icon.png".to_string(),
+        };
+        let task = SpriteIconTask::create(
+            &config,
+            &library,
+            &item,
+            &icon,
+            "test/original_icon.png",
+            ("sm", 12),
+        )
+        .unwrap();
+        let debug_icon = Path::new(
+            "target/tests/sprite_icon/keep_intermediates/distribution/debug/Package/Module/ItemSm.png",
+        );
+        assert_eq!(task.outputs(), vec![debug_icon.to_path_buf()]);
+        task.create_resources().unwrap();
+        assert_eq!(
+            raster::open(debug_icon.to_str().unwrap()).unwrap().height,
+            12
+        );
+        // the copy is refreshed even when the cached icon is reused
+        delete_file(debug_icon).unwrap();
+        task.create_resources().unwrap();
+        assert!(debug_icon.exists());
+    }
 }
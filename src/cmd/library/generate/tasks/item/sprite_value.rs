@@ -1,17 +1,20 @@
-use std::fs;
+use std::fs::read;
 use std::io;
 use std::io::Write;
 use std::path::Path;
-use std::process::Command;
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
 use crate::cmd::library::generate::config::Config;
-use crate::cmd::library::generate::task::{CleanupScope, Task};
+use crate::cmd::library::generate::render_cache::RenderCache;
+use crate::cmd::library::generate::task::{
+    CleanupScope, Task, PHASE_CLEANUP, PHASE_CREATE_RESOURCES,
+};
 use crate::cmd::library::manifest::icon::Icon;
 use crate::cmd::library::manifest::item::Item;
-use crate::utils::{create_parent_directory, delete_file};
+use crate::plantuml::{execute_plantuml, RenderError};
+use crate::utils::{create_parent_directory, delete_file, write_file_atomically};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct SpriteValueTask {
@@ -25,6 +28,11 @@ pub struct SpriteValueTask {
     java_binary: String,
     /// The path of the PlantUML jar.
     plantuml_jar: String,
+    /// The number of retries when PlantUML is killed or cannot be started.
+    plantuml_retries: u32,
+    /// The cache of the rendered sprites, when enabled.
+    #[serde(skip)]
+    render_cache: Option<RenderCache>,
 }
 
 impl SpriteValueTask {
@@ -56,11 +64,24 @@ impl SpriteValueTask {
             full_destination_text,
             java_binary: config.java_binary.clone(),
             plantuml_jar: config.plantuml_jar.clone(),
+            plantuml_retries: config.plantuml_retries,
+            render_cache: RenderCache::from_config(config),
         })
     }
 }
 
 impl Task for SpriteValueTask {
+    fn describe(&self) -> String {
+        format!(
+            "SpriteValueTask({}/{})",
+            self.item_urn, self.full_destination_text
+        )
+    }
+
+    fn phases(&self) -> Vec<&'static str> {
+        vec![PHASE_CLEANUP, PHASE_CREATE_RESOURCES]
+    }
+
     fn cleanup(&self, _scopes: &[CleanupScope]) -> Result<()> {
         log::debug!(
             "{} - SpriteValueTask - cleanup {}",
@@ -90,17 +111,37 @@ impl Task for SpriteValueTask {
         // create the destination directory
         create_parent_directory(destination_text_path)?;
 
+        // restore the sprite when the same icon has already been encoded
+        let cache_entry = match &self.render_cache {
+            None => None,
+            Some(render_cache) => {
+                let icon = read(&self.full_source_icon).map_err(|e| {
+                    anyhow::Error::new(e)
+                        .context(format!("unable to read {}", &self.full_source_icon))
+                })?;
+                let key = RenderCache::compute_key(
+                    &[&icon],
+                    "sprite",
+                    &[self.plantuml_jar.as_str(), "-encodesprite", "16z"],
+                );
+                Some((render_cache, key))
+            }
+        };
+        if let Some((render_cache, key)) = &cache_entry {
+            if render_cache.restore(key, destination_text_path)? {
+                return Ok(());
+            }
+        }
+
         // generate the sprite
-        let output = Command::new(&self.java_binary)
-            .arg("-jar")
-            .arg(&self.plantuml_jar)
-            .arg("-encodesprite")
-            .arg("16z")
-            .arg(&self.full_source_icon)
-            .output()
-            .map_err(|e| {
-                anyhow::Error::new(e).context("unable to generate the sprite".to_string())
-            })?;
+        let output = execute_plantuml(
+            &self.java_binary,
+            &self.plantuml_jar,
+            &["-encodesprite", "16z", self.full_source_icon.as_str()],
+            self.plantuml_retries,
+            None,
+        )
+        .map_err(|e| anyhow::Error::new(e).context("unable to generate the sprite".to_string()))?;
 
         // check the generation
         if !output.status.success() {
@@ -110,21 +151,23 @@ impl Task for SpriteValueTask {
             io::stderr()
                 .write_all(&output.stderr)
                 .map_err(|e| anyhow::Error::new(e).context("unable to write stderr".to_string()))?;
-            return Err(anyhow::Error::msg(String::from(
-                "failed to create the sprite",
-            )));
+            return Err(anyhow::Error::new(RenderError {
+                subject: format!("the sprite of {}", &self.full_source_icon),
+            }));
         }
 
-        // write the sprite value
-        let mut writer = fs::File::create(&self.full_destination_text).map_err(|e| {
-            anyhow::Error::new(e)
-                .context(format!("unable to create {}", &self.full_destination_text))
-        })?;
-        writer.write_all(&output.stdout).map_err(|e| {
-            anyhow::Error::new(e)
-                .context(format!("unable to write {}", &self.full_destination_text))
+        // write the sprite value, an interrupted write must not be cached
+        write_file_atomically(destination_text_path, |writer| {
+            writer.write_all(&output.stdout).map_err(|e| {
+                anyhow::Error::new(e)
+                    .context(format!("unable to write {}", &self.full_destination_text))
+            })
         })?;
 
+        if let Some((render_cache, key)) = &cache_entry {
+            render_cache.store(key, destination_text_path)?;
+        }
+
         Ok(())
     }
 }
@@ -144,6 +187,8 @@ mod test {
             full_destination_text: "target/tests/sprite_value/test_generate.text".to_string(),
             java_binary: config.java_binary,
             plantuml_jar: "test/plantuml-1.2022.4.jar".to_string(),
+            plantuml_retries: config.plantuml_retries,
+            render_cache: None,
         };
         generator.cleanup(&[CleanupScope::All]).unwrap();
         generator.create_resources().unwrap();
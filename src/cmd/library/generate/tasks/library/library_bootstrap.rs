@@ -1,21 +1,31 @@
-use std::fs::File;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use tera::{Context, Tera};
 
 use crate::cmd::library::generate::config::Config;
-use crate::cmd::library::generate::task::{CleanupScope, Task};
+use crate::cmd::library::generate::fingerprint::{
+    compute_fingerprint, is_outdated_in, write_fingerprint_in,
+};
+use crate::cmd::library::generate::sink::{resolve_sink, OutputSink};
+use crate::cmd::library::generate::task::{
+    CleanupScope, OutputKind, Task, PHASE_CLEANUP, PHASE_RENDER_ATOMIC_TEMPLATES,
+};
 use crate::cmd::library::manifest::library::Library;
-use crate::utils::{create_parent_directory, delete_file};
+use crate::utils::delete_file;
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct LibraryBootstrapTask {
     /// The name of the library.
     library_name: String,
+    /// The version of the library.
+    library_version: String,
     /// The URL to fetch the library remotely.
     remote_url: String,
+    /// The extension of the generated sources.
+    source_extension: String,
     /// The format of the items' icons.
     icon_format: String,
     /// The width for text.
@@ -38,13 +48,18 @@ pub struct LibraryBootstrapTask {
     output_directory: String,
     /// The name of the Tera template
     template: String,
+    /// The destination of the bootstrap, by default the output directory.
+    #[serde(skip)]
+    sink: Option<Arc<dyn OutputSink>>,
 }
 
 impl LibraryBootstrapTask {
     pub fn create(config: &Config, library: &Library) -> Result<LibraryBootstrapTask> {
         Ok(LibraryBootstrapTask {
             library_name: library.name.clone(),
+            library_version: library.version.clone(),
             remote_url: library.remote_url.clone(),
+            source_extension: library.rendering.source_extension.clone(),
             icon_format: library.customization.icon_format.clone(),
             text_width_max: library.customization.text_width_max,
             msg_width_max: library.customization.msg_width_max,
@@ -56,10 +71,11 @@ impl LibraryBootstrapTask {
             font_color_light: library.customization.font_color_light.clone(),
             output_directory: config.output_directory.clone(),
             template: library.templates.bootstrap.clone(),
+            sink: None,
         })
     }
     fn get_relative_source_path(&self) -> Box<Path> {
-        Box::from(Path::new("bootstrap.puml"))
+        Box::from(Path::new(&format!("bootstrap.{}", self.source_extension)))
     }
     fn get_full_source_path(&self) -> Box<Path> {
         Path::new(&self.output_directory)
@@ -69,40 +85,52 @@ impl LibraryBootstrapTask {
 }
 
 impl Task for LibraryBootstrapTask {
+    fn describe(&self) -> String {
+        format!("LibraryBootstrapTask({})", self.library_name)
+    }
+
+    fn phases(&self) -> Vec<&'static str> {
+        vec![PHASE_CLEANUP, PHASE_RENDER_ATOMIC_TEMPLATES]
+    }
+
+    fn outputs(&self) -> Vec<PathBuf> {
+        vec![self.get_full_source_path().to_path_buf()]
+    }
+
+    fn output_kind(&self) -> OutputKind {
+        OutputKind::Source
+    }
+
     fn cleanup(&self, _scopes: &[CleanupScope]) -> Result<()> {
         log::debug!("{} - LibraryBootstrapTask - cleanup", self.library_name);
-        delete_file(self.get_full_source_path().as_ref())?;
+        if CleanupScope::Bootstrap.is_included_in(_scopes) {
+            delete_file(self.get_full_source_path().as_ref())?;
+        }
         Ok(())
     }
 
-    fn render_atomic_templates(&self, _tera: &Tera) -> Result<()> {
+    fn render_atomic_templates(&self, _tera: &Tera, _context: &Context) -> Result<()> {
         log::debug!(
             "{} - LibraryBootstrapTask - render templates",
             self.library_name
         );
 
-        let destination_path = self.get_full_source_path();
+        let sink = resolve_sink(&self.sink, &self.output_directory);
+        let relative_path = self.get_relative_source_path();
 
         // skip early when generation not required
-        if destination_path.exists() {
+        let fingerprint = compute_fingerprint(self, _tera, &self.template)?;
+        if !is_outdated_in(sink.as_ref(), &relative_path, &fingerprint) {
             return Ok(());
         }
 
-        // create the destination directory
-        create_parent_directory(&destination_path)?;
-
-        // create the destination file
-        let destination_file = File::create(&destination_path).map_err(|e| {
-            anyhow::Error::new(e).context("unable to create the destination file".to_string())
-        })?;
-
-        let mut context = Context::new();
+        let mut context = _context.clone();
         context.insert("data", &self);
-        _tera
-            .render_to(&self.template, &context, destination_file)
-            .map_err(|e| {
-                anyhow::Error::new(e).context(format!("unable to render {}", &self.template))
-            })
+        let content = _tera.render(&self.template, &context).map_err(|e| {
+            anyhow::Error::new(e).context(format!("unable to render {}", &self.template))
+        })?;
+        sink.write(&relative_path, content.as_bytes())?;
+        write_fingerprint_in(sink.as_ref(), &relative_path, &fingerprint)
     }
 }
 
@@ -110,6 +138,7 @@ impl Task for LibraryBootstrapTask {
 mod test {
     use std::fs::read_to_string;
 
+    use crate::cmd::library::generate::sink::MemorySink;
     use crate::cmd::library::generate::templates::TEMPLATES;
     use crate::constants::get_default_template_library_bootstrap;
     use crate::tera::create_tera;
@@ -121,7 +150,9 @@ mod test {
         let tera = &create_tera(TEMPLATES.to_vec(), None).unwrap();
         let generator = LibraryBootstrapTask {
             library_name: "a library".to_string(),
+            library_version: "1.2.3".to_string(),
             remote_url: "a remote url".to_string(),
+            source_extension: "puml".to_string(),
             icon_format: "png".to_string(),
             text_width_max: 300,
             msg_width_max: 400,
@@ -133,14 +164,48 @@ mod test {
             font_color_light: "grey".to_string(),
             output_directory: "target/tests/library_bootstrap_generator".to_string(),
             template: get_default_template_library_bootstrap(),
+            sink: None,
         };
         generator.cleanup(&[CleanupScope::All]).unwrap();
-        generator.render_atomic_templates(tera).unwrap();
+        generator
+            .render_atomic_templates(tera, &Context::new())
+            .unwrap();
         let content =
             read_to_string(format!("{}/bootstrap.puml", generator.output_directory)).unwrap();
         assert!(content.contains(r##"!global $LIB_BASE_LOCATION="a remote url""##));
         assert!(content.contains(r##"!global $ICON_FORMAT="png""##));
         assert!(content.contains(r##"!global $FONT_SIZE_XS=2"##));
         assert!(content.contains(r##"!global $FONT_COLOR="black""##));
+        assert!(content.contains(r##"!global $LIB_VERSION="1.2.3""##));
+    }
+
+    #[test]
+    fn test_template_to_sink() {
+        let tera = &create_tera(TEMPLATES.to_vec(), None).unwrap();
+        let sink = Arc::new(MemorySink::default());
+        let generator = LibraryBootstrapTask {
+            library_name: "a library".to_string(),
+            library_version: "1.2.3".to_string(),
+            remote_url: "a remote url".to_string(),
+            source_extension: "puml".to_string(),
+            icon_format: "png".to_string(),
+            text_width_max: 300,
+            msg_width_max: 400,
+            font_size_xs: 2,
+            font_size_sm: 4,
+            font_size_md: 6,
+            font_size_lg: 8,
+            font_color: "black".to_string(),
+            font_color_light: "grey".to_string(),
+            output_directory: "target/tests/library_bootstrap_sink".to_string(),
+            template: get_default_template_library_bootstrap(),
+            sink: Some(sink.clone()),
+        };
+        generator
+            .render_atomic_templates(tera, &Context::new())
+            .unwrap();
+        let content = sink.get_content("bootstrap.puml").unwrap();
+        assert!(content.contains(r##"!global $LIB_BASE_LOCATION="a remote url""##));
+        assert!(!Path::new("target/tests/library_bootstrap_sink").exists());
     }
 }
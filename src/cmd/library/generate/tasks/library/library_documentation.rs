@@ -1,14 +1,19 @@
-use std::fs::File;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use tera::{Context, Tera};
 
 use crate::cmd::library::generate::config::Config;
-use crate::cmd::library::generate::task::{CleanupScope, Task};
+use crate::cmd::library::generate::fingerprint::{
+    compute_fingerprint, is_outdated, write_fingerprint,
+};
+use crate::cmd::library::generate::task::{
+    CleanupScope, OutputKind, Task, PHASE_CLEANUP, PHASE_RENDER_ATOMIC_TEMPLATES,
+};
 use crate::cmd::library::manifest::library::Library;
-use crate::utils::{create_parent_directory, delete_file};
+use crate::constants::{TEMPLATE_LIBRARY_DOCUMENTATION, TEMPLATE_LIBRARY_DOCUMENTATION_ASCIIDOC};
+use crate::utils::{create_parent_directory, delete_file, write_file_atomically};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Package {
@@ -20,34 +25,51 @@ pub struct Package {
 pub struct LibraryDocumentationTask {
     /// The name of the library.
     library_name: String,
+    /// The version of the library.
+    library_version: String,
     /// The URL to fetch the library remotely.
     remote_url: String,
+    /// The extension of the generated sources.
+    source_extension: String,
     /// The packages of the library.
     packages: Vec<Package>,
     /// The path to the output directory.
     output_directory: String,
     /// The name of the Tera template
     template: String,
+    /// The extension of the documentation file.
+    extension: String,
 }
 
 impl LibraryDocumentationTask {
     pub fn create(config: &Config, library: &Library) -> Result<LibraryDocumentationTask> {
+        let mut packages: Vec<Package> = library
+            .packages
+            .iter()
+            .map(|p| Package {
+                package_urn: p.urn.value.clone(),
+            })
+            .collect();
+        if !config.preserve_order {
+            packages.sort_by(|a, b| a.package_urn.cmp(&b.package_urn));
+        }
         Ok(LibraryDocumentationTask {
             library_name: library.name.clone(),
+            library_version: library.version.clone(),
             remote_url: library.remote_url.clone(),
-            packages: library
-                .packages
-                .iter()
-                .map(|p| Package {
-                    package_urn: p.urn.value.clone(),
-                })
-                .collect(),
+            source_extension: library.rendering.source_extension.clone(),
+            packages,
             output_directory: config.output_directory.clone(),
-            template: library.templates.documentation.clone(),
+            template: config.documentation_format.resolve_template(
+                &library.templates.documentation,
+                TEMPLATE_LIBRARY_DOCUMENTATION,
+                TEMPLATE_LIBRARY_DOCUMENTATION_ASCIIDOC,
+            ),
+            extension: config.documentation_format.get_extension(),
         })
     }
     fn get_relative_destination_path(&self) -> Box<Path> {
-        Box::from(Path::new("README.md"))
+        Box::from(Path::new(format!("README.{}", self.extension).as_str()))
     }
     fn get_full_destination_path(&self) -> Box<Path> {
         Path::new(&self.output_directory)
@@ -57,13 +79,31 @@ impl LibraryDocumentationTask {
 }
 
 impl Task for LibraryDocumentationTask {
+    fn describe(&self) -> String {
+        format!("LibraryDocumentationTask({})", self.library_name)
+    }
+
+    fn phases(&self) -> Vec<&'static str> {
+        vec![PHASE_CLEANUP, PHASE_RENDER_ATOMIC_TEMPLATES]
+    }
+
+    fn outputs(&self) -> Vec<PathBuf> {
+        vec![self.get_full_destination_path().to_path_buf()]
+    }
+
+    fn output_kind(&self) -> OutputKind {
+        OutputKind::Documentation
+    }
+
     fn cleanup(&self, _scopes: &[CleanupScope]) -> Result<()> {
         log::debug!("{} - LibraryDocumentationTask - cleanup", self.library_name);
-        delete_file(self.get_full_destination_path().as_ref())?;
+        if CleanupScope::Documentation.is_included_in(_scopes) {
+            delete_file(self.get_full_destination_path().as_ref())?;
+        }
         Ok(())
     }
 
-    fn render_atomic_templates(&self, _tera: &Tera) -> Result<()> {
+    fn render_atomic_templates(&self, _tera: &Tera, _context: &Context) -> Result<()> {
         log::debug!(
             "{} - LibraryDocumentationTask - render templates",
             self.library_name
@@ -72,25 +112,25 @@ impl Task for LibraryDocumentationTask {
         let destination_path = self.get_full_destination_path();
 
         // skip early when generation not required
-        if destination_path.exists() {
+        let fingerprint = compute_fingerprint(self, _tera, &self.template)?;
+        if !is_outdated(&destination_path, &fingerprint) {
             return Ok(());
         }
 
         // create the destination directory
         create_parent_directory(&destination_path)?;
 
-        // create the destination file
-        let destination_file = File::create(&destination_path).map_err(|e| {
-            anyhow::Error::new(e).context("unable to create the destination file".to_string())
-        })?;
-
-        let mut context = Context::new();
+        let mut context = _context.clone();
         context.insert("data", &self);
-        _tera
-            .render_to(&self.template, &context, destination_file)
-            .map_err(|e| {
-                anyhow::Error::new(e).context(format!("unable to render {}", &self.template))
-            })
+        // the destination file is only created once the rendering is complete
+        write_file_atomically(&destination_path, |destination_file| {
+            _tera
+                .render_to(&self.template, &context, destination_file)
+                .map_err(|e| {
+                    anyhow::Error::new(e).context(format!("unable to render {}", &self.template))
+                })
+        })?;
+        write_fingerprint(&destination_path, &fingerprint)
     }
 }
 
@@ -110,7 +150,9 @@ mod test {
         let tera = &create_tera(TEMPLATES.to_vec(), None).unwrap();
         let generator = LibraryDocumentationTask {
             library_name: "a library".to_string(),
+            library_version: "1.2.3".to_string(),
             remote_url: "a remote url".to_string(),
+            source_extension: "puml".to_string(),
             packages: vec![
                 Package {
                     package_urn: Urn::from("PackageA").value,
@@ -124,10 +166,14 @@ mod test {
             ],
             output_directory: "target/tests/library_documentation_generator".to_string(),
             template: get_default_template_library_documentation(),
+            extension: "md".to_string(),
         };
         generator.cleanup(&[CleanupScope::All]).unwrap();
-        generator.render_atomic_templates(tera).unwrap();
+        generator
+            .render_atomic_templates(tera, &Context::new())
+            .unwrap();
         let content = read_to_string(format!("{}/README.md", generator.output_directory)).unwrap();
+        assert!(content.contains(r##"The version of the library is `1.2.3`."##));
         assert!(content.contains(r##"The library provides 3 packages."##));
         assert!(content.contains(r##"- [PackageA](PackageA/README.md)"##));
         assert!(content.contains(r##"- [PackageB](PackageB/README.md)"##));
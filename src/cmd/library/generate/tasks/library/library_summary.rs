@@ -1,14 +1,19 @@
-use std::fs::File;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use tera::{Context, Tera};
 
 use crate::cmd::library::generate::config::Config;
-use crate::cmd::library::generate::task::{CleanupScope, Task};
+use crate::cmd::library::generate::fingerprint::{
+    compute_fingerprint, is_outdated, write_fingerprint,
+};
+use crate::cmd::library::generate::task::{
+    CleanupScope, OutputKind, Task, PHASE_CLEANUP, PHASE_RENDER_ATOMIC_TEMPLATES,
+};
 use crate::cmd::library::manifest::library::Library;
-use crate::utils::{create_parent_directory, delete_file};
+use crate::constants::{TEMPLATE_LIBRARY_SUMMARY, TEMPLATE_LIBRARY_SUMMARY_ASCIIDOC};
+use crate::utils::{create_parent_directory, delete_file, write_file_atomically};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Item {
@@ -42,39 +47,59 @@ pub struct LibrarySummaryTask {
     output_directory: String,
     /// The name of the Tera template
     template: String,
+    /// The extension of the documentation file.
+    extension: String,
 }
 
 impl LibrarySummaryTask {
     pub fn create(config: &Config, library: &Library) -> Result<LibrarySummaryTask> {
+        let mut packages: Vec<Package> = library
+            .packages
+            .iter()
+            .map(|p| Package {
+                package_urn: p.urn.value.clone(),
+                modules: p
+                    .modules
+                    .iter()
+                    .map(|m| Module {
+                        module_urn: m.urn.value.clone(),
+                        items: m
+                            .items
+                            .iter()
+                            .map(|i| Item {
+                                item_urn: i.urn.value.clone(),
+                            })
+                            .collect(),
+                    })
+                    .collect(),
+            })
+            .collect();
+        // the summary is stable whatever the order of the manifest
+        if !config.preserve_order {
+            packages.sort_by(|a, b| a.package_urn.cmp(&b.package_urn));
+            for package in &mut packages {
+                package
+                    .modules
+                    .sort_by(|a, b| a.module_urn.cmp(&b.module_urn));
+                for module in &mut package.modules {
+                    module.items.sort_by(|a, b| a.item_urn.cmp(&b.item_urn));
+                }
+            }
+        }
         Ok(LibrarySummaryTask {
             library_name: library.name.clone(),
-            packages: library
-                .packages
-                .iter()
-                .map(|p| Package {
-                    package_urn: p.urn.value.clone(),
-                    modules: p
-                        .modules
-                        .iter()
-                        .map(|m| Module {
-                            module_urn: m.urn.value.clone(),
-                            items: m
-                                .items
-                                .iter()
-                                .map(|i| Item {
-                                    item_urn: i.urn.value.clone(),
-                                })
-                                .collect(),
-                        })
-                        .collect(),
-                })
-                .collect(),
+            packages,
             output_directory: config.output_directory.clone(),
-            template: library.templates.summary.clone(),
+            template: config.documentation_format.resolve_template(
+                &library.templates.summary,
+                TEMPLATE_LIBRARY_SUMMARY,
+                TEMPLATE_LIBRARY_SUMMARY_ASCIIDOC,
+            ),
+            extension: config.documentation_format.get_extension(),
         })
     }
     fn get_relative_destination_path(&self) -> Box<Path> {
-        Box::from(Path::new("SUMMARY.md"))
+        Box::from(Path::new(format!("SUMMARY.{}", self.extension).as_str()))
     }
     fn get_full_destination_path(&self) -> Box<Path> {
         Path::new(&self.output_directory)
@@ -84,13 +109,31 @@ impl LibrarySummaryTask {
 }
 
 impl Task for LibrarySummaryTask {
+    fn describe(&self) -> String {
+        format!("LibrarySummaryTask({})", self.library_name)
+    }
+
+    fn phases(&self) -> Vec<&'static str> {
+        vec![PHASE_CLEANUP, PHASE_RENDER_ATOMIC_TEMPLATES]
+    }
+
+    fn outputs(&self) -> Vec<PathBuf> {
+        vec![self.get_full_destination_path().to_path_buf()]
+    }
+
+    fn output_kind(&self) -> OutputKind {
+        OutputKind::Documentation
+    }
+
     fn cleanup(&self, _scopes: &[CleanupScope]) -> Result<()> {
         log::debug!("{} - LibrarySummaryTask - cleanup", self.library_name);
-        delete_file(self.get_full_destination_path().as_ref())?;
+        if CleanupScope::Documentation.is_included_in(_scopes) {
+            delete_file(self.get_full_destination_path().as_ref())?;
+        }
         Ok(())
     }
 
-    fn render_atomic_templates(&self, _tera: &Tera) -> Result<()> {
+    fn render_atomic_templates(&self, _tera: &Tera, _context: &Context) -> Result<()> {
         log::debug!(
             "{} - LibrarySummaryTask - render templates",
             self.library_name
@@ -99,25 +142,25 @@ impl Task for LibrarySummaryTask {
         let destination_path = self.get_full_destination_path();
 
         // skip early when generation not required
-        if destination_path.exists() {
+        let fingerprint = compute_fingerprint(self, _tera, &self.template)?;
+        if !is_outdated(&destination_path, &fingerprint) {
             return Ok(());
         }
 
         // create the destination directory
         create_parent_directory(&destination_path)?;
 
-        // create the destination file
-        let destination_file = File::create(&destination_path).map_err(|e| {
-            anyhow::Error::new(e).context("unable to create the destination file".to_string())
-        })?;
-
-        let mut context = Context::new();
+        let mut context = _context.clone();
         context.insert("data", &self);
-        _tera
-            .render_to(&self.template, &context, destination_file)
-            .map_err(|e| {
-                anyhow::Error::new(e).context(format!("unable to render {}", &self.template))
-            })
+        // the destination file is only created once the rendering is complete
+        write_file_atomically(&destination_path, |destination_file| {
+            _tera
+                .render_to(&self.template, &context, destination_file)
+                .map_err(|e| {
+                    anyhow::Error::new(e).context(format!("unable to render {}", &self.template))
+                })
+        })?;
+        write_fingerprint(&destination_path, &fingerprint)
     }
 }
 
@@ -125,6 +168,7 @@ impl Task for LibrarySummaryTask {
 mod test {
     use std::fs::read_to_string;
 
+    use crate::cmd::library::generate::config::DocumentationFormat;
     use crate::cmd::library::generate::templates::TEMPLATES;
     use crate::constants::get_default_template_library_summary;
     use crate::tera::create_tera;
@@ -177,9 +221,12 @@ mod test {
             ],
             output_directory: "target/tests/library_summary_generator".to_string(),
             template: get_default_template_library_summary(),
+            extension: "md".to_string(),
         };
         generator.cleanup(&[CleanupScope::All]).unwrap();
-        generator.render_atomic_templates(tera).unwrap();
+        generator
+            .render_atomic_templates(tera, &Context::new())
+            .unwrap();
         let content = read_to_string(format!("{}/SUMMARY.md", generator.output_directory)).unwrap();
         assert!(content.contains("[Presentation](README.md)"));
         assert!(content.contains("# aws-q1-2022"));
@@ -189,4 +236,87 @@ mod test {
         );
         assert!(content.contains("- [aws-q1-2022/Architecture/Analytics/AmazonAthena](aws-q1-2022/Architecture/Analytics/AmazonAthena.md)"));
     }
+
+    fn render_summary(config: &Config, yaml: &str) -> String {
+        let tera = &create_tera(TEMPLATES.to_vec(), None).unwrap();
+        let library: &Library = &serde_yaml_ok::from_str(yaml).unwrap();
+        let task = LibrarySummaryTask::create(config, library).unwrap();
+        task.cleanup(&[CleanupScope::All]).unwrap();
+        task.render_atomic_templates(tera, &Context::new()).unwrap();
+        read_to_string(format!(
+            "{}/SUMMARY.{}",
+            config.output_directory,
+            config.documentation_format.get_extension()
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_sorted_urns() {
+        let yaml_a = r#"
+            name: library
+            remote_url: http://localhost:3000/distribution
+            packages:
+              - urn: PackageA
+                modules:
+                  - urn: PackageA/ModuleA
+                    items:
+                      - urn: PackageA/ModuleA/ItemA
+                      - urn: PackageA/ModuleA/ItemB
+                  - urn: PackageA/ModuleB
+              - urn: PackageB
+        "#;
+        let yaml_b = r#"
+            name: library
+            remote_url: http://localhost:3000/distribution
+            packages:
+              - urn: PackageB
+              - urn: PackageA
+                modules:
+                  - urn: PackageA/ModuleB
+                  - urn: PackageA/ModuleA
+                    items:
+                      - urn: PackageA/ModuleA/ItemB
+                      - urn: PackageA/ModuleA/ItemA
+        "#;
+        let config_a = &Config::default()
+            .rebase_directories("target/tests/library_summary/sorted_urns/a".to_string());
+        let config_b = &Config::default()
+            .rebase_directories("target/tests/library_summary/sorted_urns/b".to_string());
+        let content_a = render_summary(config_a, yaml_a);
+        assert_eq!(content_a, render_summary(config_b, yaml_b));
+
+        let config_b = &Config {
+            preserve_order: true,
+            ..config_b.clone()
+        };
+        let content_b = render_summary(config_b, yaml_b);
+        assert_ne!(content_a, content_b);
+        assert!(content_b.find("# PackageB").unwrap() < content_b.find("# PackageA").unwrap());
+    }
+
+    #[test]
+    fn test_asciidoc() {
+        let yaml = r#"
+            name: library
+            remote_url: http://localhost:3000/distribution
+            packages:
+              - urn: PackageA
+                modules:
+                  - urn: PackageA/ModuleA
+                    items:
+                      - urn: PackageA/ModuleA/ItemA
+        "#;
+        let config = &Config {
+            documentation_format: DocumentationFormat::AsciiDoc,
+            ..Config::default()
+                .rebase_directories("target/tests/library_summary/asciidoc".to_string())
+        };
+        let content = render_summary(config, yaml);
+        assert!(content.contains("* xref:README.adoc[Presentation]"));
+        assert!(content.contains("* xref:PackageA/README.adoc[Presentation]"));
+        assert!(content.contains("* xref:PackageA/ModuleA/README.adoc[PackageA/ModuleA]"));
+        assert!(content.contains("** xref:PackageA/ModuleA/ItemA.adoc[PackageA/ModuleA/ItemA]"));
+        assert!(!content.contains(".md"));
+    }
 }
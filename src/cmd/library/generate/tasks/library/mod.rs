@@ -13,9 +13,17 @@ mod library_summary;
 
 pub fn parse_library(config: &Config, library: &Library) -> Result<Vec<Box<dyn Task>>> {
     log::debug!("parse library {}", &library.name);
-    Ok(vec![
-        Box::from(LibraryBootstrapTask::create(config, library)?),
-        Box::from(LibraryDocumentationTask::create(config, library)?),
-        Box::from(LibrarySummaryTask::create(config, library)?),
-    ])
+    let mut tasks: Vec<Box<dyn Task>> = vec![];
+    if !config.only_docs {
+        tasks.push(Box::from(LibraryBootstrapTask::create(config, library)?));
+    }
+    // the bootstrap is required by the snippets of the items
+    if config.exact_urn {
+        return Ok(tasks);
+    }
+    tasks.push(Box::from(LibraryDocumentationTask::create(
+        config, library,
+    )?));
+    tasks.push(Box::from(LibrarySummaryTask::create(config, library)?));
+    Ok(tasks)
 }
@@ -1,15 +1,20 @@
-use std::fs::File;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use tera::{Context, Tera};
 
 use crate::cmd::library::generate::config::Config;
-use crate::cmd::library::generate::task::{CleanupScope, Task};
+use crate::cmd::library::generate::fingerprint::{
+    compute_fingerprint, is_outdated, write_fingerprint,
+};
+use crate::cmd::library::generate::task::{
+    CleanupScope, OutputKind, Task, PHASE_CLEANUP, PHASE_RENDER_ATOMIC_TEMPLATES,
+};
 use crate::cmd::library::manifest::library::Library;
 use crate::cmd::library::manifest::module::Module;
-use crate::utils::{create_parent_directory, delete_file};
+use crate::constants::{TEMPLATE_MODULE_DOCUMENTATION, TEMPLATE_MODULE_DOCUMENTATION_ASCIIDOC};
+use crate::utils::{create_parent_directory, delete_file, write_file_atomically};
 
 type ItemManifest = crate::cmd::library::manifest::item::Item;
 
@@ -39,6 +44,8 @@ pub struct ModuleDocumentationTask {
     output_directory: String,
     /// The name of the Tera template
     template: String,
+    /// The extension of the documentation file.
+    extension: String,
 }
 
 pub fn resolve_illustration(library: &Library, item: &ItemManifest) -> String {
@@ -56,12 +63,15 @@ impl ModuleDocumentationTask {
         library: &Library,
         module: &Module,
     ) -> Result<ModuleDocumentationTask> {
+        let mut items: Vec<&ItemManifest> = module.items.iter().collect();
+        if !config.preserve_order {
+            items.sort_by(|a, b| a.urn.value.cmp(&b.urn.value));
+        }
         Ok(ModuleDocumentationTask {
             module_urn: module.urn.value.clone(),
             module_name: module.urn.name.clone(),
             path_to_base: module.urn.path_to_base.clone(),
-            items_with_family: module
-                .items
+            items_with_family: items
                 .iter()
                 .filter(|i| i.family.is_some())
                 .map(|item| Item {
@@ -70,8 +80,7 @@ impl ModuleDocumentationTask {
                     illustration: resolve_illustration(library, item),
                 })
                 .collect(),
-            items_without_family: module
-                .items
+            items_without_family: items
                 .iter()
                 .filter(|i| i.family.is_none())
                 .map(|item| Item {
@@ -81,12 +90,17 @@ impl ModuleDocumentationTask {
                 })
                 .collect(),
             output_directory: config.output_directory.clone(),
-            template: module.templates.documentation.clone(),
+            template: config.documentation_format.resolve_template(
+                &module.templates.documentation,
+                TEMPLATE_MODULE_DOCUMENTATION,
+                TEMPLATE_MODULE_DOCUMENTATION_ASCIIDOC,
+            ),
+            extension: config.documentation_format.get_extension(),
         })
     }
     fn get_relative_destination_path(&self) -> Box<Path> {
         Box::from(Path::new(
-            format!("{}/README.md", self.module_urn, ).as_str(),
+            format!("{}/README.{}", self.module_urn, self.extension).as_str(),
         ))
     }
     fn get_full_destination_path(&self) -> Box<Path> {
@@ -97,13 +111,31 @@ impl ModuleDocumentationTask {
 }
 
 impl Task for ModuleDocumentationTask {
+    fn describe(&self) -> String {
+        format!("ModuleDocumentationTask({})", self.module_urn)
+    }
+
+    fn phases(&self) -> Vec<&'static str> {
+        vec![PHASE_CLEANUP, PHASE_RENDER_ATOMIC_TEMPLATES]
+    }
+
+    fn outputs(&self) -> Vec<PathBuf> {
+        vec![self.get_full_destination_path().to_path_buf()]
+    }
+
+    fn output_kind(&self) -> OutputKind {
+        OutputKind::Documentation
+    }
+
     fn cleanup(&self, _scopes: &[CleanupScope]) -> Result<()> {
         log::debug!("{} - ModuleDocumentationTask - cleanup", self.module_urn);
-        delete_file(self.get_full_destination_path().as_ref())?;
+        if CleanupScope::Documentation.is_included_in(_scopes) {
+            delete_file(self.get_full_destination_path().as_ref())?;
+        }
         Ok(())
     }
 
-    fn render_atomic_templates(&self, _tera: &Tera) -> Result<()> {
+    fn render_atomic_templates(&self, _tera: &Tera, _context: &Context) -> Result<()> {
         log::debug!(
             "{} - ModuleDocumentationTask - render templates",
             self.module_urn
@@ -112,25 +144,25 @@ impl Task for ModuleDocumentationTask {
         let destination_path = self.get_full_destination_path();
 
         // skip early when generation not required
-        if destination_path.exists() {
+        let fingerprint = compute_fingerprint(self, _tera, &self.template)?;
+        if !is_outdated(&destination_path, &fingerprint) {
             return Ok(());
         }
 
         // create the destination directory
         create_parent_directory(&destination_path)?;
 
-        // create the destination file
-        let destination_file = File::create(&destination_path).map_err(|e| {
-            anyhow::Error::new(e).context("unable to create the destination file".to_string())
-        })?;
-
-        let mut context = Context::new();
+        let mut context = _context.clone();
         context.insert("data", &self);
-        _tera
-            .render_to(&self.template, &context, destination_file)
-            .map_err(|e| {
-                anyhow::Error::new(e).context(format!("unable to render {}", &self.template))
-            })
+        // the destination file is only created once the rendering is complete
+        write_file_atomically(&destination_path, |destination_file| {
+            _tera
+                .render_to(&self.template, &context, destination_file)
+                .map_err(|e| {
+                    anyhow::Error::new(e).context(format!("unable to render {}", &self.template))
+                })
+        })?;
+        write_fingerprint(&destination_path, &fingerprint)
     }
 }
 
@@ -181,9 +213,12 @@ mod test {
             }],
             output_directory: "target/tests/module_documentation_generator".to_string(),
             template: get_default_template_module_documentation(),
+            extension: "md".to_string(),
         };
         generator.cleanup(&[CleanupScope::All]).unwrap();
-        generator.render_atomic_templates(tera).unwrap();
+        generator
+            .render_atomic_templates(tera, &Context::new())
+            .unwrap();
         let content = read_to_string(format!(
             "{}/Package/Module/README.md",
             generator.output_directory
@@ -24,21 +24,43 @@ pub fn parse_package(
     log::debug!("parse package {}", &_package.urn);
     let mut tasks: Vec<Box<dyn Task>> = vec![];
 
+    // the bootstrap is required by the snippets of the items
+    if _config.exact_urn {
+        if !_config.only_docs {
+            tasks.push(Box::from(PackageBootstrapTask::create(
+                _config, _library, _package,
+            )?));
+        }
+        return Ok(tasks);
+    }
+
+    // the documentation doesn't depend on the other artifacts of the package
+    if _config.only_docs {
+        tasks.push(Box::from(PackageDocumentationTask::create(
+            _config, _library, _package,
+        )?));
+        return Ok(tasks);
+    }
+
     for example in _package.examples.iter() {
         tasks.push(Box::from(PackageExampleTask::create(
             _config, _library, _package, example,
         )?));
     }
 
-    tasks.push(Box::from(PackageBootstrapTask::create(_config, _package)?));
+    tasks.push(Box::from(PackageBootstrapTask::create(
+        _config, _library, _package,
+    )?));
     if !_package.rendering.skip_embedded {
         tasks.push(Box::from(PackageEmbeddedTask::create(
             _config,
+            _library,
             _package,
             EmbeddedMode::Single,
         )?));
         tasks.push(Box::from(PackageEmbeddedTask::create(
             _config,
+            _library,
             _package,
             EmbeddedMode::Full,
         )?));
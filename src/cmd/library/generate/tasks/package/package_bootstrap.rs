@@ -1,19 +1,26 @@
-use std::fs::File;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use tera::{Context, Tera};
 
 use crate::cmd::library::generate::config::Config;
-use crate::cmd::library::generate::task::{CleanupScope, Task};
+use crate::cmd::library::generate::fingerprint::{
+    compute_fingerprint, is_outdated, write_fingerprint,
+};
+use crate::cmd::library::generate::task::{
+    CleanupScope, OutputKind, Task, PHASE_CLEANUP, PHASE_RENDER_ATOMIC_TEMPLATES,
+};
+use crate::cmd::library::manifest::library::Library;
 use crate::cmd::library::manifest::package::Package;
-use crate::utils::{create_parent_directory, delete_file};
+use crate::utils::{create_parent_directory, delete_file, write_file_atomically};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct PackageBootstrapTask {
     /// The URN of the package.
     package_urn: String,
+    /// The extension of the generated sources.
+    source_extension: String,
     /// The path to the output directory.
     output_directory: String,
     /// The name of the Tera template
@@ -21,16 +28,21 @@ pub struct PackageBootstrapTask {
 }
 
 impl PackageBootstrapTask {
-    pub fn create(config: &Config, package: &Package) -> Result<PackageBootstrapTask> {
+    pub fn create(
+        config: &Config,
+        library: &Library,
+        package: &Package,
+    ) -> Result<PackageBootstrapTask> {
         Ok(PackageBootstrapTask {
             package_urn: package.urn.value.clone(),
+            source_extension: library.rendering.source_extension.clone(),
             output_directory: config.output_directory.clone(),
             template: package.templates.bootstrap.clone(),
         })
     }
     fn get_relative_destination_path(&self) -> Box<Path> {
         Box::from(Path::new(
-            format!("{}/bootstrap.puml", self.package_urn).as_str(),
+            format!("{}/bootstrap.{}", self.package_urn, self.source_extension).as_str(),
         ))
     }
     fn get_full_destination_path(&self) -> Box<Path> {
@@ -41,13 +53,31 @@ impl PackageBootstrapTask {
 }
 
 impl Task for PackageBootstrapTask {
+    fn describe(&self) -> String {
+        format!("PackageBootstrapTask({})", self.package_urn)
+    }
+
+    fn phases(&self) -> Vec<&'static str> {
+        vec![PHASE_CLEANUP, PHASE_RENDER_ATOMIC_TEMPLATES]
+    }
+
+    fn outputs(&self) -> Vec<PathBuf> {
+        vec![self.get_full_destination_path().to_path_buf()]
+    }
+
+    fn output_kind(&self) -> OutputKind {
+        OutputKind::Source
+    }
+
     fn cleanup(&self, _scopes: &[CleanupScope]) -> Result<()> {
         log::debug!("{} - PackageBootstrapTask - cleanup", self.package_urn);
-        delete_file(self.get_full_destination_path().as_ref())?;
+        if CleanupScope::Bootstrap.is_included_in(_scopes) {
+            delete_file(self.get_full_destination_path().as_ref())?;
+        }
         Ok(())
     }
 
-    fn render_atomic_templates(&self, _tera: &Tera) -> Result<()> {
+    fn render_atomic_templates(&self, _tera: &Tera, _context: &Context) -> Result<()> {
         log::debug!(
             "{} - PackageBootstrapTask - render templates",
             self.package_urn
@@ -56,25 +86,25 @@ impl Task for PackageBootstrapTask {
         let destination_path = self.get_full_destination_path();
 
         // skip early when generation not required
-        if destination_path.exists() {
+        let fingerprint = compute_fingerprint(self, _tera, &self.template)?;
+        if !is_outdated(&destination_path, &fingerprint) {
             return Ok(());
         }
 
         // create the destination directory
         create_parent_directory(&destination_path)?;
 
-        // create the destination file
-        let destination_file = File::create(&destination_path).map_err(|e| {
-            anyhow::Error::new(e).context("unable to create the destination file".to_string())
-        })?;
-
-        let mut context = Context::new();
+        let mut context = _context.clone();
         context.insert("data", &self);
-        _tera
-            .render_to(&self.template, &context, destination_file)
-            .map_err(|e| {
-                anyhow::Error::new(e).context(format!("unable to render {}", &self.template))
-            })
+        // the destination file is only created once the rendering is complete
+        write_file_atomically(&destination_path, |destination_file| {
+            _tera
+                .render_to(&self.template, &context, destination_file)
+                .map_err(|e| {
+                    anyhow::Error::new(e).context(format!("unable to render {}", &self.template))
+                })
+        })?;
+        write_fingerprint(&destination_path, &fingerprint)
     }
 }
 
@@ -92,11 +122,14 @@ mod test {
         let tera = &create_tera(TEMPLATES.to_vec(), Some("test/tera/*".to_string())).unwrap();
         let generator = PackageBootstrapTask {
             package_urn: "Package".to_string(),
+            source_extension: "puml".to_string(),
             output_directory: "target/tests/package_bootstrap_generator".to_string(),
             template: "package_bootstrap_bis.tera".to_string(),
         };
         generator.cleanup(&[CleanupScope::All]).unwrap();
-        generator.render_atomic_templates(tera).unwrap();
+        generator
+            .render_atomic_templates(tera, &Context::new())
+            .unwrap();
         let content = read_to_string(format!(
             "{}/Package/bootstrap.puml",
             generator.output_directory
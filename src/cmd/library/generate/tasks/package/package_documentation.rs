@@ -1,15 +1,20 @@
-use std::fs::File;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use tera::{Context, Tera};
 
 use crate::cmd::library::generate::config::Config;
-use crate::cmd::library::generate::task::{CleanupScope, Task};
+use crate::cmd::library::generate::fingerprint::{
+    compute_fingerprint, is_outdated, write_fingerprint,
+};
+use crate::cmd::library::generate::task::{
+    CleanupScope, OutputKind, Task, PHASE_CLEANUP, PHASE_RENDER_ATOMIC_TEMPLATES,
+};
 use crate::cmd::library::manifest::library::Library;
 use crate::cmd::library::manifest::package::Package;
-use crate::utils::{create_parent_directory, delete_file};
+use crate::constants::{TEMPLATE_PACKAGE_DOCUMENTATION, TEMPLATE_PACKAGE_DOCUMENTATION_ASCIIDOC};
+use crate::utils::{create_parent_directory, delete_file, write_file_atomically};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Module {
@@ -41,6 +46,8 @@ pub struct PackageDocumentationTask {
     is_embedded_enabled: bool,
     /// True when the package is embedded.
     remote_url: String,
+    /// The extension of the generated sources.
+    source_extension: String,
     /// The relative path to the library base path.
     path_to_base: String,
     /// The modules of the package.
@@ -51,6 +58,8 @@ pub struct PackageDocumentationTask {
     output_directory: String,
     /// The name of the Tera template
     template: String,
+    /// The extension of the documentation file.
+    extension: String,
 }
 
 impl PackageDocumentationTask {
@@ -59,21 +68,26 @@ impl PackageDocumentationTask {
         library: &Library,
         package: &Package,
     ) -> Result<PackageDocumentationTask> {
+        let mut modules: Vec<Module> = package
+            .modules
+            .iter()
+            .map(|module| Module {
+                module_urn: module.urn.value.clone(),
+                module_name: module.urn.name.clone(),
+                nbr_items: module.items.len() as u32,
+            })
+            .collect();
+        if !config.preserve_order {
+            modules.sort_by(|a, b| a.module_urn.cmp(&b.module_urn));
+        }
         Ok(PackageDocumentationTask {
             package_urn: package.urn.value.clone(),
             package_name: package.urn.name.clone(),
             path_to_base: package.urn.path_to_base.clone(),
             is_embedded_enabled: !package.rendering.skip_embedded,
             remote_url: library.remote_url.clone(),
-            modules: package
-                .modules
-                .iter()
-                .map(|module| Module {
-                    module_urn: module.urn.value.clone(),
-                    module_name: module.urn.name.clone(),
-                    nbr_items: module.items.len() as u32,
-                })
-                .collect(),
+            source_extension: library.rendering.source_extension.clone(),
+            modules,
             examples: package
                 .examples
                 .iter()
@@ -85,12 +99,17 @@ impl PackageDocumentationTask {
                 })
                 .collect(),
             output_directory: config.output_directory.clone(),
-            template: package.templates.documentation.clone(),
+            template: config.documentation_format.resolve_template(
+                &package.templates.documentation,
+                TEMPLATE_PACKAGE_DOCUMENTATION,
+                TEMPLATE_PACKAGE_DOCUMENTATION_ASCIIDOC,
+            ),
+            extension: config.documentation_format.get_extension(),
         })
     }
     fn get_relative_destination_path(&self) -> Box<Path> {
         Box::from(Path::new(
-            format!("{}/README.md", self.package_urn).as_str(),
+            format!("{}/README.{}", self.package_urn, self.extension).as_str(),
         ))
     }
     fn get_full_destination_path(&self) -> Box<Path> {
@@ -101,13 +120,31 @@ impl PackageDocumentationTask {
 }
 
 impl Task for PackageDocumentationTask {
+    fn describe(&self) -> String {
+        format!("PackageDocumentationTask({})", self.package_urn)
+    }
+
+    fn phases(&self) -> Vec<&'static str> {
+        vec![PHASE_CLEANUP, PHASE_RENDER_ATOMIC_TEMPLATES]
+    }
+
+    fn outputs(&self) -> Vec<PathBuf> {
+        vec![self.get_full_destination_path().to_path_buf()]
+    }
+
+    fn output_kind(&self) -> OutputKind {
+        OutputKind::Documentation
+    }
+
     fn cleanup(&self, _scopes: &[CleanupScope]) -> Result<()> {
         log::debug!("{} - PackageDocumentationTask - cleanup", self.package_urn);
-        delete_file(self.get_full_destination_path().as_ref())?;
+        if CleanupScope::Documentation.is_included_in(_scopes) {
+            delete_file(self.get_full_destination_path().as_ref())?;
+        }
         Ok(())
     }
 
-    fn render_atomic_templates(&self, _tera: &Tera) -> Result<()> {
+    fn render_atomic_templates(&self, _tera: &Tera, _context: &Context) -> Result<()> {
         log::debug!(
             "{} - PackageDocumentationTask - render templates",
             self.package_urn
@@ -116,25 +153,25 @@ impl Task for PackageDocumentationTask {
         let destination_path = self.get_full_destination_path();
 
         // skip early when generation not required
-        if destination_path.exists() {
+        let fingerprint = compute_fingerprint(self, _tera, &self.template)?;
+        if !is_outdated(&destination_path, &fingerprint) {
             return Ok(());
         }
 
         // create the destination directory
         create_parent_directory(&destination_path)?;
 
-        // create the destination file
-        let destination_file = File::create(&destination_path).map_err(|e| {
-            anyhow::Error::new(e).context("unable to create the destination file".to_string())
-        })?;
-
-        let mut context = Context::new();
+        let mut context = _context.clone();
         context.insert("data", &self);
-        _tera
-            .render_to(&self.template, &context, destination_file)
-            .map_err(|e| {
-                anyhow::Error::new(e).context(format!("unable to render {}", &self.template))
-            })
+        // the destination file is only created once the rendering is complete
+        write_file_atomically(&destination_path, |destination_file| {
+            _tera
+                .render_to(&self.template, &context, destination_file)
+                .map_err(|e| {
+                    anyhow::Error::new(e).context(format!("unable to render {}", &self.template))
+                })
+        })?;
+        write_fingerprint(&destination_path, &fingerprint)
     }
 }
 
@@ -161,6 +198,7 @@ mod test {
             path_to_base: package_urn.path_to_base,
             is_embedded_enabled: true,
             remote_url: "http://test.local".to_string(),
+            source_extension: "puml".to_string(),
             modules: vec![
                 Module {
                     module_urn: module_a_urn.value,
@@ -187,9 +225,12 @@ mod test {
             ],
             output_directory: "target/tests/package_documentation_generator".to_string(),
             template: get_default_template_package_documentation(),
+            extension: "md".to_string(),
         };
         generator.cleanup(&[CleanupScope::All]).unwrap();
-        generator.render_atomic_templates(tera).unwrap();
+        generator
+            .render_atomic_templates(tera, &Context::new())
+            .unwrap();
         let content =
             read_to_string(format!("{}/Package/README.md", generator.output_directory)).unwrap();
         assert!(content.contains("# Package"));
@@ -1,14 +1,21 @@
-use std::fs::File;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use tera::{Context, Tera};
 
 use crate::cmd::library::generate::config::Config;
-use crate::cmd::library::generate::task::{CleanupScope, Task};
+use crate::cmd::library::generate::fingerprint::{
+    compute_fingerprint, is_outdated, write_fingerprint,
+};
+use crate::cmd::library::generate::task::{
+    CleanupScope, OutputKind, Task, PHASE_CLEANUP, PHASE_RENDER_COMPOSED_TEMPLATES,
+};
+use crate::cmd::library::manifest::library::Library;
 use crate::cmd::library::manifest::package::Package;
-use crate::utils::{create_parent_directory, delete_file, read_file_to_string};
+use crate::utils::{
+    create_parent_directory, delete_file, read_file_to_string, write_file_atomically,
+};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct PackageEmbeddedTask {
@@ -22,6 +29,8 @@ pub struct PackageEmbeddedTask {
     package_bootstrap_file: Option<String>,
     /// The definition of the package's items.
     package_item_files: Vec<String>,
+    /// The extension of the generated sources.
+    source_extension: String,
     /// The path to the output directory.
     output_directory: String,
     /// The name of the Tera template
@@ -37,15 +46,17 @@ pub enum EmbeddedMode {
 impl PackageEmbeddedTask {
     pub fn create(
         _config: &Config,
+        _library: &Library,
         _package: &Package,
         mode: EmbeddedMode,
     ) -> Result<PackageEmbeddedTask> {
         let output_directory = _config.output_directory.clone();
+        let source_extension = _library.rendering.source_extension.clone();
 
         let library_path = Path::new(&output_directory);
         let library_bootstrap_file = match mode {
             EmbeddedMode::Single => library_path
-                .join("bootstrap.puml")
+                .join(format!("bootstrap.{}", source_extension))
                 .as_path()
                 .to_str()
                 .map(|str| str.to_string()),
@@ -54,7 +65,7 @@ impl PackageEmbeddedTask {
 
         let package_path = library_path.join(_package.urn.clone().to_string());
         let package_bootstrap_file = package_path
-            .join("bootstrap.puml")
+            .join(format!("bootstrap.{}", source_extension))
             .as_path()
             .to_str()
             .map(|str| str.to_string());
@@ -63,7 +74,7 @@ impl PackageEmbeddedTask {
         for module in &_package.modules {
             for item in &module.items {
                 let item_file = library_path
-                    .join(format!("{}.puml", item.urn))
+                    .join(format!("{}.{}", item.urn, source_extension))
                     .as_path()
                     .to_str()
                     .map(|str| str.to_string());
@@ -79,6 +90,7 @@ impl PackageEmbeddedTask {
             library_bootstrap_file,
             package_bootstrap_file,
             package_item_files,
+            source_extension,
             output_directory,
             template: _package.templates.embedded.clone(),
         })
@@ -101,12 +113,13 @@ impl PackageEmbeddedTask {
     fn get_relative_destination_path(&self) -> Box<Path> {
         Box::from(Path::new(
             format!(
-                "{}/{}.puml",
+                "{}/{}.{}",
                 self.package_urn,
                 match self.mode {
                     EmbeddedMode::Single => "single",
                     EmbeddedMode::Full => "full",
-                }
+                },
+                self.source_extension
             )
                 .as_str(),
         ))
@@ -119,13 +132,31 @@ impl PackageEmbeddedTask {
 }
 
 impl Task for PackageEmbeddedTask {
+    fn describe(&self) -> String {
+        format!("PackageEmbeddedTask({}/{:?})", self.package_urn, self.mode)
+    }
+
+    fn phases(&self) -> Vec<&'static str> {
+        vec![PHASE_CLEANUP, PHASE_RENDER_COMPOSED_TEMPLATES]
+    }
+
+    fn outputs(&self) -> Vec<PathBuf> {
+        vec![self.get_embedded_destination_path().to_path_buf()]
+    }
+
+    fn output_kind(&self) -> OutputKind {
+        OutputKind::Source
+    }
+
     fn cleanup(&self, _scopes: &[CleanupScope]) -> Result<()> {
         log::debug!("{} - PackageEmbeddedTask - cleanup", self.package_urn);
-        delete_file(self.get_embedded_destination_path().as_ref())?;
+        if CleanupScope::Embedded.is_included_in(_scopes) {
+            delete_file(self.get_embedded_destination_path().as_ref())?;
+        }
         Ok(())
     }
 
-    fn render_composed_templates(&self, _tera: &Tera) -> Result<()> {
+    fn render_composed_templates(&self, _tera: &Tera, _context: &Context) -> Result<()> {
         log::debug!(
             "{} - PackageEmbeddedTask - render templates",
             self.package_urn
@@ -133,35 +164,45 @@ impl Task for PackageEmbeddedTask {
 
         let destination_path = self.get_embedded_destination_path();
 
+        // the embedded file is composed of other artifacts, their content is fingerprinted too
+        let library_bootstrap = self.get_library_bootstrap();
+        let package_bootstrap = self.get_package_bootstrap();
+        let package_items = self.get_package_items();
+
         // skip early when generation not required
-        if destination_path.exists() {
+        let fingerprint = compute_fingerprint(
+            &(self, &library_bootstrap, &package_bootstrap, &package_items),
+            _tera,
+            &self.template,
+        )?;
+        if !is_outdated(&destination_path, &fingerprint) {
             return Ok(());
         }
 
         // create the destination directory
         create_parent_directory(&destination_path)?;
 
-        // create the destination file
-        let destination_file = File::create(&destination_path).map_err(|e| {
-            anyhow::Error::new(e).context("unable to create the destination file".to_string())
-        })?;
-
-        let mut context = Context::new();
+        let mut context = _context.clone();
         context.insert("data", &self);
-        context.insert("library_bootstrap", &self.get_library_bootstrap());
-        context.insert("package_bootstrap", &self.get_package_bootstrap());
-        context.insert("package_items", &self.get_package_items());
-        _tera
-            .render_to(&self.template, &context, destination_file)
-            .map_err(|e| {
-                anyhow::Error::new(e).context(format!("unable to render {}", &self.template))
-            })
+        context.insert("library_bootstrap", &library_bootstrap);
+        context.insert("package_bootstrap", &package_bootstrap);
+        context.insert("package_items", &package_items);
+        // the destination file is only created once the rendering is complete
+        write_file_atomically(&destination_path, |destination_file| {
+            _tera
+                .render_to(&self.template, &context, destination_file)
+                .map_err(|e| {
+                    anyhow::Error::new(e).context(format!("unable to render {}", &self.template))
+                })
+        })?;
+        write_fingerprint(&destination_path, &fingerprint)
     }
 }
 
 #[cfg(test)]
 mod test {
     use std::fs::read_to_string;
+    use std::fs::File;
     use std::io::Write;
 
     use crate::cmd::library::generate::templates::TEMPLATES;
@@ -202,6 +243,7 @@ mod test {
                 "target/tests/package_embedded_generator/package_urn/package_item_file_b.txt"
                     .to_string(),
             ],
+            source_extension: "puml".to_string(),
             output_directory: "target/tests/package_embedded_generator".to_string(),
             template: get_default_template_package_embedded(),
         };
@@ -213,7 +255,8 @@ mod test {
         write_fixture_file("package_item_file_b");
 
         task.cleanup(&[CleanupScope::All]).unwrap();
-        task.render_composed_templates(tera).unwrap();
+        task.render_composed_templates(tera, &Context::new())
+            .unwrap();
 
         let content =
             read_to_string(format!("{}/package_urn/single.puml", task.output_directory)).unwrap();
@@ -240,6 +283,7 @@ mod test {
                 "target/tests/package_embedded_generator/package_urn/package_item_file_b.txt"
                     .to_string(),
             ],
+            source_extension: "puml".to_string(),
             output_directory: "target/tests/package_embedded_generator".to_string(),
             template: get_default_template_package_embedded(),
         };
@@ -251,7 +295,8 @@ mod test {
         write_fixture_file("package_item_file_b");
 
         task.cleanup(&[CleanupScope::All]).unwrap();
-        task.render_composed_templates(tera).unwrap();
+        task.render_composed_templates(tera, &Context::new())
+            .unwrap();
 
         let content =
             read_to_string(format!("{}/package_urn/full.puml", task.output_directory)).unwrap();
@@ -1,17 +1,22 @@
-use std::fs::File;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use tera::{Context, Tera};
 
 use crate::cmd::library::generate::config::Config;
-use crate::cmd::library::generate::task::{CleanupScope, Task};
+use crate::cmd::library::generate::fingerprint::{
+    compute_fingerprint, is_outdated, write_fingerprint,
+};
+use crate::cmd::library::generate::task::{
+    CleanupScope, OutputKind, Task, PHASE_CLEANUP, PHASE_RENDER_ATOMIC_TEMPLATES,
+    PHASE_RENDER_SOURCES,
+};
 use crate::cmd::library::manifest::example::Example;
 use crate::cmd::library::manifest::library::Library;
 use crate::cmd::library::manifest::package::Package;
 use crate::plantuml::PlantUML;
-use crate::utils::{create_parent_directory, delete_file};
+use crate::utils::{create_parent_directory, delete_file, write_file_atomically};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct PackageExampleTask {
@@ -21,6 +26,8 @@ pub struct PackageExampleTask {
     template: String,
     /// The relative path to the library base path.
     path_to_base: String,
+    /// The extension of the generated sources.
+    source_extension: String,
     /// The name of the Tera template
     full_source_path: String,
     /// The name of the Tera template
@@ -50,6 +57,7 @@ impl PackageExampleTask {
             package_urn: package.urn.value.clone(),
             template: example.template.clone(),
             path_to_base: package.urn.path_to_base.clone(),
+            source_extension: library.rendering.source_extension.clone(),
             full_source_path,
             full_image_path,
         })
@@ -57,6 +65,36 @@ impl PackageExampleTask {
 }
 
 impl Task for PackageExampleTask {
+    fn describe(&self) -> String {
+        format!(
+            "PackageExampleTask({}/{})",
+            self.package_urn, self.full_source_path
+        )
+    }
+
+    fn phases(&self) -> Vec<&'static str> {
+        vec![
+            PHASE_CLEANUP,
+            PHASE_RENDER_ATOMIC_TEMPLATES,
+            PHASE_RENDER_SOURCES,
+        ]
+    }
+
+    fn outputs(&self) -> Vec<PathBuf> {
+        vec![
+            PathBuf::from(&self.full_source_path),
+            PathBuf::from(&self.full_image_path),
+        ]
+    }
+
+    fn output_kind(&self) -> OutputKind {
+        OutputKind::Example
+    }
+
+    fn sources(&self) -> Vec<PathBuf> {
+        vec![PathBuf::from(&self.full_source_path)]
+    }
+
     fn cleanup(&self, _scopes: &[CleanupScope]) -> Result<()> {
         log::debug!("{} - PackageExampleTask - cleanup", self.template);
         if CleanupScope::Example.is_included_in(_scopes) {
@@ -66,31 +104,33 @@ impl Task for PackageExampleTask {
         Ok(())
     }
 
-    fn render_atomic_templates(&self, _tera: &Tera) -> Result<()> {
+    fn render_atomic_templates(&self, _tera: &Tera, _context: &Context) -> Result<()> {
         log::debug!("{} - PackageExampleTask - render templates", self.template);
 
         let destination_path = Path::new(&self.full_source_path);
 
         // skip early when generation not required
-        if destination_path.exists() {
+        let fingerprint = compute_fingerprint(self, _tera, &self.template)?;
+        if !is_outdated(destination_path, &fingerprint) {
             return Ok(());
         }
 
         // create the destination directory
         create_parent_directory(destination_path)?;
 
-        // create the destination file
-        let destination_file = File::create(destination_path).map_err(|e| {
-            anyhow::Error::new(e).context("unable to create the destination file".to_string())
-        })?;
-
-        let mut context = Context::new();
+        let mut context = _context.clone();
         context.insert("data", &self);
-        _tera
-            .render_to(&self.template, &context, destination_file)
-            .map_err(|e| {
-                anyhow::Error::new(e).context(format!("unable to render {}", &self.template))
-            })
+        // the destination file is only created once the rendering is complete
+        write_file_atomically(destination_path, |destination_file| {
+            _tera
+                .render_to(&self.template, &context, destination_file)
+                .map_err(|e| {
+                    anyhow::Error::new(e).context(format!("unable to render {}", &self.template))
+                })
+        })?;
+        // the image is rendered again from the new source
+        delete_file(Path::new(&self.full_image_path))?;
+        write_fingerprint(destination_path, &fingerprint)
     }
 
     fn render_sources(&self, plantuml: &PlantUML) -> Result<()> {
@@ -127,11 +167,14 @@ mod test {
             package_urn: "test".to_string(),
             template: "package_example_test.tera".to_string(),
             path_to_base: "".to_string(),
+            source_extension: "puml".to_string(),
             full_source_path: "target/tests/package_examples/source.puml".to_string(),
             full_image_path: "target/tests/package_examples/source.png".to_string(),
         };
         generator.cleanup(&[CleanupScope::All]).unwrap();
-        generator.render_atomic_templates(tera).unwrap();
+        generator
+            .render_atomic_templates(tera, &Context::new())
+            .unwrap();
         let content = read_to_string("target/tests/package_examples/source.puml").unwrap();
         assert!(content.trim().contains("the content of the example"));
     }
@@ -1,5 +1,7 @@
 #[allow(clippy::needless_raw_string_hashes)]
-pub const TEMPLATE: &str = r##"# {{ data.item_name }}
+pub const TEMPLATE: &str = r##"{% if meta.banner %}<!-- {{ meta.banner }} -->
+
+{% endif %}# {{ data.item_name }}
 {%- block header %}{% endblock header %}
 
 {% block content %}
@@ -12,11 +14,21 @@ include('{{ data.item_urn }}')
 ```
 {% endblock content %}
 
+{% block metadata %}
+{%- if metadata is defined and metadata | length > 0 -%}
+## Metadata
+
+| Key | Value |
+| :--- | :--- |
+{% for key, value in metadata %}| {{ key }} | {{ value }} |
+{% endfor %}
+{% endif -%}
+{% endblock metadata -%}
 {% block objects %}
 {% if data.objects | length > 0 -%}
 {% for object in data.objects %}| {{ object.name }} {% endfor %}|
 {% for object in data.objects %}| :---: {% endfor %}|
-{% for object in data.objects %}| ![illustration for {{ object.name }}]({{ data.path_to_base }}/{{ object.illustration_path }}) {% endfor %}|
+{% for object in data.objects %}| {% if object.retina_illustration_path %}<img src="{{ data.path_to_base }}/{{ object.illustration_path }}" srcset="{{ data.path_to_base }}/{{ object.illustration_path }} 1x, {{ data.path_to_base }}/{{ object.retina_illustration_path }} 2x" alt="illustration for {{ object.name }}"> {% else %}![illustration for {{ object.name }}]({{ data.path_to_base }}/{{ object.illustration_path }}) {% endif %}{% endfor %}|
 {% endif -%}
 {% endblock objects %}
 
@@ -40,15 +52,22 @@ The item provides the following sriptes:
 {% for element in elements %}
 ## {{ element.name }}
 
+{% if element.full_snippet_remote_path -%}
 ### Load remotely
 ```plantuml
 {{ read_file_content(path=element.full_snippet_remote_path) }}
 ```
-
+{% endif %}
+{% if element.full_snippet_local_path -%}
 ### Load locally
 ```plantuml
+{% if snippet_sources is defined -%}
+{{ snippet_sources | get(key=element.name, default="") }}
+{%- else -%}
 {{ read_file_content(path=element.full_snippet_local_path) }}
+{%- endif %}
 ```
+{% endif -%}
 {% endfor %}
 {% endif -%}
 {% endblock elements -%}
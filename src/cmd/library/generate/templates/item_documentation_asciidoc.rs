@@ -0,0 +1,80 @@
+#[allow(clippy::needless_raw_string_hashes)]
+pub const TEMPLATE: &str = r##"{% if meta.banner %}// {{ meta.banner }}
+{% endif %}= {{ data.item_name }}
+{%- block header %}{% endblock header %}
+
+{% block content %}
+----
+{{ data.item_urn }}
+----
+
+----
+include('{{ data.item_urn }}')
+----
+{% endblock content %}
+
+{% block metadata %}
+{%- if metadata is defined and metadata | length > 0 -%}
+== Metadata
+
+|===
+| Key | Value
+
+{% for key, value in metadata %}| {{ key }} | {{ value }}
+{% endfor -%}
+|===
+
+{% endif -%}
+{% endblock metadata -%}
+{% block objects %}
+{% if data.objects | length > 0 -%}
+|===
+{% for object in data.objects %}| {{ object.name }} {% endfor %}
+
+{% for object in data.objects %}| image:{{ data.path_to_base }}/{{ object.illustration_path }}[illustration for {{ object.name }}] {% endfor %}
+|===
+{% endif -%}
+{% endblock objects %}
+
+{% block sprites %}
+{% set icons = data.objects | filter(attribute="type", value="Icon") -%}
+{% if icons | length > 0 -%}
+== Sprites
+The item provides the following sprites:
+{% for icon in icons %}
+* `<${{ data.item_name }}Xs>`
+* `<${{ data.item_name }}Sm>`
+* `<${{ data.item_name }}Md>`
+* `<${{ data.item_name }}Lg>`
+{% endfor %}
+{% endif -%}
+{% endblock sprites %}
+
+{% set elements = data.objects | filter(attribute="type", value="Element") -%}
+{% block elements %}
+{% if elements | length > 0 -%}
+{% for element in elements %}
+== {{ element.name }}
+
+{% if element.full_snippet_remote_path -%}
+=== Load remotely
+[source,plantuml]
+----
+{{ read_file_content(path=element.full_snippet_remote_path) }}
+----
+{% endif %}
+{% if element.full_snippet_local_path -%}
+=== Load locally
+[source,plantuml]
+----
+{% if snippet_sources is defined -%}
+{{ snippet_sources | get(key=element.name, default="") }}
+{%- else -%}
+{{ read_file_content(path=element.full_snippet_local_path) }}
+{%- endif %}
+----
+{% endif -%}
+{% endfor %}
+{% endif -%}
+{% endblock elements -%}
+{% block footer %}{% endblock footer -%}"##;
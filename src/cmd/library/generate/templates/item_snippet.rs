@@ -10,7 +10,7 @@ pub const TEMPLATE: &str = r##"@startuml
 
 {%- block loader %}
 ' loads the library's bootstrap
-!include $LIB_BASE_LOCATION/bootstrap.puml
+!include $LIB_BASE_LOCATION/bootstrap.{{ data.source_extension }}
 
 ' loads the package bootstrap
 include('{{ data.package_urn }}/bootstrap')
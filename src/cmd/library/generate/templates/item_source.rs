@@ -1,5 +1,6 @@
 #[allow(clippy::needless_raw_string_hashes)]
-pub const TEMPLATE: &str = r##"{% block header -%}
+pub const TEMPLATE: &str = r##"{% if meta.banner %}' {{ meta.banner }}
+{% endif %}{% block header -%}
 ' definition of the Item {{ data.item_urn }}
 {% endblock header -%}
 
@@ -1,5 +1,6 @@
 #[allow(clippy::needless_raw_string_hashes)]
-pub const TEMPLATE: &str = r##"{%- block header %}{% endblock header %}
+pub const TEMPLATE: &str = r##"{% if meta.banner %}' {{ meta.banner }}
+{% endif %}{%- block header %}{% endblock header %}
 
 ' by default the inclusion mode is remote
 !if (%not(%variable_exists("$INCLUSION_MODE")))
@@ -22,6 +23,7 @@ pub const TEMPLATE: &str = r##"{%- block header %}{% endblock header %}
 
 ' constants
 {% block constants %}
+!global $LIB_VERSION="{{ data.library_version }}"
 !global $ICON_FORMAT="{{ data.icon_format }}"
 !global $TEXT_WIDTH_MAX={{ data.text_width_max }}
 !global $MSG_WIDTH_MAX={{ data.msg_width_max }}
@@ -77,7 +79,7 @@ skinparam DefaultFontColor $FONT_COLOR
 ' include()
 {% block procedure_include %}
 !procedure include($resource)
-    !include $LIB_BASE_LOCATION/$resource.puml
+    !include $LIB_BASE_LOCATION/$resource.{{ data.source_extension }}
 !endprocedure
 {% endblock procedure_include %}
 
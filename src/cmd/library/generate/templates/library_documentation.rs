@@ -1,7 +1,11 @@
 #[allow(clippy::needless_raw_string_hashes)]
-pub const TEMPLATE: &str = r##"# {{ data.library_name }}
+pub const TEMPLATE: &str = r##"{% if meta.banner %}<!-- {{ meta.banner }} -->
+
+{% endif %}# {{ data.library_name }}
 {%- block header %}{% endblock header %}
 
+The version of the library is `{{ data.library_version }}`.
+
 ## Packages
 
 The library provides {{ data.packages | length }} packages.
@@ -17,7 +21,7 @@ Include remotely the library:
 !global $LIB_BASE_LOCATION="{{ data.remote_url }}"
 
 ' loads the library
-!include $LIB_BASE_LOCATION/bootstrap.puml
+!include $LIB_BASE_LOCATION/bootstrap.{{ data.source_extension }}
 @enduml
 ```
 
@@ -29,7 +33,7 @@ Include locally the library:
 !global $LIB_BASE_LOCATION="<the relative path to the /distribution directory>"
 
 ' loads the library
-!include $LIB_BASE_LOCATION/bootstrap.puml
+!include $LIB_BASE_LOCATION/bootstrap.{{ data.source_extension }}
 @enduml
 ```
 
@@ -57,7 +61,7 @@ Given the following file tree, the following snippets should help you to configu
 ```plantuml
 !global $INCLUSION_MODE="local"
 !global $LIB_BASE_LOCATION="../node_modules/@tmorin/plantuml-libs/distribution"
-!include $LIB_BASE_LOCATION/bootstrap.puml
+!include $LIB_BASE_LOCATION/bootstrap.{{ data.source_extension }}
 include('c4model/bootstrap')
 ' definition of the common elements below
 ' .....
@@ -67,7 +71,7 @@ include('c4model/bootstrap')
 ```plantuml
 !global $INCLUSION_MODE="local"
 !global $LIB_BASE_LOCATION="../node_modules/@tmorin/plantuml-libs/distribution"
-!include $LIB_BASE_LOCATION/bootstrap.puml
+!include $LIB_BASE_LOCATION/bootstrap.{{ data.source_extension }}
 include('aws-20200911/bootstrap')
 ' definition of the common elements below
 ' .....
@@ -0,0 +1,14 @@
+pub const TEMPLATE: &str = r##"
+{%- block header %}{% endblock header -%}
+{%- block content -%}
+.{{ data.library_name }}
+* xref:README.adoc[Presentation]
+{% for package in data.packages %}
+.{{ package.package_urn }}
+* xref:{{ package.package_urn }}/README.adoc[Presentation]
+{%- for module in package.modules %}
+* xref:{{ module.module_urn }}/README.adoc[{{ module.module_urn }}]{% for item in module.items %}
+** xref:{{ item.item_urn }}.adoc[{{ item.item_urn }}]{% endfor %}{% endfor %}
+{% endfor %}
+{% endblock content -%}
+{% block footer %}{% endblock footer -%}"##;
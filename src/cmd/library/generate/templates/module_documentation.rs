@@ -1,5 +1,7 @@
 #[allow(clippy::needless_raw_string_hashes)]
-pub const TEMPLATE: &str = r##"# {{ data.module_name }}
+pub const TEMPLATE: &str = r##"{% if meta.banner %}<!-- {{ meta.banner }} -->
+
+{% endif %}# {{ data.module_name }}
 {%- block header %}{% endblock header %}
 
 {% set nbr_items = data.items_with_family | length + data.items_without_family | length -%}
@@ -1,3 +1,4 @@
-pub const TEMPLATE: &str = r##"{% block header %}{% endblock header %}
+pub const TEMPLATE: &str = r##"{% if meta.banner %}' {{ meta.banner }}
+{% endif %}{% block header %}{% endblock header %}
 {% block content %}{% endblock content %}
 {% block footer %}{% endblock footer %}"##;
@@ -1,5 +1,7 @@
 #[allow(clippy::needless_raw_string_hashes)]
-pub const TEMPLATE: &str = r##"# {{ data.package_name }}
+pub const TEMPLATE: &str = r##"{% if meta.banner %}<!-- {{ meta.banner }} -->
+
+{% endif %}# {{ data.package_name }}
 {% block header %}{% endblock header -%}
 
 {% block bootstrap %}
@@ -31,7 +33,7 @@ Finally, another include can be used to load the library's bootstrap, the packag
 Include remotely the resources:
 ```plantuml
 ' loads the library, the bootstrap of `{{ data.package_urn }}` and all related items
-!include {{ data.remote_url }}/{{ data.package_urn }}/single.puml
+!include {{ data.remote_url }}/{{ data.package_urn }}/single.{{ data.source_extension }}
 ```
 
 Include locally the resources:
@@ -39,7 +41,7 @@ Include locally the resources:
 ' configures the library
 !global $INCLUSION_MODE="local"
 ' loads the library, the bootstrap of `{{ data.package_urn }}` and all related items
-!include <the relative path to the /distribution directory>/{{ data.package_urn }}/single.puml
+!include <the relative path to the /distribution directory>/{{ data.package_urn }}/single.{{ data.source_extension }}
 ```
 {% endif %}
 {% endblock bootstrap %}
@@ -0,0 +1,171 @@
+use std::fmt;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use glob::{glob, Pattern};
+
+/// A local path referenced by a generated artifact but missing in the output directory.
+#[derive(Debug, PartialEq)]
+pub struct DanglingReference {
+    /// The generated artifact, relative to the output directory.
+    pub referring_file: PathBuf,
+    /// The reference as written in the artifact.
+    pub reference: String,
+}
+
+impl fmt::Display for DanglingReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} references {} which doesn't exist",
+            self.referring_file.display(),
+            self.reference
+        )
+    }
+}
+
+/// True when the reference cannot be checked locally, e.g. an URL or a PlantUML variable.
+fn is_remote(reference: &str) -> bool {
+    reference.is_empty()
+        || reference.contains("://")
+        || reference.starts_with(['#', '<', '$'])
+        || reference.starts_with("mailto:")
+}
+
+/// The targets of the Markdown links and images, the code blocks are skipped.
+fn parse_markdown_references(content: &str) -> Vec<String> {
+    let mut references = vec![];
+    let mut is_in_code_block = false;
+    for line in content.lines() {
+        if line.trim_start().starts_with("```") {
+            is_in_code_block = !is_in_code_block;
+            continue;
+        }
+        if is_in_code_block {
+            continue;
+        }
+        for (index, _) in line.match_indices("](") {
+            // e.g. `](path "title")` or `](path#anchor)`
+            let target = line[index + 2..].split(')').next().unwrap_or_default();
+            let target = target.split_whitespace().next().unwrap_or_default();
+            let target = target.split('#').next().unwrap_or_default();
+            if !is_remote(target) {
+                references.push(target.to_string());
+            }
+        }
+    }
+    references
+}
+
+/// The files included by a PlantUML source, `$LIB_BASE_LOCATION` is resolved in the local mode.
+fn parse_plantuml_references(content: &str, source_extension: &str) -> Vec<String> {
+    let is_local = content.contains("!global $INCLUSION_MODE=\"local\"");
+    let base_location = content.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix("!global $LIB_BASE_LOCATION=\"")
+            .and_then(|v| v.strip_suffix('"'))
+    });
+    let base_location = base_location.filter(|_| is_local);
+    let mut references = vec![];
+    for line in content.lines().map(|line| line.trim()) {
+        let reference = if let Some(path) = line.strip_prefix("!include ") {
+            let path = path.trim();
+            match (path.strip_prefix("$LIB_BASE_LOCATION/"), base_location) {
+                (Some(path), Some(base_location)) => format!("{}/{}", base_location, path),
+                _ => path.to_string(),
+            }
+        } else if let Some(resource) = line
+            .strip_prefix("include('")
+            .and_then(|v| v.strip_suffix("')"))
+        {
+            match base_location {
+                Some(base_location) => {
+                    format!("{}/{}.{}", base_location, resource, source_extension)
+                }
+                None => continue,
+            }
+        } else {
+            continue;
+        };
+        // e.g. `$LIB_BASE_LOCATION/$resource.puml` in the procedure include()
+        if !is_remote(&reference) && !reference.contains('$') {
+            references.push(reference);
+        }
+    }
+    references
+}
+
+/// Find the local paths referenced by the generated documentation and sources which don't exist.
+pub fn find_dangling_references(
+    output_directory: &Path,
+    source_extension: &str,
+) -> Result<Vec<DanglingReference>> {
+    let glob_pattern = format!(
+        "{}/**/*",
+        Pattern::escape(&output_directory.to_string_lossy())
+    );
+    let paths = glob(&glob_pattern).map_err(|e| {
+        anyhow::Error::new(e).context(format!(
+            "unable to parse the glob pattern ({})",
+            &glob_pattern
+        ))
+    })?;
+    let mut dangling_references = vec![];
+    for path in paths {
+        let path = path.map_err(anyhow::Error::new)?;
+        let extension = path
+            .extension()
+            .and_then(|v| v.to_str())
+            .unwrap_or_default();
+        let is_markdown = extension == "md";
+        if !is_markdown && extension != "puml" && extension != source_extension {
+            continue;
+        }
+        let content = read_to_string(&path).map_err(|e| {
+            anyhow::Error::new(e).context(format!("unable to read {}", path.display()))
+        })?;
+        let references = match is_markdown {
+            true => parse_markdown_references(&content),
+            false => parse_plantuml_references(&content, source_extension),
+        };
+        let directory = path.parent().unwrap_or(output_directory);
+        for reference in references {
+            if !directory.join(&reference).exists() {
+                dangling_references.push(DanglingReference {
+                    referring_file: path
+                        .strip_prefix(output_directory)
+                        .unwrap_or(&path)
+                        .to_path_buf(),
+                    reference,
+                });
+            }
+        }
+    }
+    Ok(dangling_references)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_markdown_references() {
+        let content = "![illustration](../Item.png) [Item](Item.md#title \"Item\") [Site](https://host/a.png)\n```plantuml\n![ignored](a.png)\n```\n[anchor](#title)";
+        assert_eq!(
+            parse_markdown_references(content),
+            vec!["../Item.png", "Item.md"]
+        );
+    }
+
+    #[test]
+    fn test_parse_plantuml_references() {
+        let content = "!global $INCLUSION_MODE=\"local\"\n!global $LIB_BASE_LOCATION=\"../..\"\n!include $LIB_BASE_LOCATION/bootstrap.iuml\ninclude('Package/bootstrap')\n!include https://host/a.puml\n    !include $LIB_BASE_LOCATION/$resource.iuml";
+        assert_eq!(
+            parse_plantuml_references(content, "iuml"),
+            vec!["../../bootstrap.iuml", "../../Package/bootstrap.iuml"]
+        );
+        let content = "!global $LIB_BASE_LOCATION=\"https://host\"\n!include $LIB_BASE_LOCATION/bootstrap.puml\ninclude('Package/bootstrap')";
+        assert!(parse_plantuml_references(content, "puml").is_empty());
+    }
+}
@@ -0,0 +1,119 @@
+use std::path::Path;
+
+use anyhow::Result;
+use clap::ArgMatches;
+use serde::Serialize;
+
+use crate::cmd::library::manifest::library::Library;
+
+#[derive(Debug, Serialize)]
+struct Entry {
+    /// The type of the URN, i.e. package, module or item.
+    #[serde(rename = "type")]
+    urn_type: String,
+    /// The value of the URN.
+    urn: String,
+}
+
+/// The URNs of the library in the manifest order, optionally filtered by type.
+fn create_entries(library: &Library, urn_type: Option<&str>) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = vec![];
+    let mut push = |entry_type: &str, urn: &str| {
+        if urn_type.is_none_or(|t| t == entry_type) {
+            entries.push(Entry {
+                urn_type: entry_type.to_string(),
+                urn: urn.to_string(),
+            });
+        }
+    };
+    for package in &library.packages {
+        push("package", &package.urn.value);
+        for module in &package.modules {
+            push("module", &module.urn.value);
+            for item in &module.items {
+                push("item", &item.urn.value);
+            }
+        }
+    }
+    entries
+}
+
+pub fn execute_library_list_urns(arg_matches: &ArgMatches) -> Result<()> {
+    // resolve the manifest path
+    let manifest_file = arg_matches
+        .get_one::<String>("MANIFEST")
+        .ok_or_else(|| anyhow::Error::msg("MANIFEST is required".to_string()))?;
+
+    // parse the manifest
+    let library = &Library::load(Path::new(manifest_file))?;
+
+    let entries = create_entries(
+        library,
+        arg_matches
+            .get_one::<String>("urn_type")
+            .map(|v| v.as_str()),
+    );
+    match arg_matches
+        .get_one::<String>("urns_format")
+        .map(|v| v.as_str())
+    {
+        Some("json") => {
+            let content = serde_json::to_string_pretty(&entries)
+                .map_err(|e| anyhow::Error::new(e).context("unable to serialize the URNs"))?;
+            println!("{}", content);
+        }
+        _ => {
+            for entry in entries {
+                println!("{}", entry.urn);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cli::build_cli;
+
+    use super::*;
+
+    #[test]
+    fn test_list_urns() {
+        let library = &Library::load(Path::new("test/library-simple.yaml")).unwrap();
+        let items: Vec<String> = create_entries(library, Some("item"))
+            .into_iter()
+            .map(|e| e.urn)
+            .collect();
+        assert_eq!(
+            items,
+            vec![
+                "c4model/Element/Person",
+                "eventstorming/Element/Message/FacadeCommand",
+            ]
+        );
+        let entries = create_entries(library, None);
+        assert_eq!(entries[0].urn_type, "package");
+        assert_eq!(entries[0].urn, "c4model");
+        assert_eq!(entries[1].urn_type, "module");
+        assert_eq!(entries[1].urn, "c4model/Element");
+
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "library",
+            "list-urns",
+            "test/library-simple.yaml",
+            "--type=item",
+            "--format=json",
+        ]);
+        execute_library_list_urns(
+            arg_matches
+                .subcommand_matches("library")
+                .unwrap()
+                .subcommand_matches("list-urns")
+                .unwrap(),
+        )
+        .unwrap();
+    }
+}
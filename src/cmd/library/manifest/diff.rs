@@ -0,0 +1,173 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::cmd::library::manifest::library::Library;
+use crate::urn::Urn;
+
+/// The content of a node of the manifest without its children, e.g. a package without its modules.
+fn get_own_content<T: Serialize>(node: &T, children: &str) -> Value {
+    let mut value = serde_json::to_value(node).unwrap_or(Value::Null);
+    if let Value::Object(map) = &mut value {
+        map.remove(children);
+    }
+    value
+}
+
+/// The URNs to generate again since the old manifest, none when the whole library has changed.
+///
+/// A changed or removed child makes its parent generated again, i.e. along with its other children.
+pub fn diff_libraries(old_library: &Library, new_library: &Library) -> Option<Vec<Urn>> {
+    // e.g. the customization impacts all the artifacts
+    let is_library_changed = get_own_content(old_library, "packages")
+        != get_own_content(new_library, "packages")
+        || old_library.packages.iter().any(|p| {
+            !new_library
+                .packages
+                .iter()
+                .any(|n| n.urn.value == p.urn.value)
+        });
+    if is_library_changed {
+        return None;
+    }
+    let mut urns: Vec<Urn> = vec![];
+    for package in &new_library.packages {
+        let old_package = match old_library
+            .packages
+            .iter()
+            .find(|p| p.urn.value == package.urn.value)
+        {
+            Some(old_package) => old_package,
+            None => {
+                urns.push(package.urn.clone());
+                continue;
+            }
+        };
+        let is_package_changed = get_own_content(old_package, "modules")
+            != get_own_content(package, "modules")
+            || old_package
+                .modules
+                .iter()
+                .any(|m| !package.modules.iter().any(|n| n.urn.value == m.urn.value));
+        if is_package_changed {
+            urns.push(package.urn.clone());
+            continue;
+        }
+        for module in &package.modules {
+            let old_module = match old_package
+                .modules
+                .iter()
+                .find(|m| m.urn.value == module.urn.value)
+            {
+                Some(old_module) => old_module,
+                None => {
+                    urns.push(module.urn.clone());
+                    continue;
+                }
+            };
+            let is_module_changed = get_own_content(old_module, "items")
+                != get_own_content(module, "items")
+                || old_module
+                    .items
+                    .iter()
+                    .any(|i| !module.items.iter().any(|n| n.urn.value == i.urn.value));
+            if is_module_changed {
+                urns.push(module.urn.clone());
+                continue;
+            }
+            for item in &module.items {
+                let is_item_changed = old_module
+                    .items
+                    .iter()
+                    .find(|i| i.urn.value == item.urn.value)
+                    .is_none_or(|old_item| {
+                        get_own_content(old_item, "") != get_own_content(item, "")
+                    });
+                if is_item_changed {
+                    urns.push(item.urn.clone());
+                }
+            }
+        }
+    }
+    Some(urns)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(yaml: &str) -> Library {
+        serde_yaml_ok::from_str(yaml).unwrap()
+    }
+
+    fn to_values(urns: Option<Vec<Urn>>) -> Option<Vec<String>> {
+        urns.map(|urns| urns.into_iter().map(|urn| urn.value).collect())
+    }
+
+    const LIBRARY: &str = r#"
+        name: library
+        remote_url: http://localhost:3000/distribution
+        packages:
+          - urn: PackageA
+            modules:
+              - urn: PackageA/ModuleA
+                items:
+                  - urn: PackageA/ModuleA/ItemA
+                  - urn: PackageA/ModuleA/ItemB
+              - urn: PackageA/ModuleB
+    "#;
+
+    #[test]
+    fn test_unchanged() {
+        assert_eq!(
+            to_values(diff_libraries(&parse(LIBRARY), &parse(LIBRARY))),
+            Some(vec![])
+        );
+    }
+
+    #[test]
+    fn test_changed_item() {
+        let new_library = LIBRARY.replace(
+            "- urn: PackageA/ModuleA/ItemB",
+            "- urn: PackageA/ModuleA/ItemB\n                    family: FamilyA",
+        );
+        assert_eq!(
+            to_values(diff_libraries(&parse(LIBRARY), &parse(&new_library))),
+            Some(vec!["PackageA/ModuleA/ItemB".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_added_and_removed() {
+        let new_library = LIBRARY.replace(
+            "- urn: PackageA/ModuleB",
+            "- urn: PackageA/ModuleC\n          - urn: PackageB",
+        );
+        assert_eq!(
+            to_values(diff_libraries(&parse(LIBRARY), &parse(&new_library))),
+            Some(vec!["PackageA".to_string(), "PackageB".to_string()])
+        );
+        let new_library = LIBRARY.replace("- urn: PackageA/ModuleA/ItemB", "");
+        assert_eq!(
+            to_values(diff_libraries(&parse(LIBRARY), &parse(&new_library))),
+            Some(vec!["PackageA/ModuleA".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_removed_package() {
+        let library = format!("{}\n          - urn: PackageB\n", LIBRARY.trim_end());
+        assert_eq!(
+            to_values(diff_libraries(&parse(&library), &parse(LIBRARY))),
+            None
+        );
+    }
+
+    #[test]
+    fn test_changed_library() {
+        let new_library = LIBRARY.replace("name: library", "name: other");
+        assert_eq!(
+            to_values(diff_libraries(&parse(LIBRARY), &parse(&new_library))),
+            None
+        );
+    }
+}
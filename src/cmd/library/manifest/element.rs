@@ -62,6 +62,15 @@ impl Shape {
             Shape::Custom { .. } => "Custom",
         })
     }
+    pub fn get_properties(&self) -> &HashMap<String, Value> {
+        match self {
+            Shape::Icon { properties, .. } => properties,
+            Shape::IconCard { properties, .. } => properties,
+            Shape::IconGroup { properties, .. } => properties,
+            Shape::Group { properties, .. } => properties,
+            Shape::Custom { properties } => properties,
+        }
+    }
     pub fn get_element_name(&self, item_urn: &Urn) -> String {
         match self {
             Shape::Icon { .. } => item_urn.name.to_string(),
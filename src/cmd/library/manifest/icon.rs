@@ -24,6 +24,13 @@ impl Icon {
             Icon::Reference { urn } => format!("{}.{}", urn.value, icon_format),
         }
     }
+    /// The path of an icon variant for the high-density displays, e.g. `Item@2x.png`.
+    pub fn get_scaled_icon_path(&self, item_urn: &Urn, icon_format: &str, scale: u32) -> String {
+        match &self {
+            Icon::Source { .. } => format!("{}@{}x.{}", item_urn.value, scale, icon_format),
+            Icon::Reference { urn } => format!("{}@{}x.{}", urn.value, scale, icon_format),
+        }
+    }
     pub fn get_sprite_name(&self, urn: &Urn, size: &str) -> String {
         match &self {
             Icon::Source { .. } => format!("{}{}", urn.name, size.to_upper_camel_case()),
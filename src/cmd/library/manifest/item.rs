@@ -1,5 +1,8 @@
+use std::collections::HashMap;
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use crate::cmd::library::manifest::element::Element;
 use crate::cmd::library::manifest::icon::Icon;
@@ -43,6 +46,9 @@ mod templates {
 pub struct Item {
     /// The URN of the Item.
     pub urn: Urn,
+    /// A short name which can be used instead of the URN.
+    #[serde(default)]
+    pub alias: Option<String>,
     /// The family of the Item.
     #[serde(default)]
     pub family: Option<String>,
@@ -55,6 +61,9 @@ pub struct Item {
     /// The definition of the templates.
     #[serde(default)]
     pub templates: ItemTemplates,
+    /// Free-form values given to the documentation templates, e.g. tags or an owner.
+    #[serde(default)]
+    pub metadata: HashMap<String, Value>,
 }
 
 #[cfg(test)]
@@ -65,12 +74,14 @@ mod tests {
     fn test_deserialized() {
         let yaml = r#"
             urn: item_urn
+            alias: item_alias
             family: item_family
             templates:
                 snippet: item_templates_snippet
         "#;
         let item: Item = serde_yaml_ok::from_str(yaml).unwrap();
         assert_eq!(item.urn.value, "item_urn");
+        assert_eq!(item.alias.unwrap(), "item_alias");
         assert_eq!(item.family.unwrap(), "item_family");
         assert!(item.elements.is_empty());
         assert!(!item.templates.source.is_empty());
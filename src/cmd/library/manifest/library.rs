@@ -1,11 +1,24 @@
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::cmd::library::manifest::element::Shape;
 use crate::cmd::library::manifest::library::customization::Customization;
+use crate::cmd::library::manifest::library::rendering::LibraryRendering;
 use crate::cmd::library::manifest::library::templates::LibraryTemplates;
 use crate::cmd::library::manifest::package::Package;
+use crate::constants::get_default_library_version;
+use crate::urn::Urn;
 
 pub mod customization {
+    use std::collections::HashMap;
+    use std::str::FromStr;
+
+    use image::imageops::FilterType;
+
     use schemars::JsonSchema;
     use serde::{Deserialize, Serialize};
 
@@ -17,7 +30,10 @@ pub mod customization {
     use crate::constants::get_default_icon_format;
     use crate::constants::get_default_icon_height;
     use crate::constants::get_default_msg_width_max;
+    use crate::constants::get_default_sprite_background;
     use crate::constants::get_default_text_width_max;
+    use crate::constants::ICON_FORMATS;
+    use crate::constants::SPRITE_BACKGROUND_TRANSPARENT;
     use crate::constants::{get_default_font_color, SPRITE_LG, SPRITE_MD, SPRITE_SM, SPRITE_XS};
 
     #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -52,9 +68,42 @@ pub mod customization {
         /// A lighter font color.
         #[serde(default = "get_default_font_color_light")]
         pub font_color_light: String,
+        /// The background of the sprites, a hex color like `#FFFFFF` or `transparent`.
+        #[serde(default = "get_default_sprite_background")]
+        pub sprite_background: String,
+        /// When true, the icons are also generated at the double of their height, e.g. `Item@2x.png`.
+        #[serde(default)]
+        pub retina_icons: bool,
+        /// The filter resizing the raster icons, e.g. `lanczos3` for crisper downscales.
+        #[serde(default)]
+        pub resize_filter: ResizeFilter,
+        /// The expected keys of the properties by shape, e.g. `Icon: [color]`.
+        ///
+        /// The shapes without expected keys, like the `Custom` ones, are not checked.
+        #[serde(default)]
+        pub shape_properties: HashMap<String, Vec<String>>,
     }
 
     impl Customization {
+        /// Check the values which cannot be checked by the parsing of the manifest.
+        pub fn validate(&self) -> anyhow::Result<()> {
+            if !ICON_FORMATS.contains(&self.icon_format.as_str()) {
+                return Err(anyhow::Error::msg(format!(
+                    "the icon format {} is not supported, the supported formats are: {}",
+                    self.icon_format,
+                    ICON_FORMATS.join(", ")
+                )));
+            }
+            if self.sprite_background != SPRITE_BACKGROUND_TRANSPARENT
+                && raster::Color::hex(&self.sprite_background).is_err()
+            {
+                return Err(anyhow::Error::msg(format!(
+                    "the sprite background {} is neither a hex color nor {}",
+                    self.sprite_background, SPRITE_BACKGROUND_TRANSPARENT
+                )));
+            }
+            Ok(())
+        }
         pub fn list_sprite_sizes(&self) -> Vec<(&str, u32)> {
             vec![
                 (SPRITE_XS, self.font_size_xs),
@@ -78,6 +127,92 @@ pub mod customization {
                 font_size_lg: get_default_font_size_lg(),
                 font_color: get_default_font_color(),
                 font_color_light: get_default_font_color_light(),
+                sprite_background: get_default_sprite_background(),
+                retina_icons: false,
+                resize_filter: ResizeFilter::default(),
+                shape_properties: HashMap::new(),
+            }
+        }
+    }
+
+    /// The filters resizing the raster icons, from the fastest to the sharpest.
+    #[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, Eq, PartialEq, JsonSchema)]
+    #[serde(rename_all = "lowercase")]
+    pub enum ResizeFilter {
+        Nearest,
+        #[default]
+        Triangle,
+        CatmullRom,
+        Gaussian,
+        Lanczos3,
+    }
+
+    impl FromStr for ResizeFilter {
+        type Err = anyhow::Error;
+
+        fn from_str(s: &str) -> anyhow::Result<Self> {
+            match s {
+                "nearest" => Ok(ResizeFilter::Nearest),
+                "triangle" => Ok(ResizeFilter::Triangle),
+                "catmullrom" => Ok(ResizeFilter::CatmullRom),
+                "gaussian" => Ok(ResizeFilter::Gaussian),
+                "lanczos3" => Ok(ResizeFilter::Lanczos3),
+                _ => Err(anyhow::Error::msg(format!(
+                    "unable to find a resize filter for {}",
+                    s
+                ))),
+            }
+        }
+    }
+
+    impl From<ResizeFilter> for FilterType {
+        fn from(resize_filter: ResizeFilter) -> Self {
+            match resize_filter {
+                ResizeFilter::Nearest => FilterType::Nearest,
+                ResizeFilter::Triangle => FilterType::Triangle,
+                ResizeFilter::CatmullRom => FilterType::CatmullRom,
+                ResizeFilter::Gaussian => FilterType::Gaussian,
+                ResizeFilter::Lanczos3 => FilterType::Lanczos3,
+            }
+        }
+    }
+}
+
+pub mod rendering {
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+
+    use crate::constants::get_default_source_extension;
+
+    #[derive(Serialize, Deserialize, Debug, JsonSchema)]
+    pub struct LibraryRendering {
+        /// The extension of the generated sources, e.g. `iuml` for `<library>/bootstrap.iuml`.
+        #[serde(default = "get_default_source_extension")]
+        pub source_extension: String,
+    }
+
+    impl LibraryRendering {
+        /// Check the values which cannot be checked by the parsing of the manifest.
+        pub fn validate(&self) -> anyhow::Result<()> {
+            let is_valid = !self.source_extension.is_empty()
+                && self
+                    .source_extension
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            if !is_valid {
+                return Err(anyhow::Error::msg(format!(
+                    "the source extension `{}` must be made of alphanumeric characters, e.g. puml",
+                    self.source_extension
+                )));
+            }
+            Ok(())
+        }
+    }
+
+    impl Default for LibraryRendering {
+        fn default() -> Self {
+            LibraryRendering {
+                source_extension: get_default_source_extension(),
             }
         }
     }
@@ -116,12 +251,41 @@ mod templates {
     }
 }
 
+/// The formats of the library manifest.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ManifestFormat {
+    #[default]
+    Yaml,
+    Json,
+}
+
+impl FromStr for ManifestFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "yaml" => Ok(ManifestFormat::Yaml),
+            "json" => Ok(ManifestFormat::Json),
+            _ => Err(anyhow::Error::msg(format!(
+                "unable to find a manifest format for {}",
+                s
+            ))),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 pub struct Library {
+    /// The path to a base manifest, relative to this one, the values of this one are merged over it.
+    #[serde(default)]
+    pub extends: Option<String>,
     /// The name of the library.
     pub name: String,
     /// The URL used to fetched the library remotely.
     pub remote_url: String,
+    /// The version of the library, printed in the generated artifacts.
+    #[serde(default = "get_default_library_version")]
+    pub version: String,
     /// The packages provided by the library.
     #[serde(default)]
     pub packages: Vec<Package>,
@@ -131,11 +295,229 @@ pub struct Library {
     /// The configuration of he library.
     #[serde(default)]
     pub customization: Customization,
+    /// The customization of the rendered resources.
+    #[serde(default)]
+    pub rendering: LibraryRendering,
     /// An optional tera directory.
     #[serde(default)]
     pub tera_discovery_pattern: Option<String>,
 }
 
+/// The reference to the base of a manifest, the other values are ignored.
+#[derive(Deserialize)]
+struct ManifestBase {
+    #[serde(default)]
+    extends: Option<String>,
+}
+
+/// True when the manifest extends another one, the invalid manifests are reported by their parsing.
+fn is_extending(content: &str, format: ManifestFormat) -> bool {
+    let base: Option<ManifestBase> = match format {
+        ManifestFormat::Yaml => serde_yaml_ok::from_str(content).ok(),
+        ManifestFormat::Json => serde_json::from_str(content).ok(),
+    };
+    base.is_some_and(|base| base.extends.is_some())
+}
+
+/// The content of a manifest as a generic value, e.g. to merge it over its base.
+fn read_value(manifest_path: &Path, format: ManifestFormat) -> anyhow::Result<serde_json::Value> {
+    let content = &read_to_string(manifest_path).map_err(|e| {
+        anyhow::Error::new(e).context(format!("unable to read {}", manifest_path.display()))
+    })?;
+    match format {
+        ManifestFormat::Yaml => serde_yaml_ok::from_str(content).map_err(anyhow::Error::new),
+        ManifestFormat::Json => serde_json::from_str(content).map_err(anyhow::Error::new),
+    }
+    .map_err(|e| e.context(format!("unable to parse {}", manifest_path.display())))
+}
+
+/// True when the list is made of nodes identified by their URNs, e.g. the packages.
+fn is_urn_list(values: &[serde_json::Value]) -> bool {
+    values.iter().all(|value| value.get("urn").is_some())
+}
+
+/// Merge a value over a base one: the mappings are merged, the nodes having a URN override the
+/// ones of the base having the same URN, the other values override the base ones.
+fn merge_values(base: serde_json::Value, value: serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+    match (base, value) {
+        (Value::Object(mut base), Value::Object(value)) => {
+            for (key, value) in value {
+                let merged = match base.remove(&key) {
+                    Some(base_value) => merge_values(base_value, value),
+                    None => value,
+                };
+                base.insert(key, merged);
+            }
+            Value::Object(base)
+        }
+        (Value::Array(mut base), Value::Array(values))
+            if is_urn_list(&base) && is_urn_list(&values) =>
+        {
+            for value in values {
+                match base.iter().position(|b| b.get("urn") == value.get("urn")) {
+                    Some(index) => base[index] = value,
+                    None => base.push(value),
+                }
+            }
+            Value::Array(base)
+        }
+        (_, value) => value,
+    }
+}
+
+/// Read a manifest merged over the manifests it extends, the chain of the manifests prevents the cycles.
+fn resolve_extends(
+    manifest_path: &Path,
+    format: ManifestFormat,
+    chain: &mut Vec<PathBuf>,
+) -> anyhow::Result<serde_json::Value> {
+    let canonical_path = manifest_path.canonicalize().map_err(|e| {
+        anyhow::Error::new(e).context(format!("unable to resolve {}", manifest_path.display()))
+    })?;
+    if let Some(position) = chain.iter().position(|path| path == &canonical_path) {
+        return Err(anyhow::Error::msg(format!(
+            "the manifests extend each other: {}",
+            chain[position..]
+                .iter()
+                .chain([&canonical_path])
+                .map(|path| path.display().to_string())
+                .collect::<Vec<String>>()
+                .join(" -> ")
+        )));
+    }
+    let value = read_value(manifest_path, format)?;
+    let base_path = match value.get("extends").and_then(|v| v.as_str()) {
+        None => return Ok(value),
+        Some(extends) => manifest_path
+            .parent()
+            .unwrap_or(Path::new(""))
+            .join(extends),
+    };
+    chain.push(canonical_path);
+    let base = resolve_extends(&base_path, format, chain)?;
+    chain.pop();
+    Ok(merge_values(base, value))
+}
+
+impl Library {
+    /// Read and parse the manifest of a library, the unknown keys are only reported.
+    pub fn load(manifest_path: &Path) -> anyhow::Result<Library> {
+        Library::load_with_strictness(manifest_path, false)
+    }
+    /// Read and parse the manifest of a library, the unknown keys are rejected when strict.
+    ///
+    /// The unknown keys are usually typos, e.g. `custmization`, which are silently ignored otherwise.
+    pub fn load_with_strictness(manifest_path: &Path, strict: bool) -> anyhow::Result<Library> {
+        Library::load_with_format(manifest_path, ManifestFormat::Yaml, strict)
+    }
+    /// Read and parse the manifest of a library written in the given format.
+    pub fn load_with_format(
+        manifest_path: &Path,
+        format: ManifestFormat,
+        strict: bool,
+    ) -> anyhow::Result<Library> {
+        let content = &read_to_string(manifest_path).map_err(|e| {
+            anyhow::Error::new(e).context(format!("unable to read {}", manifest_path.display()))
+        })?;
+        let mut unknown_keys: Vec<String> = vec![];
+        let on_unknown_key = |path: serde_ignored::Path| unknown_keys.push(path.to_string());
+        // the manifest is merged over its base before being parsed, e.g. for the required values
+        let library: Library = match (is_extending(content, format), format) {
+            (true, _) => serde_ignored::deserialize(
+                resolve_extends(manifest_path, format, &mut vec![])?,
+                on_unknown_key,
+            )
+            .map_err(anyhow::Error::new),
+            (false, ManifestFormat::Yaml) => serde_ignored::deserialize(
+                serde_yaml_ok::Deserializer::from_str(content),
+                on_unknown_key,
+            )
+            .map_err(anyhow::Error::new),
+            (false, ManifestFormat::Json) => serde_ignored::deserialize(
+                &mut serde_json::Deserializer::from_str(content),
+                on_unknown_key,
+            )
+            .map_err(anyhow::Error::new),
+        }
+        .map_err(|e| e.context(format!("unable to parse {}", manifest_path.display())))?;
+        if !unknown_keys.is_empty() {
+            let message = format!(
+                "the manifest {} contains unknown keys: {}",
+                manifest_path.display(),
+                unknown_keys.join(", ")
+            );
+            if strict {
+                return Err(anyhow::Error::msg(message));
+            }
+            log::warn!("{}", message);
+        }
+        Ok(library)
+    }
+    /// The elements having properties which are not expected for their shape, e.g. a typo.
+    pub fn find_unexpected_properties(&self) -> Vec<String> {
+        let mut problems: Vec<String> = vec![];
+        for package in &self.packages {
+            for module in &package.modules {
+                for item in &module.items {
+                    for element in &item.elements {
+                        let shape_name = element.shape.get_name();
+                        let expected_keys = match (
+                            &element.shape,
+                            self.customization.shape_properties.get(&shape_name),
+                        ) {
+                            (Shape::Custom { .. }, _) | (_, None) => continue,
+                            (_, Some(expected_keys)) => expected_keys,
+                        };
+                        let mut unexpected_keys = element
+                            .shape
+                            .get_properties()
+                            .keys()
+                            .filter(|key| !expected_keys.contains(key))
+                            .map(|key| key.as_str())
+                            .collect::<Vec<&str>>();
+                        if !unexpected_keys.is_empty() {
+                            unexpected_keys.sort();
+                            problems.push(format!(
+                                "{} - the {} shape has unexpected properties: {}",
+                                item.urn,
+                                shape_name,
+                                unexpected_keys.join(", ")
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        problems
+    }
+    /// Resolve a value given by the user to a URN, the value can be an alias or a URN.
+    pub fn resolve_urn(&self, value: &str) -> anyhow::Result<Urn> {
+        let mut package_urns: Vec<&Urn> = vec![];
+        for package in &self.packages {
+            if package.alias.as_deref() == Some(value) {
+                return Ok(package.urn.clone());
+            }
+            for module in &package.modules {
+                for item in &module.items {
+                    if item.alias.as_deref() == Some(value) {
+                        return Ok(item.urn.clone());
+                    }
+                }
+            }
+            package_urns.push(&package.urn);
+        }
+        // a value without separator is either a package or an unknown alias
+        if value.contains('/') || package_urns.iter().any(|urn| urn.value == value) {
+            return Urn::parse(value);
+        }
+        Err(anyhow::Error::msg(format!(
+            "unable to resolve `{}`, it is neither a known alias nor a package URN",
+            value
+        )))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -150,6 +532,7 @@ mod tests {
         "#;
         let library: Library = serde_yaml_ok::from_str(yaml).unwrap();
         assert_eq!(library.name, "testlib");
+        assert_eq!(library.version, "0.0.0");
         assert!(library.packages.is_empty());
         assert_eq!(library.templates.bootstrap, "library_bootstrap.tera");
         assert_eq!(
@@ -160,6 +543,200 @@ mod tests {
         assert_eq!(library.customization.font_size_xs, 10);
     }
 
+    #[test]
+    fn test_load_unknown_keys() {
+        let directory = Path::new("target/tests/manifest/unknown_keys");
+        std::fs::create_dir_all(directory).unwrap();
+        let manifest_path = directory.join("library.yaml");
+        std::fs::write(
+            &manifest_path,
+            "name: testlib\nremote_url: testlib.local:3000/distribution\ncustmization:\n  icon_format: svg\n",
+        )
+        .unwrap();
+        let library = Library::load(&manifest_path).unwrap();
+        assert_eq!(library.customization.icon_format, "png");
+        let error = Library::load_with_strictness(&manifest_path, true)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("unknown keys: custmization"));
+    }
+
+    #[test]
+    fn test_load_extends() {
+        let directory = Path::new("target/tests/manifest/extends");
+        std::fs::create_dir_all(directory.join("base")).unwrap();
+        std::fs::write(
+            directory.join("base/library.yaml"),
+            r#"
+            name: base
+            remote_url: base.local:3000/distribution
+            customization:
+              icon_format: svg
+              icon_height: 64
+            packages:
+              - urn: PackageA
+              - urn: PackageB
+                modules:
+                  - urn: PackageB/ModuleB
+        "#,
+        )
+        .unwrap();
+        let manifest_path = directory.join("library.yaml");
+        std::fs::write(
+            &manifest_path,
+            r#"
+            extends: base/library.yaml
+            name: child
+            customization:
+              icon_height: 32
+            packages:
+              - urn: PackageB
+              - urn: PackageC
+        "#,
+        )
+        .unwrap();
+        let library = Library::load_with_strictness(&manifest_path, true).unwrap();
+        assert_eq!(library.name, "child");
+        assert_eq!(library.remote_url, "base.local:3000/distribution");
+        assert_eq!(library.customization.icon_format, "svg");
+        assert_eq!(library.customization.icon_height, 32);
+        assert_eq!(
+            library
+                .packages
+                .iter()
+                .map(|package| package.urn.value.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["PackageA", "PackageB", "PackageC"]
+        );
+        // the package of the base is overridden by the one having the same URN
+        assert!(library.packages[1].modules.is_empty());
+    }
+
+    #[test]
+    fn test_load_extends_cycle() {
+        let directory = Path::new("target/tests/manifest/extends_cycle");
+        std::fs::create_dir_all(directory).unwrap();
+        std::fs::write(
+            directory.join("a.yaml"),
+            "extends: b.yaml\nname: a\nremote_url: distribution\n",
+        )
+        .unwrap();
+        std::fs::write(
+            directory.join("b.yaml"),
+            "extends: a.yaml\nname: b\nremote_url: distribution\n",
+        )
+        .unwrap();
+        let error = Library::load(&directory.join("a.yaml"))
+            .unwrap_err()
+            .to_string();
+        assert!(error.starts_with("the manifests extend each other:"));
+        assert!(error.ends_with("a.yaml"));
+    }
+
+    #[test]
+    fn test_load_json() {
+        let directory = Path::new("target/tests/manifest/json");
+        std::fs::create_dir_all(directory).unwrap();
+        let manifest_path = directory.join("library.json");
+        std::fs::write(
+            &manifest_path,
+            r#"{"name": "testlib", "remote_url": "testlib.local:3000/distribution", "packages": [{"urn": "PackageA"}]}"#,
+        )
+        .unwrap();
+        let library =
+            Library::load_with_format(&manifest_path, ManifestFormat::Json, true).unwrap();
+        assert_eq!(library.name, "testlib");
+        assert_eq!(library.packages[0].urn.value, "PackageA");
+        assert!(Library::load_with_format(&manifest_path, ManifestFormat::Yaml, true).is_ok());
+        std::fs::write(&manifest_path, "name: testlib").unwrap();
+        assert!(Library::load_with_format(&manifest_path, ManifestFormat::Json, false).is_err());
+    }
+
+    #[test]
+    fn test_unexpected_properties() {
+        let yaml = r#"
+            name: testlib
+            remote_url: testlib.local:3000/distribution
+            customization:
+              shape_properties:
+                Icon: [color]
+            packages:
+              - urn: PackageA
+                modules:
+                  - urn: PackageA/ModuleA
+                    items:
+                      - urn: PackageA/ModuleA/ItemA
+                        elements:
+                          - shape:
+                              type: Icon
+                              properties:
+                                color: red
+                                colr: red
+                          - shape:
+                              type: Group
+                              properties:
+                                colr: red
+                          - shape:
+                              type: Custom
+                              properties:
+                                colr: red
+        "#;
+        let library: Library = serde_yaml_ok::from_str(yaml).unwrap();
+        assert_eq!(
+            library.find_unexpected_properties(),
+            vec!["PackageA/ModuleA/ItemA - the Icon shape has unexpected properties: colr"]
+        );
+    }
+
+    #[test]
+    fn test_validate_icon_format() {
+        let yaml = r#"
+            name: testlib
+            remote_url: testlib.local:3000/distribution
+            customization:
+                icon_format: gif
+        "#;
+        let library: Library = serde_yaml_ok::from_str(yaml).unwrap();
+        let error = library.customization.validate().unwrap_err().to_string();
+        assert!(error.contains("gif"));
+        assert!(error.contains("png, svg, webp, avif"));
+        assert!(Customization::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_sprite_background() {
+        let yaml = r#"
+            name: testlib
+            remote_url: testlib.local:3000/distribution
+            customization:
+                sprite_background: black
+        "#;
+        let library: Library = serde_yaml_ok::from_str(yaml).unwrap();
+        let error = library.customization.validate().unwrap_err().to_string();
+        assert!(error.contains("black"));
+        let yaml = r#"
+            name: testlib
+            remote_url: testlib.local:3000/distribution
+            customization:
+                sprite_background: transparent
+        "#;
+        let library: Library = serde_yaml_ok::from_str(yaml).unwrap();
+        assert!(library.customization.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_source_extension() {
+        let library: Library =
+            serde_yaml_ok::from_str("{name: testlib, remote_url: distribution}").unwrap();
+        assert_eq!(library.rendering.source_extension, "puml");
+        let library: Library = serde_yaml_ok::from_str(
+            "{name: testlib, remote_url: distribution, rendering: {source_extension: ../iuml}}",
+        )
+        .unwrap();
+        let error = library.rendering.validate().unwrap_err().to_string();
+        assert!(error.contains("../iuml"));
+    }
+
     #[test]
     fn test_deserialized_templates() {
         let yaml = r#"
@@ -191,4 +768,41 @@ mod tests {
         assert_eq!(library.name, "testlib");
         assert_eq!(library.packages.len(), 2);
     }
+
+    #[test]
+    fn test_resolve_urn() {
+        let yaml = r#"
+            name: testlib
+            remote_url: testlib.local:3000/distribution
+            packages:
+                - urn: aws-q1-2022
+                  alias: aws
+                  modules:
+                    - urn: aws-q1-2022/Architecture
+                      items:
+                        - urn: aws-q1-2022/Architecture/Analytics/AmazonAthena
+                          alias: athena
+        "#;
+        let library: Library = serde_yaml_ok::from_str(yaml).unwrap();
+        assert_eq!(
+            library.resolve_urn("athena").unwrap().value,
+            "aws-q1-2022/Architecture/Analytics/AmazonAthena"
+        );
+        assert_eq!(library.resolve_urn("aws").unwrap().value, "aws-q1-2022");
+        assert_eq!(
+            library.resolve_urn("aws-q1-2022").unwrap().value,
+            "aws-q1-2022"
+        );
+        assert_eq!(
+            library
+                .resolve_urn("aws-q1-2022/Architecture")
+                .unwrap()
+                .value,
+            "aws-q1-2022/Architecture"
+        );
+        let error = library.resolve_urn("athena_typo").unwrap_err();
+        assert!(error.to_string().contains("`athena_typo`"));
+        assert!(library.resolve_urn("aws-q1-2022//Architecture").is_err());
+        assert!(library.resolve_urn("../aws-q1-2022").is_err());
+    }
 }
@@ -1,3 +1,4 @@
+pub mod diff;
 pub mod element;
 pub mod example;
 pub mod icon;
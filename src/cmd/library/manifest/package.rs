@@ -56,6 +56,9 @@ mod rendering {
 pub struct Package {
     /// The URN of the package.
     pub urn: Urn,
+    /// A short name which can be used instead of the URN.
+    #[serde(default)]
+    pub alias: Option<String>,
     /// The modules provided by the package.
     #[serde(default)]
     pub modules: Vec<Module>,
@@ -84,6 +87,7 @@ mod tests {
         "#;
         let package: Package = serde_yaml_ok::from_str(yaml).unwrap();
         assert_eq!(package.urn.value, "package/urn");
+        assert!(package.alias.is_none());
         assert!(package.modules.is_empty());
         assert!(package.examples.is_empty());
         assert_eq!(package.templates.bootstrap, "templates_bootstrap_path");
@@ -1,6 +1,12 @@
+pub use self::add_item::execute_library_add_item;
+pub use self::formats::execute_library_formats;
 pub use self::generate::execute_library_generate;
+pub use self::list_urns::execute_library_list_urns;
 pub use self::schema::execute_library_schema;
 
-mod generate;
+mod add_item;
+mod formats;
+pub mod generate;
+mod list_urns;
 pub mod manifest;
 mod schema;
@@ -0,0 +1,60 @@
+use std::io;
+use std::path::Path;
+
+use anyhow::Result;
+use clap::ArgMatches;
+use clap_mangen::{generate_to, Man};
+
+use crate::cli::build_cli;
+use crate::constants::LOG_TARGET_RESULT;
+use crate::utils::create_directory;
+
+pub fn execute_manpage(arg_matches: &ArgMatches) -> Result<()> {
+    match arg_matches.get_one::<String>("output_directory") {
+        // only the page of the root command
+        None => Man::new(build_cli())
+            .render(&mut io::stdout())
+            .map_err(|e| anyhow::Error::new(e).context("unable to write the man page")),
+        // one page per command, e.g. plantuml-generator-library-generate.1
+        Some(output_directory) => {
+            create_directory(Path::new(output_directory))?;
+            generate_to(build_cli(), output_directory).map_err(|e| {
+                anyhow::Error::new(e).context(format!(
+                    "unable to write the man pages in {}",
+                    output_directory
+                ))
+            })?;
+            log::info!(target: LOG_TARGET_RESULT, "{}", output_directory);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use crate::utils::delete_file_or_directory;
+
+    use super::*;
+
+    #[test]
+    fn test_manpage() {
+        let output_directory = "target/tests/manpage";
+        delete_file_or_directory(Path::new(output_directory)).unwrap();
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "manpage",
+            "--output=target/tests/manpage",
+        ]);
+        execute_manpage(arg_matches.subcommand_matches("manpage").unwrap()).unwrap();
+        for file_name in [
+            "plantuml-generator.1",
+            "plantuml-generator-library-generate.1",
+        ] {
+            let content = read_to_string(Path::new(output_directory).join(file_name));
+            assert!(!content.unwrap().is_empty());
+        }
+    }
+}
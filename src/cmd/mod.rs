@@ -1,11 +1,20 @@
 pub use self::completion::execute_completion;
 pub use self::diagram::execute_diagram_generate;
+pub use self::diagram::execute_diagram_render;
+pub use self::doctor::execute_doctor;
+pub use self::library::execute_library_add_item;
+pub use self::library::execute_library_formats;
 pub use self::library::execute_library_generate;
+pub use self::library::execute_library_list_urns;
 pub use self::library::execute_library_schema;
+pub use self::manpage::execute_manpage;
 pub use self::workspace::execute_workspace_init;
 pub use self::workspace::execute_workspace_install;
+pub use self::workspace::execute_workspace_list;
 
 mod completion;
 mod diagram;
-mod library;
+mod doctor;
+pub mod library;
+mod manpage;
 mod workspace;
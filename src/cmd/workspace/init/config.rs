@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use crate::constants::get_default_cache_directory;
 use crate::constants::get_default_source_directory;
 use crate::constants::get_default_workspace_manifest;
+use crate::project::ProjectConfig;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Config {
@@ -43,15 +44,24 @@ impl Config {
     }
 }
 
-impl Default for Config {
-    fn default() -> Self {
+impl Config {
+    /// Create the config, the environment takes precedence over the project config file.
+    pub fn from_project(project: &ProjectConfig) -> Config {
         Config {
             workspace_manifest: std::env::var("PLANTUML_GENERATOR_WORKSPACE_MANIFEST")
                 .unwrap_or_else(|_| get_default_workspace_manifest()),
             source_directory: std::env::var("PLANTUML_GENERATOR_SOURCE_DIRECTORY")
                 .unwrap_or_else(|_| get_default_source_directory()),
             cache_directory: std::env::var("PLANTUML_GENERATOR_CACHE_DIRECTORY")
-                .unwrap_or_else(|_| get_default_cache_directory()),
+                .ok()
+                .or_else(|| project.cache_directory.clone())
+                .unwrap_or_else(get_default_cache_directory),
         }
     }
 }
+
+impl Default for Config {
+    fn default() -> Self {
+        Config::from_project(&ProjectConfig::default())
+    }
+}
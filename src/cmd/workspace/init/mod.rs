@@ -4,14 +4,33 @@ use anyhow::Result;
 use clap::ArgMatches;
 
 use crate::cmd::workspace::init::config::Config;
+use crate::cmd::workspace::manifest::artifact::Artifact;
 use crate::cmd::workspace::manifest::workspace::Workspace;
+use crate::network::download;
+use crate::project::ProjectConfig;
 use crate::utils::{create_directory, create_parent_directory};
 
 mod config;
 
+/// Resolve the version of the latest release of the builtin artifact from the GitHub redirection.
+fn resolve_latest_builtin_version() -> Result<String> {
+    let url = "https://github.com/tmorin/plantuml-libs/releases/latest";
+    log::info!("resolve the latest version from {}", url);
+    let response = download(url)?;
+    // e.g. https://github.com/tmorin/plantuml-libs/releases/tag/v11.0.0
+    match response.url().path().rsplit_once("/tag/v") {
+        Some((_, version)) if !version.is_empty() => Ok(version.to_string()),
+        _ => Err(anyhow::Error::msg(format!(
+            "unable to resolve the latest version from {}",
+            response.url()
+        ))),
+    }
+}
+
 pub fn execute_workspace_init(arg_matches: &ArgMatches) -> Result<()> {
     // resolve the config
-    let config = &Config::default().update_from_args(arg_matches);
+    let project = &ProjectConfig::load(arg_matches)?;
+    let config = &Config::from_project(project).update_from_args(arg_matches);
     if log::log_enabled!(log::Level::Info) {
         log::info!("cache_directory: {}", &config.cache_directory);
         log::info!("source_directory: {}", &config.source_directory);
@@ -31,10 +50,22 @@ pub fn execute_workspace_init(arg_matches: &ArgMatches) -> Result<()> {
     create_directory(cache_path)?;
     // create source directory
     create_parent_directory(manifest_path.as_path())?;
+    // resolve the starter artifacts
+    let artifacts = match arg_matches.get_one::<String>("with_builtin") {
+        None => vec![],
+        Some(version) => vec![Artifact::Builtin {
+            version: match version.as_str() {
+                "latest" => resolve_latest_builtin_version()?,
+                _ => version.clone(),
+            },
+            checksum: None,
+            url: None,
+        }],
+    };
     // create the Workspace manifest
     let manifest = Workspace {
         cache_directory: cache_path.to_str().unwrap().to_string(),
-        artifacts: vec![],
+        artifacts,
     };
     // save the Workspace manifest
     let f = std::fs::OpenOptions::new()
@@ -82,6 +113,38 @@ mod test {
         assert!(path_cache_directory.exists());
     }
 
+    #[test]
+    fn test_init_with_builtin() {
+        delete_file_or_directory("target/tests/cmd/workspace/init_with_builtin".as_ref()).unwrap();
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "workspace",
+            "init",
+            "-s=target/tests/cmd/workspace/init_with_builtin/source",
+            "-C=target/tests/cmd/workspace/init_with_builtin/cache",
+            "--with-builtin=11.0.0",
+        ]);
+        execute_workspace_init(
+            arg_matches
+                .subcommand_matches("workspace")
+                .unwrap()
+                .subcommand_matches("init")
+                .unwrap(),
+        )
+        .unwrap();
+        let workspace = Workspace::load(Path::new(
+            "target/tests/cmd/workspace/init_with_builtin/source/.pgen-workspace.yaml",
+        ))
+        .unwrap();
+        assert_eq!(workspace.artifacts.len(), 1);
+        assert_eq!(
+            workspace.artifacts[0].get_type(),
+            "github.com/tmorin/plantuml-libs"
+        );
+        assert_eq!(workspace.artifacts[0].get_version(), "11.0.0");
+    }
+
     #[test]
     fn test_init_when_manifest_already_exist() {
         let source_path = Path::new("target/tests/cmd/workspace/init/source");
@@ -1,19 +1,145 @@
-use std::fs::{read_to_string, File};
-use std::path::Path;
+use std::fs::{write, File};
+use std::io::{copy, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
 
 use clap::ArgMatches;
+use flate2::read::GzDecoder;
+use glob::{glob, Pattern};
+use sha2::{Digest, Sha256};
 
 use crate::cmd::workspace::install::config::Config;
-use crate::cmd::workspace::manifest::artifact::Artifact;
+use crate::cmd::workspace::manifest::artifact::{ArchiveFormat, Artifact};
 use crate::cmd::workspace::manifest::workspace::Workspace;
-use crate::utils::{create_directory, create_parent_directory, delete_file_or_directory};
+use crate::network::{download, to_download_error};
+use crate::utils::{
+    create_directory, create_parent_directory, delete_file, delete_file_or_directory, read_file,
+    write_file_atomically,
+};
 
 mod config;
 
+/// The path of the list of the files extracted from the archive, next to the artifact.
+fn get_extracted_files_path(artifact_path: &Path) -> PathBuf {
+    let mut path = artifact_path.as_os_str().to_os_string();
+    path.push(".files");
+    PathBuf::from(path)
+}
+
+/// The path of the checksum of the archive, recorded once the download is completed.
+fn get_checksum_path(archive_path: &Path) -> PathBuf {
+    let mut path = archive_path.as_os_str().to_os_string();
+    path.push(".sha256");
+    PathBuf::from(path)
+}
+
+/// The SHA-256 of a file as a hexadecimal string.
+fn compute_checksum(path: &Path) -> anyhow::Result<String> {
+    let mut file = File::open(path)
+        .map_err(|e| anyhow::Error::new(e).context(format!("unable to open {}", path.display())))?;
+    let mut hasher = Sha256::new();
+    copy(&mut file, &mut hasher)
+        .map_err(|e| anyhow::Error::new(e).context(format!("unable to read {}", path.display())))?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// True when the archive matches the checksum of the manifest, or else the recorded one.
+fn is_archive_valid(archive_path: &Path, checksum: Option<&str>) -> bool {
+    let expected_checksum = match checksum {
+        Some(checksum) => Some(checksum.trim().to_lowercase()),
+        None => read_file(&get_checksum_path(archive_path))
+            .ok()
+            .flatten()
+            .map(|checksum| checksum.trim().to_lowercase()),
+    };
+    match expected_checksum {
+        // the archives without checksum, e.g. copied by hand, are trusted
+        None => true,
+        Some(expected_checksum) => compute_checksum(archive_path)
+            .map(|checksum| checksum == expected_checksum)
+            .unwrap_or(false),
+    }
+}
+
+/// The format of the archive, from its magic bytes or else its extension.
+fn detect_archive_format(archive_path: &Path, archive_file: &mut File) -> ArchiveFormat {
+    let mut magic_bytes = [0u8; 2];
+    let is_gzip = archive_file.read_exact(&mut magic_bytes).is_ok() && magic_bytes == [0x1f, 0x8b];
+    let _ = archive_file.seek(SeekFrom::Start(0));
+    match is_gzip {
+        true => ArchiveFormat::TarGz,
+        false => ArchiveFormat::from_path(&archive_path.to_string_lossy()),
+    }
+}
+
+/// Extract a zip or tar.gz archive into the artifact directory.
+fn extract_archive(archive_path: &Path, artifact_path: &Path) -> anyhow::Result<()> {
+    let mut archive_file = File::open(archive_path).map_err(|e| {
+        anyhow::Error::new(e).context(format!("unable to open {}", archive_path.display()))
+    })?;
+    match detect_archive_format(archive_path, &mut archive_file) {
+        ArchiveFormat::TarGz => tar::Archive::new(GzDecoder::new(archive_file))
+            .unpack(artifact_path)
+            .map_err(|e| {
+                anyhow::Error::new(e)
+                    .context(format!("unable to extract {}", archive_path.display()))
+            }),
+        ArchiveFormat::Zip => {
+            zip_extract::extract(archive_file, artifact_path, false).map_err(|e| {
+                anyhow::Error::new(e).context(format!("unable to unzip {}", archive_path.display()))
+            })
+        }
+    }
+}
+
+/// The paths of the files of the artifact, relative to the artifact.
+fn list_files(artifact_path: &Path) -> anyhow::Result<Vec<String>> {
+    let pattern = format!(
+        "{}/**/*",
+        Pattern::escape(artifact_path.to_string_lossy().as_ref())
+    );
+    let paths = glob(&pattern).map_err(|e| {
+        anyhow::Error::new(e).context(format!("unable to parse the glob pattern ({})", pattern))
+    })?;
+    let mut files = paths
+        .flatten()
+        .filter(|path| path.is_file())
+        .filter_map(|path| {
+            path.strip_prefix(artifact_path)
+                .ok()
+                .map(|p| p.to_string_lossy().to_string())
+        })
+        .collect::<Vec<String>>();
+    files.sort();
+    Ok(files)
+}
+
+/// Record the files of the extracted artifact, the record marks the extraction as completed.
+fn write_extracted_files(artifact_path: &Path) -> anyhow::Result<()> {
+    let files_path = get_extracted_files_path(artifact_path);
+    write(&files_path, list_files(artifact_path)?.join("\n")).map_err(|e| {
+        anyhow::Error::new(e).context(format!("unable to write {}", files_path.display()))
+    })
+}
+
+/// True when the extraction has been completed and, when verified, all the files are there.
+fn is_artifact_complete(artifact_path: &Path, do_verify: bool) -> bool {
+    match read_file(&get_extracted_files_path(artifact_path)) {
+        Ok(Some(files)) => {
+            !do_verify
+                || files
+                    .lines()
+                    .filter(|file| !file.is_empty())
+                    .all(|file| artifact_path.join(file).is_file())
+        }
+        _ => false,
+    }
+}
+
 pub fn execute_workspace_install(arg_matches: &ArgMatches) -> anyhow::Result<()> {
     // resolve the config
     let config = &Config::default().update_from_args(arg_matches);
     let do_force_install = arg_matches.get_flag("do_force_install");
+    let do_verify = arg_matches.get_flag("do_verify");
     if log::log_enabled!(log::Level::Info) {
         log::info!("source_directory: {}", &config.source_directory);
         log::info!("workspace_manifest: {}", &config.workspace_manifest);
@@ -21,45 +147,40 @@ pub fn execute_workspace_install(arg_matches: &ArgMatches) -> anyhow::Result<()>
     let source_path = Path::new(config.source_directory.as_str());
     let manifest_path = source_path.join(config.workspace_manifest.as_str());
 
-    // stop if manifest doesn't exists
-    if !manifest_path.exists() {
-        Err(anyhow::Error::msg(format!(
-            "the manifest {} doesn't exists",
-            manifest_path.to_str().unwrap(),
-        )))?;
-    }
-
-    // create the YAML parser
-    let yaml = &read_to_string(&manifest_path).map_err(|e| {
-        anyhow::Error::new(e).context(format!(
-            "unable to read {}",
-            manifest_path.to_str().unwrap()
-        ))
-    })?;
-
     // parse the manifest
-    let manifest: &Workspace = &serde_yaml_ok::from_str(yaml).map_err(|e| {
-        anyhow::Error::new(e).context(format!(
-            "unable to parse {}",
-            manifest_path.to_str().unwrap()
-        ))
-    })?;
+    let manifest = &Workspace::load(&manifest_path)?;
     log::debug!("manifest {:?}", manifest);
 
     // process the artifact
     for artifact in &manifest.artifacts {
         log::debug!("process artifact {:?}", artifact);
         match artifact {
-            Artifact::Builtin { version } => {
+            Artifact::Builtin { .. } => {
                 // resolve the path
-                let cache_path = Path::new(&manifest.cache_directory);
-                let archive_cache_path = &cache_path.join("tmorin_plantuml-libs");
-                let archive_path = &archive_cache_path.join(format!("archive-{}.zip", version));
-                let artifact_path = &archive_cache_path.join(version);
+                let archive_path = &artifact.get_archive_path(&manifest.cache_directory);
+                let artifact_path = &artifact.get_artifact_path(&manifest.cache_directory);
 
                 // cleanup if expected
                 if do_force_install {
                     delete_file_or_directory(archive_path)?;
+                    delete_file(&get_checksum_path(archive_path))?;
+                    delete_file_or_directory(artifact_path)?;
+                    delete_file(&get_extracted_files_path(artifact_path))?;
+                }
+
+                // download again the archive when it doesn't match its checksum
+                if archive_path.exists() && !is_archive_valid(archive_path, artifact.get_checksum())
+                {
+                    log::warn!("the archive {:?} is corrupted", archive_path);
+                    delete_file(archive_path)?;
+                    delete_file(&get_checksum_path(archive_path))?;
+                    delete_file_or_directory(artifact_path)?;
+                    delete_file(&get_extracted_files_path(artifact_path))?;
+                }
+
+                // extract again the artifact when the previous extraction is incomplete
+                if artifact_path.exists() && !is_artifact_complete(artifact_path, do_verify) {
+                    log::warn!("the artifact {:?} is incomplete", artifact_path);
                     delete_file_or_directory(artifact_path)?;
                 }
 
@@ -67,23 +188,28 @@ pub fn execute_workspace_install(arg_matches: &ArgMatches) -> anyhow::Result<()>
                 if !archive_path.exists() {
                     // create the cache folder
                     create_parent_directory(archive_path)?;
-                    let url = format!(
-                        "https://github.com/tmorin/plantuml-libs/releases/download/v{}/tmorin-plantuml-libs.zip",
-                        version,
-                    );
+                    let url = artifact.get_url();
                     log::info!("download {}", url);
-                    match reqwest::blocking::get(&url)
-                        .map_err(anyhow::Error::new)
-                        .and_then(|r| r.error_for_status().map_err(anyhow::Error::new))
+                    // the archive is only created once the download is complete
+                    match download(&url)
                         .and_then(|mut r| {
-                            File::create(archive_path)
-                                .map_err(anyhow::Error::new)
-                                .and_then(|mut archive_file| {
-                                    r.copy_to(&mut archive_file).map_err(anyhow::Error::new)
-                                })
-                        }) {
-                        Ok(_) => {
-                            log::info!("download completed for {}", url)
+                            write_file_atomically(archive_path, |archive_file| {
+                                r.copy_to(archive_file)
+                                    .map(|_| ())
+                                    .map_err(|e| to_download_error(e, &url))
+                            })
+                        })
+                        .and_then(|_| compute_checksum(archive_path))
+                    {
+                        Ok(checksum) if is_archive_valid(archive_path, artifact.get_checksum()) => {
+                            log::info!("download completed for {}", url);
+                            write(get_checksum_path(archive_path), checksum).map_err(|e| {
+                                anyhow::Error::new(e).context("unable to write the checksum")
+                            })?;
+                        }
+                        Ok(checksum) => {
+                            log::warn!("the checksum of {} is unexpected: {}", url, checksum);
+                            delete_file(archive_path)?;
                         }
                         Err(e) => {
                             log::warn!("{:?}", e)
@@ -91,31 +217,21 @@ pub fn execute_workspace_install(arg_matches: &ArgMatches) -> anyhow::Result<()>
                     }
                 }
 
-                // unzip the archive
+                // extract the archive
                 if archive_path.exists() && !artifact_path.exists() {
                     // create the destination folder
                     create_directory(artifact_path)?;
-                    log::info!("unzip {:?} to {:?}", archive_path, artifact_path);
-                    match File::open(archive_path)
-                        .map_err(|e| {
-                            anyhow::Error::new(e).context(format!(
-                                "unable to open {}",
-                                archive_path.to_str().unwrap()
-                            ))
-                        })
-                        .and_then(|archive_file| {
-                            zip_extract::extract(archive_file, artifact_path, false).map_err(|e| {
-                                anyhow::Error::new(e).context(format!(
-                                    "unable to unzip {}",
-                                    artifact_path.to_str().unwrap()
-                                ))
-                            })
-                        }) {
+                    log::info!("extract {:?} to {:?}", archive_path, artifact_path);
+                    match extract_archive(archive_path, artifact_path)
+                        .and_then(|_| write_extracted_files(artifact_path))
+                    {
                         Ok(_) => {
-                            log::info!("unzip completed for {:?}", archive_path)
+                            log::info!("extraction completed for {:?}", archive_path)
                         }
                         Err(e) => {
-                            log::warn!("{:?}", e)
+                            log::warn!("{:?}", e);
+                            // a partial extraction must not be reused
+                            delete_file_or_directory(artifact_path)?;
                         }
                     };
                 }
@@ -136,6 +252,205 @@ mod test {
 
     use super::*;
 
+    #[test]
+    fn test_install_restore_incomplete_artifact() {
+        let test_path = Path::new("target/tests/cmd/workspace/install_verify");
+        let manifest_path = &test_path.join("source").join(WORKSPACE_MANIFEST);
+        let archive_path = &test_path.join("cache/tmorin_plantuml-libs/archive-1.0.0.zip");
+        let restored_path =
+            &test_path.join("cache/tmorin_plantuml-libs/1.0.0/distribution/bootstrap.puml");
+
+        delete_file_or_directory(test_path).unwrap();
+        create_parent_directory(manifest_path).unwrap();
+        std::fs::write(
+            manifest_path,
+            r#"
+            cache_directory: "target/tests/cmd/workspace/install_verify/cache"
+            artifacts:
+              - type: "github.com/tmorin/plantuml-libs"
+                version: "1.0.0"
+            "#,
+        )
+        .unwrap();
+        // the archive is already there, so nothing is downloaded
+        create_parent_directory(archive_path).unwrap();
+        copy(Path::new("test/workspace-archive.zip"), archive_path).unwrap();
+
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "workspace",
+            "install",
+            "-s=target/tests/cmd/workspace/install_verify/source",
+            "--verify",
+        ]);
+        let install = || {
+            execute_workspace_install(
+                arg_matches
+                    .subcommand_matches("workspace")
+                    .unwrap()
+                    .subcommand_matches("install")
+                    .unwrap(),
+            )
+            .unwrap()
+        };
+
+        install();
+        assert!(restored_path.exists());
+
+        delete_file(restored_path).unwrap();
+        assert!(!restored_path.exists());
+
+        install();
+        assert!(restored_path.exists());
+    }
+
+    #[test]
+    fn test_install_tar_gz_archive() {
+        let test_path = Path::new("target/tests/cmd/workspace/install_tar_gz");
+        let manifest_path = &test_path.join("source").join(WORKSPACE_MANIFEST);
+        let archive_path = &test_path.join("cache/tmorin_plantuml-libs/archive-1.0.0.tar.gz");
+        let artifact_path = &test_path.join("cache/tmorin_plantuml-libs/1.0.0");
+
+        delete_file_or_directory(test_path).unwrap();
+        create_parent_directory(manifest_path).unwrap();
+        std::fs::write(
+            manifest_path,
+            r#"
+            cache_directory: "target/tests/cmd/workspace/install_tar_gz/cache"
+            artifacts:
+              - type: "github.com/tmorin/plantuml-libs"
+                version: "1.0.0"
+                url: "http://localhost/tmorin-plantuml-libs.tar.gz"
+            "#,
+        )
+        .unwrap();
+        // the archive is already there, so nothing is downloaded
+        create_parent_directory(archive_path).unwrap();
+        let encoder = flate2::write::GzEncoder::new(
+            File::create(archive_path).unwrap(),
+            flate2::Compression::default(),
+        );
+        let mut builder = tar::Builder::new(encoder);
+        builder
+            .append_path_with_name(
+                "test/library-simple.yaml",
+                "distribution/library-simple.yaml",
+            )
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "workspace",
+            "install",
+            "-s=target/tests/cmd/workspace/install_tar_gz/source",
+        ]);
+        execute_workspace_install(
+            arg_matches
+                .subcommand_matches("workspace")
+                .unwrap()
+                .subcommand_matches("install")
+                .unwrap(),
+        )
+        .unwrap();
+
+        assert!(artifact_path
+            .join("distribution/library-simple.yaml")
+            .is_file());
+        assert!(is_artifact_complete(artifact_path, true));
+    }
+
+    #[test]
+    fn test_archive_checksum() {
+        let test_path = Path::new("target/tests/cmd/workspace/install_checksum");
+        let archive_path = &test_path.join("archive-1.0.0.zip");
+        delete_file_or_directory(test_path).unwrap();
+        create_parent_directory(archive_path).unwrap();
+        copy(Path::new("test/workspace-archive.zip"), archive_path).unwrap();
+        let checksum = compute_checksum(archive_path).unwrap();
+        assert_eq!(checksum.len(), 64);
+
+        // without checksum, the archive is trusted
+        assert!(is_archive_valid(archive_path, None));
+        assert!(is_archive_valid(
+            archive_path,
+            Some(&checksum.to_uppercase())
+        ));
+        assert!(!is_archive_valid(archive_path, Some("0000")));
+
+        // the recorded checksum is used when the manifest doesn't provide one
+        write(get_checksum_path(archive_path), &checksum).unwrap();
+        assert!(is_archive_valid(archive_path, None));
+        write(archive_path, "corrupted").unwrap();
+        assert!(!is_archive_valid(archive_path, None));
+    }
+
+    #[test]
+    fn test_install_repair_corrupted_archive() {
+        let test_path = Path::new("target/tests/cmd/workspace/install_repair");
+        let manifest_path = &test_path.join("source").join(WORKSPACE_MANIFEST);
+        let archive_path = &test_path.join("cache/tmorin_plantuml-libs/archive-1.0.0.zip");
+        let artifact_path = &test_path.join("cache/tmorin_plantuml-libs/1.0.0");
+
+        delete_file_or_directory(test_path).unwrap();
+        create_parent_directory(manifest_path).unwrap();
+        // the URL cannot be downloaded, so the archive only comes from the cache
+        std::fs::write(
+            manifest_path,
+            r#"
+            cache_directory: "target/tests/cmd/workspace/install_repair/cache"
+            artifacts:
+              - type: "github.com/tmorin/plantuml-libs"
+                version: "1.0.0"
+                url: "file:///tmorin-plantuml-libs.zip"
+            "#,
+        )
+        .unwrap();
+        // the archive is there with the checksum recorded by its download
+        create_parent_directory(archive_path).unwrap();
+        copy(Path::new("test/workspace-archive.zip"), archive_path).unwrap();
+        let checksum = compute_checksum(archive_path).unwrap();
+        write(get_checksum_path(archive_path), &checksum).unwrap();
+
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "workspace",
+            "install",
+            "-s=target/tests/cmd/workspace/install_repair/source",
+        ]);
+        let install = || {
+            execute_workspace_install(
+                arg_matches
+                    .subcommand_matches("workspace")
+                    .unwrap()
+                    .subcommand_matches("install")
+                    .unwrap(),
+            )
+            .unwrap()
+        };
+
+        install();
+        assert!(is_artifact_complete(artifact_path, true));
+
+        // the archive is corrupted, e.g. by an interrupted copy
+        write(archive_path, "corrupted").unwrap();
+
+        // the corrupted archive and its artifact are discarded, then the download fails
+        install();
+        assert!(!archive_path.exists());
+        assert!(!get_checksum_path(archive_path).exists());
+        assert!(!artifact_path.exists());
+
+        // a valid archive is put back, like a successful download
+        copy(Path::new("test/workspace-archive.zip"), archive_path).unwrap();
+        install();
+        assert_eq!(compute_checksum(archive_path).unwrap(), checksum);
+        assert!(is_artifact_complete(artifact_path, true));
+    }
+
     #[test]
     fn test_install() {
         let test_path = Path::new("target/tests/cmd/workspace/install");
@@ -0,0 +1,145 @@
+use std::fmt::{Display, Formatter};
+use std::fs::read_dir;
+use std::path::Path;
+
+use clap::ArgMatches;
+
+use crate::cmd::workspace::list::config::Config;
+use crate::cmd::workspace::manifest::workspace::Workspace;
+
+mod config;
+
+#[derive(Debug)]
+struct Entry {
+    /// The type of the artifact.
+    artifact_type: String,
+    /// The version of the artifact.
+    version: String,
+    /// When true, the artifact has been extracted in the cache directory.
+    present: bool,
+    /// The size of the archive and of the extracted files, in bytes.
+    size: u64,
+}
+
+impl Display for Entry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let status = if self.present { "present" } else { "absent" };
+        write!(
+            f,
+            "[{}] {} {} ({} bytes)",
+            status, self.artifact_type, self.version, self.size
+        )
+    }
+}
+
+/// The size of a file or of a directory and its content, missing paths are empty.
+fn get_size(path: &Path) -> u64 {
+    match path.metadata() {
+        Ok(metadata) if metadata.is_dir() => read_dir(path)
+            .map(|entries| entries.flatten().map(|entry| get_size(&entry.path())).sum())
+            .unwrap_or_default(),
+        Ok(metadata) => metadata.len(),
+        Err(_) => 0,
+    }
+}
+
+fn create_entries(manifest: &Workspace) -> Vec<Entry> {
+    manifest
+        .artifacts
+        .iter()
+        .map(|artifact| {
+            let archive_path = artifact.get_archive_path(&manifest.cache_directory);
+            let artifact_path = artifact.get_artifact_path(&manifest.cache_directory);
+            Entry {
+                artifact_type: artifact.get_type().to_string(),
+                version: artifact.get_version().to_string(),
+                present: artifact_path.is_dir(),
+                size: get_size(&archive_path) + get_size(&artifact_path),
+            }
+        })
+        .collect()
+}
+
+pub fn execute_workspace_list(arg_matches: &ArgMatches) -> anyhow::Result<()> {
+    // resolve the config
+    let config = &Config::default().update_from_args(arg_matches);
+    if log::log_enabled!(log::Level::Info) {
+        log::info!("source_directory: {}", &config.source_directory);
+        log::info!("workspace_manifest: {}", &config.workspace_manifest);
+    }
+    let source_path = Path::new(config.source_directory.as_str());
+    let manifest_path = source_path.join(config.workspace_manifest.as_str());
+
+    // parse the manifest
+    let manifest = &Workspace::load(&manifest_path)?;
+
+    for entry in create_entries(manifest) {
+        println!("{}", entry);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::write;
+
+    use crate::cli::build_cli;
+    use crate::constants::WORKSPACE_MANIFEST;
+    use crate::utils::{create_directory, create_parent_directory, delete_file_or_directory};
+
+    use super::*;
+
+    #[test]
+    fn test_list() {
+        let test_path = Path::new("target/tests/cmd/workspace/list");
+        let manifest_path = &test_path.join("source").join(WORKSPACE_MANIFEST);
+        delete_file_or_directory(test_path).unwrap();
+        create_parent_directory(manifest_path).unwrap();
+        write(
+            manifest_path,
+            r#"
+            cache_directory: "target/tests/cmd/workspace/list/cache"
+            artifacts:
+              - type: "github.com/tmorin/plantuml-libs"
+                version: "1.0.0"
+              - type: "github.com/tmorin/plantuml-libs"
+                version: "2.0.0"
+            "#,
+        )
+        .unwrap();
+        // only the first artifact is installed
+        let artifact_path = test_path.join("cache/tmorin_plantuml-libs/1.0.0");
+        create_directory(&artifact_path).unwrap();
+        write(artifact_path.join("bootstrap.puml"), "0123456789").unwrap();
+
+        let manifest = &Workspace::load(manifest_path).unwrap();
+        let lines: Vec<String> = create_entries(manifest)
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "[present] github.com/tmorin/plantuml-libs 1.0.0 (10 bytes)",
+                "[absent] github.com/tmorin/plantuml-libs 2.0.0 (0 bytes)",
+            ]
+        );
+
+        let arg_matches = build_cli().get_matches_from([
+            "plantuml-generator",
+            "-l=Off",
+            "workspace",
+            "list",
+            "-s=target/tests/cmd/workspace/list/source",
+        ]);
+        execute_workspace_list(
+            arg_matches
+                .subcommand_matches("workspace")
+                .unwrap()
+                .subcommand_matches("list")
+                .unwrap(),
+        )
+        .unwrap();
+    }
+}
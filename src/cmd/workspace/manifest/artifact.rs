@@ -1,3 +1,5 @@
+use std::path::{Path, PathBuf};
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -9,5 +11,91 @@ pub enum Artifact {
         /// The version.
         #[serde(default)]
         version: String,
+        /// The SHA-256 of the archive, by default the one computed at the download.
+        #[serde(default)]
+        checksum: Option<String>,
+        /// The URL of the archive, a zip or a tar.gz, by default the one of the GitHub release.
+        #[serde(default)]
+        url: Option<String>,
     },
 }
+
+/// The formats of the archives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    /// The format matching the extension of a path or URL, by default zip.
+    pub fn from_path(path: &str) -> ArchiveFormat {
+        let path = path.to_lowercase();
+        if path.ends_with(".tar.gz") || path.ends_with(".tgz") {
+            ArchiveFormat::TarGz
+        } else {
+            ArchiveFormat::Zip
+        }
+    }
+    /// The extension of the archives of the format.
+    pub fn get_extension(&self) -> &str {
+        match self {
+            ArchiveFormat::Zip => "zip",
+            ArchiveFormat::TarGz => "tar.gz",
+        }
+    }
+}
+
+impl Artifact {
+    /// The type of the artifact, as written in the manifest.
+    pub fn get_type(&self) -> &str {
+        match self {
+            Artifact::Builtin { .. } => "github.com/tmorin/plantuml-libs",
+        }
+    }
+    /// The version of the artifact.
+    pub fn get_version(&self) -> &str {
+        match self {
+            Artifact::Builtin { version, .. } => version,
+        }
+    }
+    /// The expected SHA-256 of the archive, when given by the manifest.
+    pub fn get_checksum(&self) -> Option<&str> {
+        match self {
+            Artifact::Builtin { checksum, .. } => checksum.as_deref(),
+        }
+    }
+    /// The URL of the archive.
+    pub fn get_url(&self) -> String {
+        match self {
+            Artifact::Builtin { version, url, .. } => url.clone().unwrap_or(format!(
+                "https://github.com/tmorin/plantuml-libs/releases/download/v{}/tmorin-plantuml-libs.zip",
+                version,
+            )),
+        }
+    }
+    /// The format of the archive, resolved from its URL.
+    pub fn get_archive_format(&self) -> ArchiveFormat {
+        ArchiveFormat::from_path(&self.get_url())
+    }
+    /// The path of the downloaded archive in the cache directory.
+    pub fn get_archive_path(&self, cache_directory: &str) -> PathBuf {
+        match self {
+            Artifact::Builtin { version, .. } => Path::new(cache_directory)
+                .join("tmorin_plantuml-libs")
+                .join(format!(
+                    "archive-{}.{}",
+                    version,
+                    self.get_archive_format().get_extension()
+                )),
+        }
+    }
+    /// The path of the extracted archive in the cache directory.
+    pub fn get_artifact_path(&self, cache_directory: &str) -> PathBuf {
+        match self {
+            Artifact::Builtin { version, .. } => Path::new(cache_directory)
+                .join("tmorin_plantuml-libs")
+                .join(version),
+        }
+    }
+}
@@ -1,3 +1,6 @@
+use std::fs::read_to_string;
+use std::path::Path;
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -11,3 +14,32 @@ pub struct Workspace {
     #[serde(default)]
     pub artifacts: Vec<Artifact>,
 }
+
+impl Workspace {
+    /// Read and parse the manifest of a workspace.
+    pub fn load(manifest_path: &Path) -> anyhow::Result<Workspace> {
+        // stop if manifest doesn't exists
+        if !manifest_path.exists() {
+            Err(anyhow::Error::msg(format!(
+                "the manifest {} doesn't exists",
+                manifest_path.to_str().unwrap(),
+            )))?;
+        }
+
+        // create the YAML parser
+        let yaml = &read_to_string(manifest_path).map_err(|e| {
+            anyhow::Error::new(e).context(format!(
+                "unable to read {}",
+                manifest_path.to_str().unwrap()
+            ))
+        })?;
+
+        // parse the manifest
+        serde_yaml_ok::from_str(yaml).map_err(|e| {
+            anyhow::Error::new(e).context(format!(
+                "unable to parse {}",
+                manifest_path.to_str().unwrap()
+            ))
+        })
+    }
+}
@@ -1,6 +1,8 @@
 pub use self::init::execute_workspace_init;
 pub use self::install::execute_workspace_install;
+pub use self::list::execute_workspace_list;
 
 mod init;
 mod install;
+mod list;
 mod manifest;
@@ -64,12 +64,28 @@ pub fn get_default_icon_height() -> u32 {
     ICON_HEIGHT
 }
 
+pub const LIBRARY_VERSION: &str = "0.0.0";
+
+pub fn get_default_library_version() -> String {
+    LIBRARY_VERSION.to_string()
+}
+
+/// The extension of the generated sources, e.g. `<library>/<package>/<module>/<item>.puml`.
+pub const SOURCE_EXTENSION: &str = "puml";
+
+pub fn get_default_source_extension() -> String {
+    SOURCE_EXTENSION.to_string()
+}
+
 pub const ICON_FORMAT: &str = "png";
 
 pub fn get_default_icon_format() -> String {
     ICON_FORMAT.to_string()
 }
 
+/// The formats of the generated icons, WebP and AVIF are only encoded from raster sources.
+pub const ICON_FORMATS: [&str; 4] = ["png", "svg", "webp", "avif"];
+
 pub const FONT_COLOR: &str = "#212121";
 
 pub fn get_default_font_color() -> String {
@@ -82,6 +98,15 @@ pub fn get_default_font_color_light() -> String {
     FONT_COLOR_LIGHT.to_string()
 }
 
+pub const SPRITE_BACKGROUND: &str = "#FFFFFF";
+
+pub fn get_default_sprite_background() -> String {
+    SPRITE_BACKGROUND.to_string()
+}
+
+/// The sprite background keeping the transparency of the icons.
+pub const SPRITE_BACKGROUND_TRANSPARENT: &str = "transparent";
+
 pub const SOURCE_DIRECTORY: &str = ".";
 
 pub fn get_default_source_directory() -> String {
@@ -118,12 +143,54 @@ pub fn get_default_plantuml_version() -> String {
     PLANTUML_VERSION.to_string()
 }
 
+/// The oldest PlantUML version supporting the features of the generator, e.g. the sprite encodings.
+pub const MINIMUM_PLANTUML_VERSION: &str = "1.2021.0";
+
 pub const PLANTUML_JAR: &str = ".cache/plantuml-1.2024.7.jar";
 
 pub fn get_default_plantuml_jar() -> String {
     PLANTUML_JAR.to_string()
 }
 
+pub const PLANTUML_RETRIES: u32 = 2;
+
+pub fn get_default_plantuml_retries() -> u32 {
+    PLANTUML_RETRIES
+}
+
+pub const RENDER_FORMAT: &str = "svg";
+
+/// The output formats of PlantUML, when they cannot be read from the jar.
+pub const DIAGRAM_FORMATS: [&str; 12] = [
+    "png",
+    "svg",
+    "eps",
+    "pdf",
+    "vdx",
+    "xmi",
+    "scxml",
+    "html",
+    "txt",
+    "utxt",
+    "latex",
+    "latex:nopreamble",
+];
+
+pub const JOBS: usize = 1;
+
+pub fn get_default_jobs() -> usize {
+    JOBS
+}
+
+/// The default maximum number of Java processes running at once, each JVM reserves its heap.
+pub const JAVA_CONCURRENCY: usize = 4;
+
+/// The default banner of the generated text artifacts, without timestamp to keep the outputs stable.
+pub const BANNER: &str = "DO NOT EDIT - generated by plantuml-generator v{version}";
+
+/// The default timeout of the connections and the reads of the downloads, in seconds.
+pub const DOWNLOAD_TIMEOUT: u64 = 30;
+
 pub const JAVA_BINARY: &str = "java";
 
 pub fn get_default_java_binary() -> String {
@@ -148,6 +215,8 @@ pub fn get_default_template_item_documentation() -> String {
     TEMPLATE_ITEM_DOCUMENTATION.to_string()
 }
 
+pub const TEMPLATE_ITEM_DOCUMENTATION_ASCIIDOC: &str = "item_documentation_asciidoc.tera";
+
 pub const TEMPLATE_ITEM_SOURCE: &str = "item_source.tera";
 
 pub fn get_default_template_item_source() -> String {
@@ -172,18 +241,24 @@ pub fn get_default_template_library_documentation() -> String {
     TEMPLATE_LIBRARY_DOCUMENTATION.to_string()
 }
 
+pub const TEMPLATE_LIBRARY_DOCUMENTATION_ASCIIDOC: &str = "library_documentation_asciidoc.tera";
+
 pub const TEMPLATE_LIBRARY_SUMMARY: &str = "library_summary.tera";
 
 pub fn get_default_template_library_summary() -> String {
     TEMPLATE_LIBRARY_SUMMARY.to_string()
 }
 
+pub const TEMPLATE_LIBRARY_SUMMARY_ASCIIDOC: &str = "library_summary_asciidoc.tera";
+
 pub const TEMPLATE_MODULE_DOCUMENTATION: &str = "module_documentation.tera";
 
 pub fn get_default_template_module_documentation() -> String {
     TEMPLATE_MODULE_DOCUMENTATION.to_string()
 }
 
+pub const TEMPLATE_MODULE_DOCUMENTATION_ASCIIDOC: &str = "module_documentation_asciidoc.tera";
+
 pub const TEMPLATE_PACKAGE_BOOTSTRAP: &str = "package_bootstrap.tera";
 
 pub fn get_default_template_package_bootstrap() -> String {
@@ -202,6 +277,8 @@ pub fn get_default_template_package_documentation() -> String {
     TEMPLATE_PACKAGE_DOCUMENTATION.to_string()
 }
 
+pub const TEMPLATE_PACKAGE_DOCUMENTATION_ASCIIDOC: &str = "package_documentation_asciidoc.tera";
+
 pub const TEMPLATE_PACKAGE_EXAMPLE: &str = "package_example.tera";
 
 pub const WORKSPACE_MANIFEST: &str = ".pgen-workspace.yaml";
@@ -209,3 +286,27 @@ pub const WORKSPACE_MANIFEST: &str = ".pgen-workspace.yaml";
 pub fn get_default_workspace_manifest() -> String {
     WORKSPACE_MANIFEST.to_string()
 }
+
+pub const PROJECT_CONFIG: &str = ".pgen.yaml";
+
+/// The gitignore-like file of the source directory listing the sources not to render.
+pub const DIAGRAM_IGNORE: &str = ".pgenignore";
+
+/// The timestamps of the last generations of the diagrams keyed by source in the cache directory.
+pub const DIAGRAM_LAST_GENERATIONS: &str = "LAST_GENERATIONS.json";
+
+/// The timestamp of the last generation shared by all the diagrams, replaced by [DIAGRAM_LAST_GENERATIONS].
+pub const DIAGRAM_LAST_GENERATION: &str = "LAST_GENERATION";
+
+pub const LOG_TARGET_RESULT: &str = "plantuml_generator::result";
+
+pub const CACHE_LOCK: &str = ".pgen.lock";
+
+pub const CACHE_LOCK_TIMEOUT: &str = "60";
+
+pub const GENERATED_INDEX: &str = "generated-index.json";
+
+/// The directory of the output hosting the intermediate sprite icons, when they are kept.
+pub const DEBUG_DIRECTORY: &str = "debug";
+
+pub const RENDER_CACHE_DIRECTORY: &str = "render";
@@ -1,26 +1,112 @@
+use std::time::{Duration, Instant};
+
+/// The maximum delay between two progress messages.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
 #[derive(Debug)]
 pub struct Counter {
     total: usize,
     current: usize,
+    started_at: Instant,
+    logged_at: Instant,
 }
 
 impl Counter {
     pub fn start(total: usize) -> Counter {
         log::info!("start - {} tasks to execute", total);
-        Counter { total, current: 0 }
+        let now = Instant::now();
+        Counter {
+            total,
+            current: 0,
+            started_at: now,
+            logged_at: now,
+        }
     }
     pub fn increase(&mut self) {
         self.current += 1;
-        if self.current % 100_usize == 0 || self.current == self.total {
-            log::info!(
-                "progress - {}% - {}/{} tasks executed",
-                self.current * 100 / self.total,
-                self.current,
-                self.total,
-            )
+        if self.current.is_multiple_of(100_usize)
+            || self.current == self.total
+            || self.logged_at.elapsed() >= PROGRESS_INTERVAL
+        {
+            self.logged_at = Instant::now();
+            log::info!("{}", self.get_progress_message(self.started_at.elapsed()))
+        }
+    }
+    /// The number of executed tasks per second.
+    fn get_throughput(&self, elapsed: Duration) -> f64 {
+        match elapsed.as_secs_f64() {
+            secs if secs > 0.0 => self.current as f64 / secs,
+            _ => 0.0,
         }
     }
+    /// The estimated time to execute the remaining tasks, based on the running rate.
+    fn get_eta(&self, elapsed: Duration) -> Option<Duration> {
+        match self.current {
+            0 => None,
+            current => Some(elapsed.mul_f64((self.total - current) as f64 / current as f64)),
+        }
+    }
+    fn get_progress_message(&self, elapsed: Duration) -> String {
+        let eta = self
+            .get_eta(elapsed)
+            .map(|eta| format!("{:.2?}", eta))
+            .unwrap_or_else(|| "unknown".to_string());
+        format!(
+            "progress - {}% - {}/{} tasks executed - {:.1} tasks/s - ETA {}",
+            self.current * 100 / self.total,
+            self.current,
+            self.total,
+            self.get_throughput(elapsed),
+            eta,
+        )
+    }
+    fn get_stop_message(&self) -> String {
+        format!(
+            "stop - {} tasks have been executed in {:.2?}",
+            self.total,
+            self.started_at.elapsed()
+        )
+    }
     pub fn stop(&self) {
-        log::info!("stop - {} tasks have been executed", self.total)
+        log::info!("{}", self.get_stop_message())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::thread::sleep;
+
+    use super::*;
+
+    #[test]
+    fn test_stop_message() {
+        let mut counter = Counter::start(2);
+        counter.increase();
+        counter.increase();
+        sleep(Duration::from_millis(10));
+        let message = counter.get_stop_message();
+        assert!(message.starts_with("stop - 2 tasks have been executed in "));
+        assert!(message.ends_with("ms"));
+    }
+
+    #[test]
+    fn test_eta() {
+        let mut counter = Counter::start(4);
+        assert!(counter.get_eta(Duration::ZERO).is_none());
+        let mut previous_eta = Duration::MAX;
+        for step in 1..=4 {
+            counter.increase();
+            // one task per second
+            let elapsed = Duration::from_secs(step);
+            let eta = counter.get_eta(elapsed).unwrap();
+            assert!(eta < previous_eta);
+            assert_eq!(counter.get_throughput(elapsed), 1.0);
+            previous_eta = eta;
+        }
+        assert_eq!(previous_eta, Duration::ZERO);
+        assert_eq!(
+            counter.get_progress_message(Duration::from_secs(4)),
+            "progress - 100% - 4/4 tasks executed - 1.0 tasks/s - ETA 0.00ns"
+        );
     }
 }
@@ -0,0 +1,27 @@
+extern crate clap;
+
+pub use crate::app::start_app;
+
+mod app;
+mod cli;
+mod cmd;
+mod constants;
+mod counter;
+mod lock;
+mod network;
+mod plantuml;
+mod png;
+mod project;
+mod svg;
+mod tera;
+mod urn;
+mod utils;
+
+/// The generation of the PlantUML libraries, e.g. to embed it in a build tool.
+pub mod library {
+    pub use crate::cmd::library::generate::{
+        generate_library, plan_library, CleanupScope, Config, GenerateOptions,
+    };
+    pub use crate::cmd::library::manifest::library::{Library, ManifestFormat};
+    pub use crate::urn::Urn;
+}
@@ -0,0 +1,84 @@
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use clap::ArgMatches;
+use fs2::FileExt;
+
+use crate::constants::CACHE_LOCK;
+use crate::utils::create_directory;
+
+/// An advisory lock on the cache directory, released when dropped.
+#[derive(Debug)]
+pub struct CacheLock {
+    /// The opened lock file.
+    file: File,
+    /// The path to the lock file.
+    path: PathBuf,
+}
+
+impl CacheLock {
+    /// Wait for the lock of the cache directory until the timeout is reached.
+    pub fn acquire(cache_directory: &Path, timeout: Duration) -> Result<CacheLock> {
+        create_directory(cache_directory)?;
+        let path = cache_directory.join(CACHE_LOCK);
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .map_err(|e| {
+                anyhow::Error::new(e).context(format!("unable to open {}", path.display()))
+            })?;
+        let started_at = Instant::now();
+        while file.try_lock_exclusive().is_err() {
+            if started_at.elapsed() >= timeout {
+                return Err(anyhow::Error::msg(format!(
+                    "unable to lock {} after {}s, another generation may use the cache directory (see --no-lock)",
+                    path.display(),
+                    timeout.as_secs()
+                )));
+            }
+            sleep(Duration::from_millis(100));
+        }
+        log::debug!("lock acquired: {}", path.display());
+        Ok(CacheLock { file, path })
+    }
+}
+
+impl Drop for CacheLock {
+    fn drop(&mut self) {
+        if let Err(e) = self.file.unlock() {
+            log::warn!("unable to unlock {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// Lock the cache directory unless `--no-lock` is set.
+pub fn lock_cache_directory(args: &ArgMatches, cache_directory: &str) -> Result<Option<CacheLock>> {
+    if args.get_flag("do_skip_lock") {
+        return Ok(None);
+    }
+    let timeout = args
+        .get_one::<u64>("lock_timeout")
+        .copied()
+        .unwrap_or_default();
+    CacheLock::acquire(Path::new(cache_directory), Duration::from_secs(timeout)).map(Some)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_acquire() {
+        let cache_directory = Path::new("target/tests/lock/acquire");
+        let lock = CacheLock::acquire(cache_directory, Duration::from_secs(1)).unwrap();
+        let result = CacheLock::acquire(cache_directory, Duration::from_millis(200));
+        assert!(result.unwrap_err().to_string().contains("unable to lock"));
+        drop(lock);
+        CacheLock::acquire(cache_directory, Duration::from_millis(200)).unwrap();
+    }
+}
@@ -1,19 +1,7 @@
-extern crate clap;
-
 use std::env::args;
 use std::process::exit;
 
-use crate::app::start_app;
-
-mod app;
-mod cli;
-mod cmd;
-mod constants;
-mod counter;
-mod plantuml;
-mod tera;
-mod urn;
-mod utils;
+use plantuml_generator::start_app;
 
 fn main() {
     exit(start_app(args()))
@@ -0,0 +1,185 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use reqwest::blocking::{Client, Response};
+use reqwest::{NoProxy, Proxy};
+
+use crate::constants::DOWNLOAD_TIMEOUT;
+
+/// The client of the network operations, created once.
+static CLIENT: Mutex<Option<Client>> = Mutex::new(None);
+
+/// The value of an environment variable of the proxies, the lowercase one takes precedence like curl.
+fn get_proxy_var(get_var: &dyn Fn(&str) -> Option<String>, name: &str) -> Option<String> {
+    get_var(&name.to_lowercase())
+        .or_else(|| get_var(name))
+        .filter(|v| !v.is_empty())
+}
+
+/// Create the client, the explicit proxy takes precedence over `HTTP_PROXY`, `HTTPS_PROXY` and `ALL_PROXY`.
+///
+/// The hosts of `NO_PROXY` are always reached directly.
+/// The timeout applies to the connection and to each read, i.e. not to the whole download.
+fn create_client(
+    proxy: Option<&str>,
+    timeout: Duration,
+    get_var: &dyn Fn(&str) -> Option<String>,
+) -> Result<Client> {
+    let mut proxies = vec![];
+    match proxy {
+        Some(url) => proxies.push(Proxy::all(url)),
+        None => {
+            if let Some(url) = get_proxy_var(get_var, "HTTP_PROXY") {
+                proxies.push(Proxy::http(url));
+            }
+            if let Some(url) = get_proxy_var(get_var, "HTTPS_PROXY") {
+                proxies.push(Proxy::https(url));
+            }
+            if let Some(url) = get_proxy_var(get_var, "ALL_PROXY") {
+                proxies.push(Proxy::all(url));
+            }
+        }
+    }
+    let no_proxy = get_proxy_var(get_var, "NO_PROXY").and_then(|v| NoProxy::from_string(&v));
+    // the proxies of the environment are resolved above, i.e. not by reqwest
+    let mut builder = Client::builder()
+        .no_proxy()
+        .connect_timeout(timeout)
+        .timeout(timeout);
+    for proxy in proxies {
+        let proxy =
+            proxy.map_err(|e| anyhow::Error::new(e).context("unable to configure the proxy"))?;
+        builder = builder.proxy(proxy.no_proxy(no_proxy.clone()));
+    }
+    builder
+        .build()
+        .map_err(|e| anyhow::Error::new(e).context("unable to create the HTTP client"))
+}
+
+/// Create the client of the network operations, e.g. from `--proxy` and `--download-timeout`.
+pub fn configure_network(proxy: Option<&str>, timeout: Option<Duration>) -> Result<()> {
+    let client = create_client(
+        proxy,
+        timeout.unwrap_or(Duration::from_secs(DOWNLOAD_TIMEOUT)),
+        &|name| std::env::var(name).ok(),
+    )?;
+    *CLIENT.lock().unwrap_or_else(|e| e.into_inner()) = Some(client);
+    Ok(())
+}
+
+/// The client of the network operations, configured from the environment unless configured.
+fn get_client() -> Result<Client> {
+    let mut client = CLIENT.lock().unwrap_or_else(|e| e.into_inner());
+    if client.is_none() {
+        *client = Some(create_client(
+            None,
+            Duration::from_secs(DOWNLOAD_TIMEOUT),
+            &|name| std::env::var(name).ok(),
+        )?);
+    }
+    // the client is a reference counted handle
+    Ok(client.clone().unwrap())
+}
+
+/// The failure of a download, the timeouts are reported as such.
+pub fn to_download_error(error: reqwest::Error, url: &str) -> anyhow::Error {
+    let message = match error.is_timeout() {
+        true => format!("the download of {} timed out", url),
+        false => format!("unable to download {}", url),
+    };
+    anyhow::Error::new(error).context(message)
+}
+
+fn send(client: &Client, url: &str) -> Result<Response> {
+    client
+        .get(url)
+        .send()
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| to_download_error(e, url))
+}
+
+/// Request the URL with the client of the network operations, the error statuses are failures.
+pub fn download(url: &str) -> Result<Response> {
+    send(&get_client()?, url)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    use super::*;
+
+    /// Accept a single request and answer it, the request line is returned.
+    fn start_proxy() -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request_line = String::new();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            reader.read_line(&mut request_line).unwrap();
+            // skip the headers
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+                .unwrap();
+            request_line.trim().to_string()
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn test_proxy_from_env() {
+        let (proxy_url, handle) = start_proxy();
+        let env = HashMap::from([("HTTP_PROXY".to_string(), proxy_url)]);
+        let client =
+            create_client(None, Duration::from_secs(5), &|name| env.get(name).cloned()).unwrap();
+        let body = client
+            .get("http://plantuml.invalid/plantuml.jar")
+            .send()
+            .unwrap()
+            .text()
+            .unwrap();
+        assert_eq!(body, "ok");
+        assert_eq!(
+            handle.join().unwrap(),
+            "GET http://plantuml.invalid/plantuml.jar HTTP/1.1"
+        );
+    }
+
+    #[test]
+    fn test_explicit_proxy() {
+        let (proxy_url, handle) = start_proxy();
+        let env = HashMap::from([("HTTP_PROXY".to_string(), "http://127.0.0.1:1".to_string())]);
+        let client = create_client(Some(&proxy_url), Duration::from_secs(5), &|name| {
+            env.get(name).cloned()
+        })
+        .unwrap();
+        client.get("http://plantuml.invalid/").send().unwrap();
+        assert!(handle.join().unwrap().contains("http://plantuml.invalid/"));
+        assert!(create_client(Some("not a url"), Duration::from_secs(5), &|_| None).is_err());
+    }
+
+    #[test]
+    fn test_download_timeout() {
+        // the connection is accepted by the system but nothing is answered
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/plantuml.jar", listener.local_addr().unwrap());
+        let client = create_client(None, Duration::from_secs(1), &|_| None).unwrap();
+        let started_at = std::time::Instant::now();
+        let error = send(&client, &url).unwrap_err();
+        assert!(started_at.elapsed() < Duration::from_secs(10));
+        assert_eq!(
+            error.to_string(),
+            format!("the download of {} timed out", url)
+        );
+        drop(listener);
+    }
+}
@@ -1,13 +1,273 @@
-use std::ffi::OsString;
-use std::fs::File;
+use std::ffi::{OsStr, OsString};
 use std::io;
-use std::io::Write;
-use std::path::Path;
-use std::process::Command;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Output, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
-use crate::utils::create_parent_directory;
+use crate::constants::{get_default_plantuml_retries, MINIMUM_PLANTUML_VERSION};
+use crate::network::{download, to_download_error};
+use crate::utils::{create_parent_directory, delete_file, write_file_atomically};
 use anyhow::Result;
 
+/// True when the process didn't exit by itself, e.g. the JVM has been killed by the OOM killer.
+fn is_killed(status: &ExitStatus) -> bool {
+    matches!(status.code(), None | Some(137))
+}
+
+/// The delay between two checks of a process executed with a timeout.
+const WAIT_INTERVAL: Duration = Duration::from_millis(50);
+
+/// True when the failure is caused by a process killed after its timeout.
+pub fn is_timeout(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause
+            .downcast_ref::<io::Error>()
+            .is_some_and(|e| e.kind() == io::ErrorKind::TimedOut)
+    })
+}
+
+/// The slots of the Java processes running at once.
+struct JavaSlots {
+    /// The maximum number of Java processes running at once.
+    limit: AtomicUsize,
+    /// The number of Java processes running.
+    used: Mutex<usize>,
+    /// Notified when a Java process ends.
+    released: Condvar,
+}
+
+/// A slot to run a Java process, it is released when dropped.
+struct JavaSlot<'a> {
+    slots: &'a JavaSlots,
+}
+
+impl JavaSlots {
+    const fn new(limit: usize) -> JavaSlots {
+        JavaSlots {
+            limit: AtomicUsize::new(limit),
+            used: Mutex::new(0),
+            released: Condvar::new(),
+        }
+    }
+    fn set_limit(&self, limit: usize) {
+        self.limit.store(limit.max(1), Ordering::SeqCst);
+        self.released.notify_all();
+    }
+    /// Wait until a Java process can be started.
+    fn acquire(&self) -> JavaSlot<'_> {
+        let mut used = self.used.lock().unwrap_or_else(|e| e.into_inner());
+        while *used >= self.limit.load(Ordering::SeqCst) {
+            used = self.released.wait(used).unwrap_or_else(|e| e.into_inner());
+        }
+        *used += 1;
+        JavaSlot { slots: self }
+    }
+}
+
+impl Drop for JavaSlot<'_> {
+    fn drop(&mut self) {
+        let mut used = self.slots.used.lock().unwrap_or_else(|e| e.into_inner());
+        *used -= 1;
+        self.slots.released.notify_one();
+    }
+}
+
+/// The slots shared by all the jobs of the process, unlimited by default.
+static JAVA_SLOTS: JavaSlots = JavaSlots::new(usize::MAX);
+
+/// Limit the number of Java processes running at once, each JVM reserves its heap.
+pub fn set_java_concurrency(limit: usize) {
+    JAVA_SLOTS.set_limit(limit);
+}
+
+/// The failure of PlantUML to render a source, e.g. because of a syntax error.
+#[derive(Debug)]
+pub struct RenderError {
+    /// The rendered subject, e.g. the path of the source.
+    pub subject: String,
+}
+
+impl std::fmt::Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "failed to render {}", self.subject)
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+/// Collect the output of a process, the pipes are drained while the process is running.
+fn read_pipe<R: Read + Send + 'static>(pipe: Option<R>) -> JoinHandle<io::Result<Vec<u8>>> {
+    thread::spawn(move || {
+        let mut bytes = vec![];
+        if let Some(mut pipe) = pipe {
+            pipe.read_to_end(&mut bytes)?;
+        }
+        Ok(bytes)
+    })
+}
+
+/// Execute the command, the process is killed when it lasts longer than the timeout.
+fn output_with_timeout(command: &mut Command, timeout: Option<Duration>) -> io::Result<Output> {
+    let timeout = match timeout {
+        None => return command.output(),
+        Some(timeout) => timeout,
+    };
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+    let started_at = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if started_at.elapsed() >= timeout {
+            child.kill()?;
+            child.wait()?;
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("the process has been killed after {:?}", timeout),
+            ));
+        }
+        thread::sleep(WAIT_INTERVAL);
+    };
+    let join = |handle: JoinHandle<io::Result<Vec<u8>>>| {
+        handle
+            .join()
+            .map_err(|_| io::Error::other("unable to read the output of the process"))?
+    };
+    Ok(Output {
+        status,
+        stdout: join(stdout)?,
+        stderr: join(stderr)?,
+    })
+}
+
+/// Execute the PlantUML jar, the transient failures are retried.
+///
+/// A failure is transient when the process cannot be spawned or is killed.
+/// The exit codes reported by PlantUML, e.g. on syntax errors, are deterministic and not retried,
+/// neither are the timeouts.
+pub fn execute_plantuml<S: AsRef<OsStr>>(
+    java_binary: &str,
+    plantuml_jar: &str,
+    args: &[S],
+    retries: u32,
+    timeout: Option<Duration>,
+) -> io::Result<Output> {
+    execute_plantuml_in_slots(
+        &JAVA_SLOTS,
+        java_binary,
+        plantuml_jar,
+        args,
+        retries,
+        timeout,
+    )
+}
+
+/// Execute the PlantUML jar once a slot is available, the slot is kept during an attempt.
+fn execute_plantuml_in_slots<S: AsRef<OsStr>>(
+    slots: &JavaSlots,
+    java_binary: &str,
+    plantuml_jar: &str,
+    args: &[S],
+    retries: u32,
+    timeout: Option<Duration>,
+) -> io::Result<Output> {
+    let mut attempt = 0;
+    loop {
+        let slot = slots.acquire();
+        let result = output_with_timeout(
+            Command::new(java_binary)
+                .arg("-jar")
+                .arg(plantuml_jar)
+                .args(args),
+            timeout,
+        );
+        drop(slot);
+        let is_transient = match &result {
+            Err(e) => e.kind() != io::ErrorKind::TimedOut,
+            Ok(output) => is_killed(&output.status),
+        };
+        if !is_transient || attempt >= retries {
+            return result;
+        }
+        attempt += 1;
+        log::warn!(
+            "the execution of PlantUML failed, retry {}/{}",
+            attempt,
+            retries
+        );
+    }
+}
+
+/// The version reported by `-version`, e.g. `1.2022.4` from `PlantUML version 1.2022.4 (...)`.
+fn parse_version(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix("PlantUML version ")
+            .and_then(|v| v.split_whitespace().next())
+            .map(String::from)
+    })
+}
+
+/// The numeric components of a version, e.g. `[1, 2022, 4]`, the suffixes are ignored.
+fn to_components(version: &str) -> Vec<u32> {
+    version
+        .split('.')
+        .map_while(|component| {
+            let digits: String = component.chars().take_while(char::is_ascii_digit).collect();
+            digits.parse().ok()
+        })
+        .collect()
+}
+
+/// The problems of the running version, i.e. below the minimum or another release year than the configured one.
+fn get_version_warnings(version: &str, configured_version: &str) -> Vec<String> {
+    let mut warnings = vec![];
+    let components = to_components(version);
+    if components < to_components(MINIMUM_PLANTUML_VERSION) {
+        warnings.push(format!(
+            "the PlantUML version {} is older than {}, the generated artifacts may be broken",
+            version, MINIMUM_PLANTUML_VERSION
+        ));
+    }
+    // e.g. 1.2022.4 and 1.2022.7 are compatible but not 1.2022.4 and 1.2024.7
+    let configured_components = to_components(configured_version);
+    if components
+        .iter()
+        .take(2)
+        .ne(configured_components.iter().take(2))
+    {
+        warnings.push(format!(
+            "the PlantUML version {} differs from the configured one {}",
+            version, configured_version
+        ));
+    }
+    warnings
+}
+
+/// The warnings and the errors printed by PlantUML, e.g. `Warning: no image in a.puml`.
+pub fn find_problems(stderr: &str) -> Vec<String> {
+    stderr
+        .lines()
+        .map(str::trim)
+        .filter(|line| {
+            let line = line.to_lowercase();
+            line.starts_with("warning") || line.starts_with("error")
+        })
+        .map(String::from)
+        .collect()
+}
+
 #[derive(Debug)]
 pub struct PlantUML {
     /// The command/path of the java binary.
@@ -16,10 +276,21 @@ pub struct PlantUML {
     plantuml_jar: String,
     /// The path of the PlantUML jar.
     plantuml_version: String,
+    /// The number of retries when PlantUML is killed or cannot be started.
+    retries: u32,
+    /// The layout engine, e.g. `smetana` to render without Graphviz.
+    layout: Option<String>,
+    /// The maximum duration of a rendering, unlimited when none.
+    timeout: Option<Duration>,
 }
 
 impl PlantUML {
-    pub fn render(&self, source_path: &Path, p_args_as_strings: Option<Vec<String>>) -> Result<()> {
+    /// Render a source, the standard error of PlantUML is returned to surface its warnings.
+    pub fn render(
+        &self,
+        source_path: &Path,
+        p_args_as_strings: Option<Vec<String>>,
+    ) -> Result<String> {
         //get the source
         let source = match source_path.to_str() {
             None => {
@@ -31,27 +302,118 @@ impl PlantUML {
             Some(s) => s,
         };
         // generate the file
-        let p_args = p_args_as_strings.map(|strings| {
-            strings
-                .iter()
-                .map(OsString::from)
-                .collect::<Vec<OsString>>()
-        });
-        let output = Command::new(&self.java_binary)
-            .arg("-jar")
-            .arg(&self.plantuml_jar)
-            .arg(source)
-            .args(p_args.unwrap_or_default())
-            .output()
-            .map_err(|e| anyhow::Error::new(e).context(format!("unable to render {}", source)))?;
+        let mut args = vec![OsString::from(source)];
+        args.extend(self.get_layout_args().into_iter().map(OsString::from));
+        for p_arg in p_args_as_strings.unwrap_or_default() {
+            args.push(OsString::from(p_arg));
+        }
+        let output = execute_plantuml(
+            &self.java_binary,
+            &self.plantuml_jar,
+            &args,
+            self.retries,
+            self.timeout,
+        )
+        .map_err(|e| anyhow::Error::new(e).context(format!("unable to render {}", source)))?;
         io::stdout().write_all(&output.stdout)?;
         io::stderr().write_all(&output.stderr)?;
         // check the generation
         if !output.status.success() {
-            return Err(anyhow::Error::msg(format!("failed to render {}", source)));
+            return Err(anyhow::Error::new(RenderError {
+                subject: source.to_string(),
+            }));
         }
 
-        Ok(())
+        Ok(String::from_utf8_lossy(&output.stderr).to_string())
+    }
+    /// Render a single source through the pipe mode of PlantUML, the diagram is returned.
+    pub fn pipe(&self, source: &[u8], format: &str) -> Result<Vec<u8>> {
+        let _slot = JAVA_SLOTS.acquire();
+        let mut child = Command::new(&self.java_binary)
+            .arg("-jar")
+            .arg(&self.plantuml_jar)
+            .arg("-pipe")
+            .arg(format!("-t{}", format))
+            .args(self.get_layout_args())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| anyhow::Error::new(e).context("unable to start PlantUML"))?;
+        // the source is written from another thread to avoid a deadlock on large diagrams
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| anyhow::Error::msg("unable to get the input of PlantUML"))?;
+        let source = source.to_vec();
+        let writer = thread::spawn(move || stdin.write_all(&source));
+        let output = child
+            .wait_with_output()
+            .map_err(|e| anyhow::Error::new(e).context("unable to render the source"))?;
+        writer
+            .join()
+            .map_err(|_| anyhow::Error::msg("unable to write the source"))?
+            .map_err(|e| anyhow::Error::new(e).context("unable to write the source"))?;
+        // check the generation
+        if !output.status.success() {
+            io::stderr().write_all(&output.stderr)?;
+            return Err(anyhow::Error::new(RenderError {
+                subject: "the source".to_string(),
+            }));
+        }
+        Ok(output.stdout)
+    }
+    /// The version of the PlantUML jar, as reported by the jar itself.
+    pub fn version(&self) -> Result<String> {
+        let output = execute_plantuml(
+            &self.java_binary,
+            &self.plantuml_jar,
+            &["-version"],
+            self.retries,
+            self.timeout,
+        )
+        .map_err(|e| anyhow::Error::new(e).context("unable to get the version of PlantUML"))?;
+        parse_version(&String::from_utf8_lossy(&output.stdout)).ok_or_else(|| {
+            anyhow::Error::msg(format!(
+                "unable to parse the version of {}",
+                self.plantuml_jar
+            ))
+        })
+    }
+    /// Warn when the version of the jar is too old or differs from the configured one.
+    pub fn check_version(&self) -> Result<Vec<String>> {
+        let version = self.version()?;
+        log::info!("the PlantUML version is {}", version);
+        let warnings = get_version_warnings(&version, &self.plantuml_version);
+        for warning in &warnings {
+            log::warn!("{}", warning);
+        }
+        Ok(warnings)
+    }
+    /// Override the number of retries when PlantUML is killed or cannot be started.
+    pub fn with_retries(self, retries: u32) -> PlantUML {
+        PlantUML { retries, ..self }
+    }
+    /// Override the layout engine, PlantUML selects it when none.
+    pub fn with_layout(self, layout: Option<String>) -> PlantUML {
+        PlantUML { layout, ..self }
+    }
+    /// Override the maximum duration of a rendering, unlimited when none.
+    pub fn with_timeout(self, timeout: Option<Duration>) -> PlantUML {
+        PlantUML { timeout, ..self }
+    }
+    /// The arguments selecting the layout engine, if any.
+    pub fn get_layout_args(&self) -> Vec<String> {
+        self.layout
+            .iter()
+            .map(|layout| format!("-Playout={}", layout))
+            .collect()
+    }
+    /// The arguments identifying a rendering, i.e. the jar, its version and the layout engine.
+    pub fn get_cache_args(&self) -> Vec<String> {
+        let mut args = vec![self.plantuml_jar.clone(), self.plantuml_version.clone()];
+        args.extend(self.get_layout_args());
+        args
     }
     pub fn download(&self) -> Result<()> {
         // https://github.com/plantuml/plantuml/releases/download/v1.2024.7/plantuml-1.2024.7.jar
@@ -68,20 +430,43 @@ impl PlantUML {
 
         create_parent_directory(destination_path)?;
 
-        let mut destination_file = File::create(destination_path).map_err(|e| {
-            anyhow::Error::new(e).context(format!("unable to open {}", &self.plantuml_jar))
-        })?;
-
         log::info!("download the PlantUML jar from {}", url);
-        reqwest::blocking::get(&url)
-            .map_err(|e| anyhow::Error::new(e).context(format!("unable to download {}", &url)))?
-            .copy_to(&mut destination_file)
-            .map_err(|e| {
-                anyhow::Error::new(e).context(format!("unable to write {}", &self.plantuml_jar))
-            })?;
+        download_file(&url, destination_path)
+    }
+}
+
+/// Download a file, the file is only created once the download is complete.
+fn download_file(url: &str, destination_path: &Path) -> Result<()> {
+    let mut response = download(url)?;
+    write_file_atomically(destination_path, |destination_file| {
+        response
+            .copy_to(destination_file)
+            .map(|_| ())
+            .map_err(|e| match e.is_timeout() {
+                true => to_download_error(e, url),
+                false => anyhow::Error::new(e)
+                    .context(format!("unable to write {}", destination_path.display())),
+            })
+    })
+}
 
-        Ok(())
+/// Delete the PlantUML jars of the cache directory but the active one, return the pruned jars.
+pub fn prune_jars(cache_directory: &str, active_jar: &str) -> Result<Vec<PathBuf>> {
+    let active_jar_path = Path::new(active_jar).canonicalize().ok();
+    let pattern = Path::new(cache_directory).join("plantuml-*.jar");
+    let mut pruned_jars: Vec<PathBuf> = vec![];
+    for jar_path in glob::glob(&pattern.to_string_lossy())
+        .map_err(|e| anyhow::Error::new(e).context("unable to search the PlantUML jars"))?
+        .flatten()
+    {
+        if jar_path.canonicalize().ok() == active_jar_path {
+            continue;
+        }
+        log::info!("prune the PlantUML jar {}", jar_path.display());
+        delete_file(&jar_path)?;
+        pruned_jars.push(jar_path);
     }
+    Ok(pruned_jars)
 }
 
 pub fn create_plantuml(
@@ -93,13 +478,16 @@ pub fn create_plantuml(
         java_binary: java_binary.to_string(),
         plantuml_jar: plantuml_jar.to_string(),
         plantuml_version: plantuml_version.to_string(),
+        retries: get_default_plantuml_retries(),
+        layout: None,
+        timeout: None,
     })
 }
 
 #[cfg(test)]
 mod tests {
     use crate::constants::{JAVA_BINARY, PLANTUML_VERSION};
-    use crate::utils::delete_file;
+    use crate::utils::{create_directory, delete_file, delete_file_or_directory};
 
     use super::*;
 
@@ -109,8 +497,216 @@ mod tests {
             java_binary: JAVA_BINARY.to_string(),
             plantuml_jar: "target/plantuml.jar".to_string(),
             plantuml_version: PLANTUML_VERSION.to_string(),
+            retries: 0,
+            layout: None,
+            timeout: None,
         };
         delete_file(Path::new(&plantuml.plantuml_jar)).unwrap_or_default();
         plantuml.download().expect("the download fails");
     }
+
+    #[test]
+    fn test_download_truncated() {
+        use std::io::{BufRead, BufReader};
+        use std::net::TcpListener;
+
+        let destination_path = Path::new("target/tests/plantuml/truncated/plantuml.jar");
+        delete_file_or_directory(destination_path.parent().unwrap()).unwrap();
+        create_parent_directory(destination_path).unwrap();
+        // the connection is closed before the announced content is sent
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/plantuml.jar", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 100\r\n\r\npartial")
+                .unwrap();
+        });
+        assert!(download_file(&url, destination_path).is_err());
+        handle.join().unwrap();
+        assert!(!destination_path.exists());
+        assert_eq!(
+            std::fs::read_dir(destination_path.parent().unwrap())
+                .unwrap()
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_prune_jars() {
+        let cache_directory = Path::new("target/tests/plantuml/prune_jars");
+        delete_file_or_directory(cache_directory).unwrap();
+        create_directory(cache_directory).unwrap();
+        let old_jar = cache_directory.join("plantuml-1.2022.4.jar");
+        let active_jar = cache_directory.join("plantuml-1.2024.7.jar");
+        let other_file = cache_directory.join("LAST_GENERATION");
+        for path in [&old_jar, &active_jar, &other_file] {
+            std::fs::write(path, "").unwrap();
+        }
+        let pruned_jars = prune_jars(
+            &cache_directory.to_string_lossy(),
+            &active_jar.to_string_lossy(),
+        )
+        .unwrap();
+        assert_eq!(pruned_jars, vec![old_jar.clone()]);
+        assert!(!old_jar.exists());
+        assert!(active_jar.exists());
+        assert!(other_file.exists());
+    }
+
+    #[cfg(unix)]
+    fn create_fake_java(name: &str, script: &str) -> String {
+        use std::os::unix::fs::PermissionsExt;
+        let path = Path::new("target/tests/plantuml").join(name);
+        create_parent_directory(&path).unwrap();
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_render_with_layout() {
+        let args_path = "target/tests/plantuml/layout.args";
+        delete_file(Path::new(args_path)).unwrap();
+        let java_binary = create_fake_java("layout.sh", &format!("echo \"$@\" > {}", args_path));
+        let plantuml = create_plantuml(&java_binary, "plantuml.jar", PLANTUML_VERSION)
+            .unwrap()
+            .with_layout(Some("smetana".to_string()));
+        plantuml.render(Path::new("a.puml"), None).unwrap();
+        let args = std::fs::read_to_string(args_path).unwrap();
+        assert!(args.contains("a.puml -Playout=smetana"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_retry_killed_process() {
+        let marker = "target/tests/plantuml/killed_once.marker";
+        delete_file(Path::new(marker)).unwrap();
+        let java_binary = create_fake_java(
+            "killed_once.sh",
+            &format!(
+                "if [ ! -f {0} ]; then touch {0}; kill -9 $$; fi\necho rendered",
+                marker
+            ),
+        );
+        let output = execute_plantuml(&java_binary, "plantuml.jar", &["a.puml"], 2, None).unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "rendered");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_no_retry_on_plantuml_error() {
+        let calls = "target/tests/plantuml/syntax_error.calls";
+        delete_file(Path::new(calls)).unwrap();
+        let java_binary = create_fake_java(
+            "syntax_error.sh",
+            &format!("echo call >> {}\nexit 200", calls),
+        );
+        let output = execute_plantuml(&java_binary, "plantuml.jar", &["a.puml"], 2, None).unwrap();
+        assert!(!output.status.success());
+        assert_eq!(std::fs::read_to_string(calls).unwrap().lines().count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_java_concurrency() {
+        let log_path = "target/tests/plantuml/java_concurrency.log";
+        delete_file(Path::new(log_path)).unwrap();
+        let java_binary = create_fake_java(
+            "java_concurrency.sh",
+            &format!("echo start >> {0}\nsleep 0.2\necho end >> {0}", log_path),
+        );
+        let slots = &JavaSlots::new(2);
+        thread::scope(|scope| {
+            for _ in 0..6 {
+                scope.spawn(|| {
+                    execute_plantuml_in_slots(
+                        slots,
+                        &java_binary,
+                        "plantuml.jar",
+                        &["a.puml"],
+                        0,
+                        None,
+                    )
+                    .unwrap()
+                });
+            }
+        });
+        let mut running = 0;
+        let mut max_running = 0;
+        for line in std::fs::read_to_string(log_path).unwrap().lines() {
+            match line {
+                "start" => running += 1,
+                _ => running -= 1,
+            }
+            max_running = max_running.max(running);
+        }
+        assert_eq!(max_running, 2);
+    }
+
+    #[test]
+    fn test_get_version_warnings() {
+        assert_eq!(
+            parse_version("PlantUML version 1.2022.4 (Sat Apr 09 13:29:17 UTC 2022)\n(GPL source distribution)"),
+            Some("1.2022.4".to_string())
+        );
+        assert!(get_version_warnings("1.2024.7", "1.2024.7").is_empty());
+        assert!(get_version_warnings("1.2024.8beta2", "1.2024.7").is_empty());
+        let warnings = get_version_warnings("1.2022.4", "1.2024.7");
+        assert_eq!(
+            warnings,
+            vec!["the PlantUML version 1.2022.4 differs from the configured one 1.2024.7"]
+        );
+        assert_eq!(get_version_warnings("1.2019.1", "1.2019.1").len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_check_version() {
+        let java_binary = create_fake_java(
+            "version.sh",
+            "echo 'PlantUML version 1.2019.1 (Sun Jan 13 18:00:00 UTC 2019)'",
+        );
+        let plantuml = create_plantuml(&java_binary, "plantuml.jar", "1.2024.7").unwrap();
+        assert_eq!(plantuml.version().unwrap(), "1.2019.1");
+        let warnings = plantuml.check_version().unwrap();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("older than"));
+        let java_binary = create_fake_java("no_version.sh", "echo 'unknown'");
+        let plantuml = create_plantuml(&java_binary, "plantuml.jar", "1.2024.7").unwrap();
+        assert!(plantuml.check_version().is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_render_timeout() {
+        let java_binary = create_fake_java("sleep.sh", "exec sleep 10");
+        let plantuml = create_plantuml(&java_binary, "plantuml.jar", PLANTUML_VERSION)
+            .unwrap()
+            .with_timeout(Some(Duration::from_millis(500)));
+        let started_at = Instant::now();
+        let error = plantuml.render(Path::new("a.puml"), None).unwrap_err();
+        assert!(is_timeout(&error));
+        assert!(started_at.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_find_problems() {
+        let stderr = "[Log] hello\nWarning: no image in a.puml\nNo diagram found\nError line 2 in file: b.puml\n";
+        assert_eq!(
+            find_problems(stderr),
+            vec![
+                "Warning: no image in a.puml",
+                "Error line 2 in file: b.puml"
+            ]
+        );
+    }
 }
@@ -0,0 +1,93 @@
+use std::fs::{read, write};
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::Result;
+use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+use image::ImageFormat;
+
+/// The name of the optimizer implemented by the tool itself.
+pub const BUILTIN_PNG_OPTIMIZER: &str = "builtin";
+
+/// Encode again the PNG with the best compression and the adaptive filters, the pixels are kept as is.
+pub fn optimize_png(content: &[u8]) -> Result<Vec<u8>> {
+    let image = image::load_from_memory_with_format(content, ImageFormat::Png)?;
+    let mut optimized = vec![];
+    let encoder =
+        PngEncoder::new_with_quality(&mut optimized, CompressionType::Best, FilterType::Adaptive);
+    image.write_with_encoder(encoder)?;
+    Ok(optimized)
+}
+
+/// Optimize losslessly in place a PNG file, with the built-in optimizer or with an oxipng-like binary.
+///
+/// The built-in optimizer keeps the file when it is already smaller, e.g. when it uses a palette.
+pub fn optimize_png_file(png_path: &Path, optimizer: &str) -> Result<()> {
+    log::debug!("optimize {} with {}", png_path.display(), optimizer);
+    if optimizer == BUILTIN_PNG_OPTIMIZER {
+        let content = read(png_path).map_err(|e| {
+            anyhow::Error::new(e).context(format!("unable to read {}", png_path.display()))
+        })?;
+        let optimized = optimize_png(&content)
+            .map_err(|e| e.context(format!("unable to optimize {}", png_path.display())))?;
+        if optimized.len() >= content.len() {
+            return Ok(());
+        }
+        return write(png_path, optimized).map_err(|e| {
+            anyhow::Error::new(e).context(format!("unable to write {}", png_path.display()))
+        });
+    }
+    // oxipng optimizes the files in place
+    let output = Command::new(optimizer)
+        .arg(png_path)
+        .output()
+        .map_err(|e| {
+            anyhow::Error::new(e).context(format!("unable to optimize {}", png_path.display()))
+        })?;
+    if !output.status.success() {
+        io::stderr().write_all(&output.stderr)?;
+        return Err(anyhow::Error::msg(format!(
+            "failed to optimize {}",
+            png_path.display()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::utils::create_parent_directory;
+
+    use super::*;
+
+    #[test]
+    fn test_optimize_png_file() {
+        let png_path = Path::new("target/tests/png/icon.png");
+        create_parent_directory(png_path).unwrap();
+        // the icon is saved without compression nor filter
+        let original = image::open("test/original_icon.png").unwrap();
+        let mut unoptimized = vec![];
+        let encoder = PngEncoder::new_with_quality(
+            &mut unoptimized,
+            CompressionType::Fast,
+            FilterType::NoFilter,
+        );
+        original.write_with_encoder(encoder).unwrap();
+        write(png_path, &unoptimized).unwrap();
+
+        optimize_png_file(png_path, BUILTIN_PNG_OPTIMIZER).unwrap();
+
+        let optimized = read(png_path).unwrap();
+        assert!(optimized.len() < unoptimized.len());
+        let optimized = image::load_from_memory(&optimized).unwrap();
+        assert_eq!(
+            (optimized.width(), optimized.height()),
+            (original.width(), original.height())
+        );
+        assert_eq!(optimized.to_rgba8(), original.to_rgba8());
+
+        assert!(optimize_png_file(png_path, "target/tests/png/missing-oxipng").is_err());
+    }
+}
@@ -0,0 +1,107 @@
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use clap::ArgMatches;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::PROJECT_CONFIG;
+
+/// The values of the project config file used to seed the commands' configuration.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ProjectConfig {
+    /// The path to the output directory.
+    #[serde(default)]
+    pub output_directory: Option<String>,
+    /// The path to the cache directory.
+    #[serde(default)]
+    pub cache_directory: Option<String>,
+    /// The PlantUML version.
+    #[serde(default)]
+    pub plantuml_version: Option<String>,
+    /// The path to the PlantUML jar.
+    #[serde(default)]
+    pub plantuml_jar: Option<String>,
+    /// The path to the java binary.
+    #[serde(default)]
+    pub java_binary: Option<String>,
+    /// The path to the inkscape binary.
+    #[serde(default)]
+    pub inkscape_binary: Option<String>,
+    /// The number of threads generating the library.
+    #[serde(default)]
+    pub threads: Option<usize>,
+}
+
+/// Resolve a relative path from the directory of the project config file.
+fn rebase(directory: &Path, path: Option<String>) -> Option<String> {
+    path.map(|path| directory.join(path).to_string_lossy().to_string())
+}
+
+/// Find the project config file in the given directory or in one of its ancestors.
+fn discover(directory: &Path) -> Option<PathBuf> {
+    directory
+        .ancestors()
+        .map(|d| d.join(PROJECT_CONFIG))
+        .find(|p| p.is_file())
+}
+
+impl ProjectConfig {
+    pub fn read(path: &Path) -> Result<ProjectConfig> {
+        let yaml = read_to_string(path).map_err(|e| {
+            anyhow::Error::new(e).context(format!("unable to read {}", path.display()))
+        })?;
+        let project: ProjectConfig = serde_yaml_ok::from_str(&yaml).map_err(|e| {
+            anyhow::Error::new(e).context(format!("unable to parse {}", path.display()))
+        })?;
+        // the paths are relative to the file, e.g. when it is discovered from a subdirectory
+        let directory = path.parent().unwrap_or(Path::new(""));
+        Ok(ProjectConfig {
+            output_directory: rebase(directory, project.output_directory),
+            cache_directory: rebase(directory, project.cache_directory),
+            plantuml_jar: rebase(directory, project.plantuml_jar),
+            ..project
+        })
+    }
+    /// Load the file given by `--config` or the one discovered from the current directory.
+    pub fn load(args: &ArgMatches) -> Result<ProjectConfig> {
+        let path = match args.get_one::<String>("config_file") {
+            Some(v) => Some(PathBuf::from(v)),
+            None => std::env::current_dir()
+                .ok()
+                .and_then(|d| discover(d.as_path())),
+        };
+        match path {
+            None => Ok(ProjectConfig::default()),
+            Some(path) => {
+                log::info!("project config: {}", path.display());
+                ProjectConfig::read(&path)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::write;
+
+    use crate::utils::{create_directory, delete_file_or_directory};
+
+    use super::*;
+
+    #[test]
+    fn test_discover() {
+        let root = Path::new("target/tests/project/discover");
+        let nested = root.join("a/b");
+        delete_file_or_directory(root).unwrap();
+        create_directory(&nested).unwrap();
+        write(root.join(PROJECT_CONFIG), "cache_directory: a_cache").unwrap();
+        assert_eq!(discover(&nested).unwrap(), root.join(PROJECT_CONFIG));
+        let project = ProjectConfig::read(&discover(&nested).unwrap()).unwrap();
+        assert_eq!(
+            project.cache_directory.unwrap(),
+            root.join("a_cache").to_string_lossy()
+        );
+        assert!(project.output_directory.is_none());
+    }
+}
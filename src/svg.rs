@@ -0,0 +1,282 @@
+use std::fs::{read_to_string, write};
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::Result;
+
+/// The name of the optimizer implemented by the tool itself.
+pub const BUILTIN_SVG_OPTIMIZER: &str = "builtin";
+
+/// The prefixes of the elements and attributes only used by the editors.
+const EDITOR_PREFIXES: [&str; 5] = ["inkscape", "sodipodi", "rdf", "cc", "dc"];
+
+/// True when the element or the attribute is only used by the editors.
+fn is_editor_name(name: &str) -> bool {
+    match name.split_once(':') {
+        Some(("xmlns", prefix)) => EDITOR_PREFIXES.contains(&prefix),
+        Some((prefix, _)) => EDITOR_PREFIXES.contains(&prefix),
+        None => name == "metadata",
+    }
+}
+
+fn create_malformed_error() -> anyhow::Error {
+    anyhow::Error::msg("the SVG is malformed")
+}
+
+/// The position of the end of the tag starting the content.
+///
+/// The quoted values and the internal subset of a declaration are skipped,
+/// e.g. `<!DOCTYPE svg [<!ENTITY a "b">]>`.
+fn find_tag_end(content: &str) -> Result<usize> {
+    let mut quote: Option<char> = None;
+    let mut subset_depth = 0;
+    for (index, c) in content.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            (None, '[') => subset_depth += 1,
+            (None, ']') if subset_depth > 0 => subset_depth -= 1,
+            (None, '>') if subset_depth == 0 => return Ok(index),
+            _ => {}
+        }
+    }
+    Err(create_malformed_error())
+}
+
+/// True when the whitespaces of the element are rendered, e.g. between the `<tspan>` of a `<text>`.
+fn is_whitespace_preserved(name: &str, attributes: &[(&str, &str)]) -> bool {
+    let local_name = name.rsplit(':').next().unwrap_or(name);
+    local_name == "text"
+        || attributes.iter().any(|(name, value)| {
+            *name == "xml:space" && value.trim_matches(['"', '\'']) == "preserve"
+        })
+}
+
+/// The attributes of a tag, the values are kept with their quotes.
+fn parse_attributes(mut attributes: &str) -> Result<Vec<(&str, &str)>> {
+    let mut parsed = vec![];
+    loop {
+        attributes = attributes.trim_start();
+        if attributes.is_empty() {
+            return Ok(parsed);
+        }
+        let (name, rest) = attributes
+            .split_once('=')
+            .ok_or_else(create_malformed_error)?;
+        let rest = rest.trim_start();
+        let quote = rest.chars().next().ok_or_else(create_malformed_error)?;
+        let end = rest[1..].find(quote).ok_or_else(create_malformed_error)? + 2;
+        parsed.push((name.trim(), &rest[..end]));
+        attributes = &rest[end..];
+    }
+}
+
+/// Strip the comments, the editors' elements and attributes and the whitespaces between the tags.
+///
+/// The whitespaces of the texts and of the elements with `xml:space="preserve"` are kept.
+pub fn optimize_svg(content: &str) -> Result<String> {
+    let mut output = String::with_capacity(content.len());
+    let mut rest = content;
+    // the depth within a removed element, zero when the content is kept
+    let mut skipped_depth = 0;
+    // true for each open element keeping its whitespaces
+    let mut preserved: Vec<bool> = vec![];
+    while !rest.is_empty() {
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            let end = comment.find("-->").ok_or_else(create_malformed_error)?;
+            rest = &comment[end + 3..];
+        } else if rest.starts_with("<![CDATA[") {
+            let end = rest.find("]]>").ok_or_else(create_malformed_error)? + 3;
+            if skipped_depth == 0 {
+                output.push_str(&rest[..end]);
+            }
+            rest = &rest[end..];
+        } else if rest.starts_with("<?") || rest.starts_with("<!") {
+            let end = find_tag_end(rest)? + 1;
+            output.push_str(&rest[..end]);
+            rest = &rest[end..];
+        } else if rest.starts_with('<') {
+            let end = find_tag_end(rest)?;
+            let tag = &rest[1..end];
+            rest = &rest[end + 1..];
+            if let Some(name) = tag.strip_prefix('/') {
+                match skipped_depth {
+                    0 => {
+                        output.push_str(&format!("</{}>", name.trim()));
+                        preserved.pop();
+                    }
+                    _ => skipped_depth -= 1,
+                }
+                continue;
+            }
+            let is_self_closing = tag.ends_with('/');
+            let tag = tag.trim_end_matches('/');
+            let (name, attributes) = tag
+                .split_once(|c: char| c.is_whitespace())
+                .unwrap_or((tag, ""));
+            if skipped_depth > 0 || is_editor_name(name) {
+                if !is_self_closing {
+                    skipped_depth += 1;
+                }
+                continue;
+            }
+            let attributes = parse_attributes(attributes)?;
+            output.push('<');
+            output.push_str(name);
+            for (name, value) in &attributes {
+                if !is_editor_name(name) {
+                    output.push_str(&format!(" {}={}", name, value));
+                }
+            }
+            if is_self_closing {
+                output.push_str("/>");
+            } else {
+                output.push('>');
+                let is_parent_preserved = preserved.last().copied().unwrap_or(false);
+                preserved.push(is_parent_preserved || is_whitespace_preserved(name, &attributes));
+            }
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            let text = &rest[..end];
+            let is_preserved = preserved.last().copied().unwrap_or(false);
+            if skipped_depth == 0 && (is_preserved || !text.trim().is_empty()) {
+                output.push_str(text);
+            }
+            rest = &rest[end..];
+        }
+    }
+    Ok(output)
+}
+
+/// Optimize in place an SVG file, with the built-in optimizer or with an svgo-like binary.
+pub fn optimize_svg_file(svg_path: &Path, optimizer: &str) -> Result<()> {
+    log::debug!("optimize {} with {}", svg_path.display(), optimizer);
+    if optimizer == BUILTIN_SVG_OPTIMIZER {
+        let content = read_to_string(svg_path).map_err(|e| {
+            anyhow::Error::new(e).context(format!("unable to read {}", svg_path.display()))
+        })?;
+        let optimized = optimize_svg(&content)
+            .map_err(|e| e.context(format!("unable to optimize {}", svg_path.display())))?;
+        return write(svg_path, optimized).map_err(|e| {
+            anyhow::Error::new(e).context(format!("unable to write {}", svg_path.display()))
+        });
+    }
+    let output = Command::new(optimizer)
+        .arg(svg_path)
+        .arg("-o")
+        .arg(svg_path)
+        .output()
+        .map_err(|e| {
+            anyhow::Error::new(e).context(format!("unable to optimize {}", svg_path.display()))
+        })?;
+    if !output.status.success() {
+        io::stderr().write_all(&output.stderr)?;
+        return Err(anyhow::Error::msg(format!(
+            "failed to optimize {}",
+            svg_path.display()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::copy;
+
+    use crate::utils::create_parent_directory;
+
+    use super::*;
+
+    #[test]
+    fn test_optimize_svg() {
+        let svg = r##"<?xml version="1.0"?>
+<!-- Created with Inkscape -->
+<svg xmlns="http://www.w3.org/2000/svg" xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape" inkscape:version="1.0" width="10">
+  <sodipodi:namedview pagecolor="#ffffff" />
+  <metadata><rdf:RDF><cc:Work /></rdf:RDF></metadata>
+  <g inkscape:label="layer" id="g1">
+    <text x="1">a > b</text>
+  </g>
+</svg>
+"##;
+        assert_eq!(
+            optimize_svg(svg).unwrap(),
+            r#"<?xml version="1.0"?><svg xmlns="http://www.w3.org/2000/svg" width="10"><g id="g1"><text x="1">a > b</text></g></svg>"#
+        );
+    }
+
+    #[test]
+    fn test_optimize_svg_whitespaces() {
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg">
+  <text x="1"><tspan>a</tspan> <tspan>b</tspan></text>
+  <g xml:space="preserve"><desc>  </desc></g>
+</svg>"##;
+        let optimized = optimize_svg(svg).unwrap();
+        let document = roxmltree::Document::parse(&optimized).unwrap();
+        let text = document
+            .descendants()
+            .find(|node| node.has_tag_name("text"))
+            .unwrap();
+        let content: String = text
+            .descendants()
+            .filter(|node| node.is_text())
+            .filter_map(|node| node.text())
+            .collect::<Vec<&str>>()
+            .concat();
+        assert_eq!(content, "a b");
+        let desc = document
+            .descendants()
+            .find(|node| node.has_tag_name("desc"))
+            .unwrap();
+        assert_eq!(desc.text(), Some("  "));
+        assert!(!optimized.contains("\n"));
+    }
+
+    #[test]
+    fn test_optimize_svg_doctype() {
+        let svg = r##"<?xml version="1.0"?>
+<!DOCTYPE svg PUBLIC "-//W3C//DTD SVG 1.1//EN" "http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd" [
+  <!ENTITY ns_svg "http://www.w3.org/2000/svg">
+  <!ENTITY label "a > b">
+]>
+<svg xmlns="&ns_svg;">
+  <text x="1">&label;</text>
+</svg>"##;
+        let optimized = optimize_svg(svg).unwrap();
+        assert!(optimized.contains(r#"<!ENTITY label "a > b">"#));
+        let document = roxmltree::Document::parse_with_options(
+            &optimized,
+            roxmltree::ParsingOptions {
+                allow_dtd: true,
+                ..roxmltree::ParsingOptions::default()
+            },
+        )
+        .unwrap();
+        let text = document
+            .descendants()
+            .find(|node| node.has_tag_name("text"))
+            .unwrap();
+        assert_eq!(text.text(), Some("a > b"));
+    }
+
+    #[test]
+    fn test_optimize_svg_file() {
+        let svg_path = Path::new("target/tests/svg/MessageConstruction__MessageExpiration.svg");
+        create_parent_directory(svg_path).unwrap();
+        copy(
+            "test/raw/eip/MessageConstruction__MessageExpiration.svg",
+            svg_path,
+        )
+        .unwrap();
+        let original_size = svg_path.metadata().unwrap().len();
+        optimize_svg_file(svg_path, BUILTIN_SVG_OPTIMIZER).unwrap();
+        let optimized = read_to_string(svg_path).unwrap();
+        assert!((optimized.len() as u64) < original_size);
+        assert!(!optimized.contains("inkscape:"));
+        assert!(!optimized.contains("sodipodi:"));
+        assert!(!optimized.contains("<!--"));
+        roxmltree::Document::parse(&optimized).unwrap();
+    }
+}
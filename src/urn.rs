@@ -15,6 +15,9 @@ pub struct Urn {
     /// The latest component of the value.
     pub label: String,
     /// The relative path to the library's directory.
+    ///
+    /// One `..` per component, e.g. `a/b/c` gives `../../..` and `a` gives `..`.
+    /// An empty value is the library root itself and gives `.`.
     pub path_to_base: String,
 }
 
@@ -25,6 +28,42 @@ impl PartialEq for Urn {
 }
 
 impl Urn {
+    /// Create a URN from a string value rejecting malformed values.
+    pub fn parse(value: &str) -> anyhow::Result<Urn> {
+        if value.is_empty() {
+            return Err(anyhow::Error::msg("the URN is empty"));
+        }
+        if value.starts_with('/') || value.ends_with('/') {
+            return Err(anyhow::Error::msg(format!(
+                "the URN `{}` must not start or end with a slash",
+                value
+            )));
+        }
+        for component in value.split('/') {
+            if component.is_empty() {
+                return Err(anyhow::Error::msg(format!(
+                    "the URN `{}` must not contain an empty component",
+                    value
+                )));
+            }
+            if component == "." || component == ".." {
+                return Err(anyhow::Error::msg(format!(
+                    "the URN `{}` must not contain relative components",
+                    value
+                )));
+            }
+            if let Some(c) = component
+                .chars()
+                .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+            {
+                return Err(anyhow::Error::msg(format!(
+                    "the URN `{}` contains the forbidden character `{}`",
+                    value, c
+                )));
+            }
+        }
+        Ok(Urn::from(value))
+    }
     pub fn get_parent(&self) -> Urn {
         let parts: Vec<&str> = self.value.split('/').collect();
         if parts.len() == 1 {
@@ -34,6 +73,13 @@ impl Urn {
         let parent_urn_value: String = parent_parts.join("/");
         Urn::from(parent_urn_value.as_str())
     }
+    /// The number of components, e.g. `a/b/c` gives `3` and the empty value gives `0`.
+    pub fn depth(&self) -> usize {
+        match self.value.is_empty() {
+            true => 0,
+            false => self.value.split('/').count(),
+        }
+    }
     pub fn is_included_in(&self, urns: &[Urn]) -> bool {
         urns.is_empty()
             || urns.iter().any(|other| {
@@ -45,6 +91,12 @@ impl Urn {
                 other.value.starts_with(&self.value)
             })
     }
+    pub fn is_excluded_by(&self, urns: &[Urn]) -> bool {
+        urns.iter().any(|other| {
+            // excluded if same or descendant
+            self.value == other.value || self.value.starts_with(&format!("{}/", other.value))
+        })
+    }
 }
 
 impl fmt::Display for Urn {
@@ -80,7 +132,7 @@ impl<'de> Deserialize<'de> for Urn {
             where
                 E: Error,
             {
-                Ok(Urn::from(v))
+                Urn::parse(v).map_err(E::custom)
             }
         }
         deserializer.deserialize_string(UrnVisitor)
@@ -91,19 +143,22 @@ impl FromStr for Urn {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Urn::from(s))
+        Urn::parse(s)
     }
 }
 
 impl From<&str> for Urn {
     fn from(value: &str) -> Self {
-        let parts: Vec<&str> = value.split('/').map(|_| "..").collect();
-        let path_to_base = match parts.is_empty() {
+        let path_to_base = match value.is_empty() {
             true => ".".to_string(),
-            false => parts.join("/"),
+            false => value
+                .split('/')
+                .map(|_| "..")
+                .collect::<Vec<&str>>()
+                .join("/"),
         };
 
-        let name = value.split('/').last().unwrap_or(value);
+        let name = value.split('/').next_back().unwrap_or(value);
         Urn {
             value: String::from(value),
             name: String::from(name),
@@ -129,6 +184,38 @@ mod tests {
         );
         assert_eq!(Urn::from("PackageA/ModuleB").path_to_base, "../..");
         assert_eq!(Urn::from("PackageA").path_to_base, "..");
+        assert_eq!(Urn::from("").path_to_base, ".");
+        assert_eq!(Urn::from("a").path_to_base, "..");
+        assert_eq!(Urn::from("a/b/c").path_to_base, "../../..");
+        assert_eq!(Urn::from("a/b/c").get_parent().path_to_base, "../..");
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Urn::parse("aws-q1-2022/Architecture/Analytics/Amazon_Athena.v2")
+                .unwrap()
+                .value,
+            "aws-q1-2022/Architecture/Analytics/Amazon_Athena.v2"
+        );
+        assert!(Urn::parse("").is_err());
+        assert!(Urn::parse("/PackageA").is_err());
+        assert!(Urn::parse("PackageA/").is_err());
+        assert!(Urn::parse("PackageA//ModuleB").is_err());
+        assert!(Urn::parse("PackageA/../ModuleB").is_err());
+        assert!(Urn::parse("PackageA/./ModuleB").is_err());
+        assert!(Urn::parse("Package A").is_err());
+        assert!(Urn::parse(" PackageA").is_err());
+        assert!(Urn::parse("PackageA/Module:B").is_err());
+        assert!(Urn::parse("PackageA\\ModuleB").is_err());
+    }
+
+    #[test]
+    fn test_deserialize_malformed() {
+        let error = serde_yaml_ok::from_str::<Urn>("PackageA//ModuleB").unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("the URN `PackageA//ModuleB` must not contain an empty component"));
     }
 
     #[test]
@@ -150,6 +237,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_urn_depth() {
+        assert_eq!(Urn::from("").depth(), 0);
+        assert_eq!(Urn::from("PackageA").depth(), 1);
+        assert_eq!(Urn::from("PackageA/ModuleB").depth(), 2);
+        assert_eq!(Urn::from("PackageA/ModuleB/FamilyC/ItemD").depth(), 4);
+    }
+
     #[test]
     fn test_urn_is_included_in_c4model() {
         assert!(Urn::from("c4model").is_included_in(&[Urn::from("c4model/Element")]));
@@ -181,4 +276,26 @@ mod tests {
         assert!(!Urn::from("PackageB").is_included_in(&[Urn::from("PackageA")]));
         assert!(Urn::from("PackageA").is_included_in(&[Urn::from("PackageA")]));
     }
+
+    #[test]
+    fn test_urn_is_excluded_by() {
+        assert!(!Urn::from("PackageA/ModuleB").is_excluded_by(&[]));
+        assert!(Urn::from("PackageA").is_excluded_by(&[Urn::from("PackageA")]));
+        assert!(Urn::from("PackageA/ModuleB/FamilyC/ItemD")
+            .is_excluded_by(&[Urn::from("PackageA/ModuleB")]));
+        assert!(!Urn::from("PackageA").is_excluded_by(&[Urn::from("PackageA/ModuleB")]));
+        assert!(!Urn::from("PackageAB").is_excluded_by(&[Urn::from("PackageA")]));
+    }
+
+    #[test]
+    fn test_urn_included_and_excluded() {
+        let includes = &[Urn::from("PackageA")];
+        let excludes = &[Urn::from("PackageA/ModuleC")];
+        let is_selected = |urn: &Urn| urn.is_included_in(includes) && !urn.is_excluded_by(excludes);
+        assert!(is_selected(&Urn::from("PackageA")));
+        assert!(is_selected(&Urn::from("PackageA/ModuleB/FamilyC/ItemD")));
+        assert!(!is_selected(&Urn::from("PackageA/ModuleC")));
+        assert!(!is_selected(&Urn::from("PackageA/ModuleC/FamilyC/ItemD")));
+        assert!(!is_selected(&Urn::from("PackageB")));
+    }
 }
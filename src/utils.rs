@@ -1,5 +1,5 @@
-use std::fs::{create_dir_all, read_to_string, remove_dir_all, remove_file};
-use std::path::Path;
+use std::fs::{create_dir_all, read_to_string, remove_dir_all, remove_file, rename, File};
+use std::path::{Component, Path, PathBuf};
 
 use anyhow::Result;
 
@@ -69,3 +69,136 @@ pub fn delete_file_or_directory(path: &Path) -> Result<()> {
     }
     Ok(())
 }
+
+/// Set the Unix permissions of a path, the directories are also searchable where readable, e.g. 0644 becomes 0755.
+///
+/// The permissions are left unchanged on the other platforms.
+pub fn set_mode(path: &Path, mode: u32) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = match path.is_dir() {
+            true => mode | ((mode & 0o444) >> 2),
+            false => mode,
+        };
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode)).map_err(|e| {
+            anyhow::Error::new(e).context(format!("unable to set the mode of {}", path.display()))
+        })?;
+    }
+    #[cfg(not(unix))]
+    let _ = (path, mode);
+    Ok(())
+}
+
+/// Check the nearest existing ancestor of the path is writable, i.e. the path can be written.
+pub fn check_writable_path(path: &Path) -> Result<()> {
+    let existing_path = path
+        .ancestors()
+        .find(|p| p.exists())
+        .unwrap_or_else(|| Path::new("."));
+    let is_readonly = existing_path
+        .metadata()
+        .map(|m| m.permissions().readonly())
+        .unwrap_or_default();
+    if is_readonly {
+        return Err(anyhow::Error::msg(format!(
+            "{} is not writable",
+            existing_path.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Resolve the `.` and `..` components without the file system, i.e. the path may not exist yet.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                _ => normalized.push(component),
+            },
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Check the path doesn't escape the directory, e.g. because of a `..` component.
+pub fn check_contained_path(directory: &Path, path: &Path) -> Result<()> {
+    if !normalize_path(path).starts_with(normalize_path(directory)) {
+        return Err(anyhow::Error::msg(format!(
+            "{} escapes the directory {}",
+            path.display(),
+            directory.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Write the file through a temporary sibling renamed once complete, i.e. never half written.
+pub fn write_file_atomically<F>(file_path: &Path, write: F) -> Result<()>
+where
+    F: FnOnce(&mut File) -> Result<()>,
+{
+    let file_name = file_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| {
+            anyhow::Error::msg(format!("unable to get the name of {}", file_path.display()))
+        })?;
+    let temporary_path =
+        file_path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()));
+    let mut temporary_file = File::create(&temporary_path).map_err(|e| {
+        anyhow::Error::new(e).context(format!("unable to create {}", temporary_path.display()))
+    })?;
+    if let Err(e) = write(&mut temporary_file) {
+        drop(temporary_file);
+        delete_file(&temporary_path)?;
+        return Err(e);
+    }
+    drop(temporary_file);
+    rename(&temporary_path, file_path).map_err(|e| {
+        anyhow::Error::new(e).context(format!("unable to rename {}", temporary_path.display()))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Write;
+
+    use super::*;
+
+    #[test]
+    fn test_write_file_atomically() {
+        let directory = Path::new("target/tests/utils/write_file_atomically");
+        delete_file_or_directory(directory).unwrap();
+        create_directory(directory).unwrap();
+        let file_path = directory.join("file.txt");
+        // an interrupted write doesn't leave anything behind
+        let result = write_file_atomically(&file_path, |f| {
+            f.write_all(b"partial")?;
+            Err(anyhow::Error::msg("interrupted"))
+        });
+        assert!(result.is_err());
+        assert!(!file_path.exists());
+        assert_eq!(directory.read_dir().unwrap().count(), 0);
+        // a complete write is moved to the destination
+        write_file_atomically(&file_path, |f| Ok(f.write_all(b"complete")?)).unwrap();
+        assert_eq!(read_to_string(&file_path).unwrap(), "complete");
+        assert_eq!(directory.read_dir().unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_check_contained_path() {
+        let directory = Path::new("./distribution");
+        assert!(check_contained_path(directory, Path::new("distribution/a/b.puml")).is_ok());
+        assert!(check_contained_path(directory, Path::new("distribution/a/../b.puml")).is_ok());
+        assert!(check_contained_path(directory, Path::new("distribution/a/../../b.puml")).is_err());
+        assert!(check_contained_path(directory, Path::new("distribution-b/c.puml")).is_err());
+        assert!(check_contained_path(directory, Path::new("../distribution/c.puml")).is_err());
+    }
+}